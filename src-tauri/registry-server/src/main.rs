@@ -0,0 +1,312 @@
+// Reference template registry: serves `GET /api/v1/templates` in the format MCP Manager
+// expects, from a directory of JSON template files that is re-read on every request.
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+// The app's filtering and paging, so both sides list templates the same way
+use template_filter::{Listed, TemplateFilter};
+
+const TEMPLATES_PATH: &str = "/api/v1/templates";
+const DEFAULT_ADDR: &str = "127.0.0.1:3988";
+const DEFAULT_PAGE_SIZE: usize = 20;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Template {
+    #[serde(default)]
+    id: String,
+    name: String,
+    #[serde(default)]
+    description: String,
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    env: Option<HashMap<String, String>>,
+    #[serde(default)]
+    require_file_path: bool,
+    // Passed through as declared; the app validates them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    parameters: Vec<serde_json::Value>,
+    #[serde(default)]
+    repo_url: String,
+    #[serde(default)]
+    created_at: String,
+    #[serde(default)]
+    updated_at: String,
+    #[serde(default)]
+    downloads: i32,
+    #[serde(default)]
+    total_usage_time: i32,
+}
+
+// A template file holds one template or a list of them
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TemplateFile {
+    One(Box<Template>),
+    Many(Vec<Template>),
+}
+
+struct Options {
+    addr: String,
+    dir: PathBuf,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        addr: DEFAULT_ADDR.to_string(),
+        dir: PathBuf::from("templates"),
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--addr" => options.addr = value()?,
+            "--dir" => options.dir = PathBuf::from(value()?),
+            "--help" | "-h" => {
+                println!(
+                    "Usage: registry-server [--addr {}] [--dir templates]",
+                    DEFAULT_ADDR
+                );
+                std::process::exit(0);
+            }
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+// Every template in the directory, sorted by name; ids default to the template name
+fn load_templates(dir: &Path) -> Result<Vec<Template>, String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    let mut templates = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let file: TemplateFile = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid template file {}: {}", path.display(), e))?;
+        let mut found = match file {
+            TemplateFile::One(template) => vec![*template],
+            TemplateFile::Many(list) => list,
+        };
+        for template in &mut found {
+            if template.id.is_empty() {
+                template.id = template.name.clone();
+            }
+        }
+        templates.extend(found);
+    }
+    templates.sort_by_key(|t| t.name.to_lowercase());
+    Ok(templates)
+}
+
+fn decode_component(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (decode_component(key), decode_component(value)),
+            None => (decode_component(pair), String::new()),
+        })
+        .collect()
+}
+
+impl Listed for Template {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn require_file_path(&self) -> bool {
+        self.require_file_path
+    }
+}
+
+// Filter and page the templates the way the app's `PaginationParams` describe
+fn select(templates: Vec<Template>, query: &HashMap<String, String>) -> serde_json::Value {
+    let page = query
+        .get("page")
+        .and_then(|p| p.parse::<usize>().ok())
+        .unwrap_or(1);
+    let page_size = query
+        .get("page_size")
+        .and_then(|p| p.parse::<usize>().ok())
+        .filter(|size| *size > 0)
+        .unwrap_or(DEFAULT_PAGE_SIZE);
+    let require_file_path = query
+        .get("require_file_path")
+        .and_then(|v| v.parse::<bool>().ok());
+
+    let (total, list) =
+        TemplateFilter::new(query.get("keyword").map(String::as_str), require_file_path)
+            .select(templates, page, page_size);
+    json!({ "total": total, "list": list })
+}
+
+fn respond(stream: &mut TcpStream, status: &str, body: &serde_json::Value) {
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    if let Err(e) = stream.write_all(response.as_bytes()) {
+        eprintln!("Failed to write response: {}", e);
+    }
+}
+
+fn handle(mut stream: TcpStream, dir: &Path) {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    // Headers are not used; read them so the client sees a complete exchange
+    let mut line = String::new();
+    while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+        line.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    println!("{} {}", method, target);
+
+    if method != "GET" {
+        let body = json!({ "code": 405, "msg": "Method not allowed", "data": null });
+        return respond(&mut stream, "405 Method Not Allowed", &body);
+    }
+    if path.trim_end_matches('/') != TEMPLATES_PATH {
+        let body = json!({ "code": 404, "msg": "Not found", "data": null });
+        return respond(&mut stream, "404 Not Found", &body);
+    }
+
+    match load_templates(dir) {
+        Ok(templates) => {
+            let data = select(templates, &parse_query(query));
+            let body = json!({ "code": 0, "msg": "success", "data": data });
+            respond(&mut stream, "200 OK", &body);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            let body = json!({ "code": 500, "msg": e, "data": null });
+            respond(&mut stream, "500 Internal Server Error", &body);
+        }
+    }
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    if let Err(e) = load_templates(&options.dir) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+
+    let listener = match TcpListener::bind(&options.addr) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to listen on {}: {}", options.addr, e);
+            std::process::exit(1);
+        }
+    };
+    println!(
+        "Serving templates from {} on http://{}{}",
+        options.dir.display(),
+        options.addr,
+        TEMPLATES_PATH
+    );
+
+    for stream in listener.incoming().flatten() {
+        let dir = options.dir.clone();
+        thread::spawn(move || handle(stream, &dir));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(name: &str, description: &str, require_file_path: bool) -> Template {
+        Template {
+            id: name.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            command: "npx".to_string(),
+            args: Vec::new(),
+            env: None,
+            require_file_path,
+            parameters: Vec::new(),
+            repo_url: String::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            downloads: 0,
+            total_usage_time: 0,
+        }
+    }
+
+    #[test]
+    fn filters_and_pages() {
+        let templates = vec![
+            template("fetch", "Fetch web pages", false),
+            template("filesystem", "Read and write files", true),
+            template("git", "Read git repositories", true),
+        ];
+        let query = parse_query("page=2&page_size=1&keyword=READ&require_file_path=true");
+        let data = select(templates, &query);
+
+        assert_eq!(data["total"], 2);
+        assert_eq!(data["list"][0]["name"], "git");
+    }
+
+    #[test]
+    fn decodes_query_components() {
+        let query = parse_query("keyword=web+pages%2Fsearch&flag");
+        assert_eq!(query["keyword"], "web pages/search");
+        assert_eq!(query["flag"], "");
+    }
+}
@@ -0,0 +1,605 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const GRACEFUL_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// An MCP client application the manager knows how to restart
+pub struct ClientApp {
+    pub id: &'static str,
+    pub name: &'static str,
+    // Lowercase executable file names without `.exe`
+    exe_names: &'static [&'static str],
+    // Bundle name used by `open -a` on macOS
+    mac_app: &'static str,
+    // Desktop entry ids tried on Linux, without `.desktop`
+    desktop_ids: &'static [&'static str],
+    // Executables with a matching name that belong to other tools
+    excluded_paths: &'static [&'static str],
+}
+
+pub const CLIENT_APPS: &[ClientApp] = &[
+    ClientApp {
+        id: "claude",
+        name: "Claude Desktop",
+        exe_names: &["claude", "claude-desktop"],
+        mac_app: "Claude",
+        desktop_ids: &["claude-desktop", "claude", "com.anthropic.claude"],
+        // Claude Code ships a CLI binary with the same name
+        excluded_paths: &["/.local/share/claude/", "/.claude/", "/node_modules/"],
+    },
+    ClientApp {
+        id: "vscode",
+        name: "VS Code",
+        exe_names: &["code"],
+        mac_app: "Visual Studio Code",
+        desktop_ids: &["code", "com.visualstudio.code", "visual-studio-code"],
+        excluded_paths: &["/.vscode-server/"],
+    },
+    ClientApp {
+        id: "vscode-insiders",
+        name: "VS Code Insiders",
+        exe_names: &["code-insiders", "code - insiders"],
+        mac_app: "Visual Studio Code - Insiders",
+        desktop_ids: &["code-insiders", "com.visualstudio.code.insiders"],
+        excluded_paths: &["/.vscode-server-insiders/"],
+    },
+    ClientApp {
+        id: "cursor",
+        name: "Cursor",
+        exe_names: &["cursor"],
+        mac_app: "Cursor",
+        desktop_ids: &["cursor", "co.anysphere.cursor"],
+        excluded_paths: &["/.cursor-server/"],
+    },
+    ClientApp {
+        id: "windsurf",
+        name: "Windsurf",
+        exe_names: &["windsurf"],
+        mac_app: "Windsurf",
+        desktop_ids: &["windsurf", "com.codeium.windsurf"],
+        excluded_paths: &["/.windsurf-server/"],
+    },
+    ClientApp {
+        id: "zed",
+        name: "Zed",
+        exe_names: &["zed", "zed-editor", "zeditor"],
+        mac_app: "Zed",
+        desktop_ids: &["dev.zed.Zed", "zed"],
+        excluded_paths: &[],
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessEntry {
+    pub pid: u32,
+    pub ppid: u32,
+    pub exe: PathBuf,
+    pub args: Vec<String>,
+    // Set when the process runs from a mounted AppImage
+    pub appimage: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestartResult {
+    pub client: String,
+    pub stopped_pids: Vec<u32>,
+    pub forced: bool,
+    pub relaunched_with: String,
+}
+
+pub fn find_client_app(id: &str) -> Result<&'static ClientApp, String> {
+    CLIENT_APPS
+        .iter()
+        .find(|app| app.id == id)
+        .ok_or_else(|| format!("Unknown client application: {}", id))
+}
+
+impl ClientApp {
+    fn matches(&self, process: &ProcessEntry) -> bool {
+        let path = process.exe.to_string_lossy().replace('\\', "/");
+        if self.excluded_paths.iter().any(|p| path.contains(p)) {
+            return false;
+        }
+
+        // The main executable of a macOS bundle is not always named after the app
+        if path.contains(&format!("/{}.app/Contents/MacOS/", self.mac_app)) {
+            return true;
+        }
+
+        let file_name = path.rsplit('/').next().unwrap_or_default().to_lowercase();
+        let file_name = file_name.strip_suffix(".exe").unwrap_or(&file_name);
+        self.exe_names.contains(&file_name)
+    }
+}
+
+// Top-level processes of the app: matching processes whose parent is not part of it
+fn app_roots<'a>(app: &ClientApp, processes: &'a [ProcessEntry]) -> Vec<&'a ProcessEntry> {
+    let matching: HashSet<u32> = processes
+        .iter()
+        .filter(|p| app.matches(p))
+        .map(|p| p.pid)
+        .collect();
+    processes
+        .iter()
+        .filter(|p| matching.contains(&p.pid) && !matching.contains(&p.ppid))
+        .collect()
+}
+
+// The roots plus every process started beneath them
+fn process_tree(roots: &[&ProcessEntry], processes: &[ProcessEntry]) -> HashSet<u32> {
+    let mut tree: HashSet<u32> = roots.iter().map(|p| p.pid).collect();
+    loop {
+        let before = tree.len();
+        for process in processes {
+            if tree.contains(&process.ppid) {
+                tree.insert(process.pid);
+            }
+        }
+        if tree.len() == before {
+            return tree;
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn list_processes() -> Result<Vec<ProcessEntry>, String> {
+    use std::fs;
+
+    let entries = fs::read_dir("/proc").map_err(|e| format!("Failed to read /proc: {}", e))?;
+    let mut processes = Vec::new();
+
+    for entry in entries.flatten() {
+        let Ok(pid) = entry.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+        let dir = entry.path();
+
+        // Processes of other users cannot be inspected, skip them
+        let Ok(exe) = fs::read_link(dir.join("exe")) else {
+            continue;
+        };
+        let Ok(stat) = fs::read_to_string(dir.join("stat")) else {
+            continue;
+        };
+        // `comm` may contain spaces and parentheses, so parse after the last ')'
+        let ppid = stat
+            .rfind(')')
+            .and_then(|i| stat[i + 1..].split_whitespace().nth(1))
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(0);
+
+        let args = fs::read(dir.join("cmdline"))
+            .map(|raw| split_nul(&raw))
+            .unwrap_or_default();
+        let appimage = fs::read(dir.join("environ")).ok().and_then(|raw| {
+            split_nul(&raw)
+                .into_iter()
+                .find_map(|var| var.strip_prefix("APPIMAGE=").map(PathBuf::from))
+        });
+
+        // A replaced binary shows up as "/path (deleted)"
+        let exe = PathBuf::from(
+            exe.to_string_lossy()
+                .trim_end_matches(" (deleted)")
+                .to_string(),
+        );
+
+        processes.push(ProcessEntry {
+            pid,
+            ppid,
+            exe,
+            args,
+            appimage,
+        });
+    }
+
+    Ok(processes)
+}
+
+#[cfg(target_os = "linux")]
+fn split_nul(raw: &[u8]) -> Vec<String> {
+    raw.split(|b| *b == 0)
+        .filter(|part| !part.is_empty())
+        .map(|part| String::from_utf8_lossy(part).to_string())
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+pub fn list_processes() -> Result<Vec<ProcessEntry>, String> {
+    // `comm` is the full executable path on macOS
+    let output = Command::new("ps")
+        .args(["-axo", "pid=,ppid=,comm="])
+        .output()
+        .map_err(|e| format!("Failed to run ps command: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let processes = stdout
+        .lines()
+        .filter_map(|line| {
+            let (pid, rest) = line.trim_start().split_once(char::is_whitespace)?;
+            let (ppid, exe) = rest.trim_start().split_once(char::is_whitespace)?;
+            let (pid, ppid) = (pid.parse().ok()?, ppid.parse().ok()?);
+            let exe = PathBuf::from(exe.trim());
+            Some(ProcessEntry {
+                pid,
+                ppid,
+                args: vec![exe.to_string_lossy().to_string()],
+                exe,
+                appimage: None,
+            })
+        })
+        .collect();
+
+    Ok(processes)
+}
+
+#[cfg(windows)]
+pub fn list_processes() -> Result<Vec<ProcessEntry>, String> {
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "Get-CimInstance Win32_Process | Select-Object ProcessId,ParentProcessId,ExecutablePath | ConvertTo-Json -Compress",
+        ])
+        .output()
+        .map_err(|e| format!("Failed to list processes: {}", e))?;
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse process list: {}", e))?;
+
+    let processes = json
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|p| {
+            let exe = PathBuf::from(p.get("ExecutablePath")?.as_str()?);
+            Some(ProcessEntry {
+                pid: p.get("ProcessId")?.as_u64()? as u32,
+                ppid: p.get("ParentProcessId")?.as_u64()? as u32,
+                args: vec![exe.to_string_lossy().to_string()],
+                exe,
+                appimage: None,
+            })
+        })
+        .collect();
+
+    Ok(processes)
+}
+
+fn any_alive(pids: &HashSet<u32>) -> Result<bool, String> {
+    Ok(list_processes()?.iter().any(|p| pids.contains(&p.pid)))
+}
+
+fn wait_for_exit(pids: &HashSet<u32>, timeout: Duration) -> Result<bool, String> {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if !any_alive(pids)? {
+            return Ok(true);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    Ok(!any_alive(pids)?)
+}
+
+#[cfg(unix)]
+fn signal(pids: &[u32], signal: &str) {
+    if pids.is_empty() {
+        return;
+    }
+    let _ = Command::new("kill")
+        .arg(format!("-{}", signal))
+        .args(pids.iter().map(|pid| pid.to_string()))
+        .stderr(Stdio::null())
+        .status();
+}
+
+#[cfg(unix)]
+fn request_exit(app: &ClientApp, roots: &[u32]) {
+    // Let the app run its own shutdown path first
+    #[cfg(target_os = "macos")]
+    {
+        let _ = Command::new("osascript")
+            .args(["-e", &format!("quit app \"{}\"", app.mac_app)])
+            .status();
+    }
+    #[cfg(not(target_os = "macos"))]
+    let _ = app;
+
+    signal(roots, "TERM");
+}
+
+#[cfg(unix)]
+fn force_exit(tree: &[u32]) {
+    signal(tree, "KILL");
+}
+
+#[cfg(windows)]
+fn request_exit(_app: &ClientApp, roots: &[u32]) {
+    for pid in roots {
+        let _ = Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
+
+#[cfg(windows)]
+fn force_exit(tree: &[u32]) {
+    for pid in tree {
+        let _ = Command::new("taskkill")
+            .args(["/F", "/PID", &pid.to_string(), "/T"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
+
+// Stop every process of the app, escalating after the timeout
+fn terminate(
+    app: &ClientApp,
+    roots: &[&ProcessEntry],
+    processes: &[ProcessEntry],
+) -> Result<bool, String> {
+    let tree = process_tree(roots, processes);
+    let root_pids: Vec<u32> = roots.iter().map(|p| p.pid).collect();
+
+    request_exit(app, &root_pids);
+    if wait_for_exit(&tree, GRACEFUL_TIMEOUT)? {
+        return Ok(false);
+    }
+
+    println!("{} did not exit in time, killing it", app.name);
+    force_exit(&tree.iter().copied().collect::<Vec<_>>());
+    if !wait_for_exit(&tree, GRACEFUL_TIMEOUT)? {
+        return Err(format!("Failed to stop {}", app.name));
+    }
+    Ok(true)
+}
+
+fn spawn_detached(command: &mut Command) -> Result<(), String> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to launch: {}", e))?;
+    // Reap the launcher so it does not linger as a zombie
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+// Arguments of the root process worth passing on, e.g. opened folders
+#[cfg(target_os = "linux")]
+fn relaunch_args(root: &ProcessEntry) -> Vec<String> {
+    root.args
+        .iter()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--type=") && !arg.starts_with("--inspect"))
+        .cloned()
+        .collect()
+}
+
+// The executable to start again: the AppImage itself, as its mount directory disappears
+// with the process, or the binary that ran unless an update replaced it
+#[cfg(target_os = "linux")]
+fn relaunch_program(root: &ProcessEntry) -> Option<&Path> {
+    match &root.appimage {
+        Some(appimage) => Some(appimage),
+        None => Some(root.exe.as_path()).filter(|exe| exe.exists()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn find_desktop_entry(app: &ClientApp) -> Option<(String, PathBuf)> {
+    let home = std::env::var("HOME").unwrap_or_default();
+    let data_home =
+        std::env::var("XDG_DATA_HOME").unwrap_or_else(|_| format!("{}/.local/share", home));
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string());
+
+    let mut dirs: Vec<PathBuf> = vec![PathBuf::from(&data_home)];
+    dirs.extend(data_dirs.split(':').map(PathBuf::from));
+    dirs.push(PathBuf::from(format!(
+        "{}/flatpak/exports/share",
+        data_home
+    )));
+    dirs.push(PathBuf::from("/var/lib/flatpak/exports/share"));
+    dirs.push(PathBuf::from("/var/lib/snapd/desktop"));
+
+    app.desktop_ids.iter().find_map(|id| {
+        dirs.iter()
+            .map(|dir| dir.join("applications").join(format!("{}.desktop", id)))
+            .find(|path| path.exists())
+            .map(|path| (id.to_string(), path))
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn launch(app: &ClientApp, root: &ProcessEntry) -> Result<String, String> {
+    if let Some(program) = relaunch_program(root) {
+        spawn_detached(Command::new(program).args(relaunch_args(root)))?;
+        return Ok(program.to_string_lossy().to_string());
+    }
+
+    // The binary was replaced (e.g. by an update), go through the desktop entry
+    if let Some((id, path)) = find_desktop_entry(app) {
+        if spawn_detached(Command::new("gtk-launch").arg(&id)).is_ok()
+            || spawn_detached(Command::new("gio").arg("launch").arg(&path)).is_ok()
+        {
+            return Ok(path.to_string_lossy().to_string());
+        }
+    }
+
+    Err(format!(
+        "Could not find how to relaunch {} ({} no longer exists)",
+        app.name,
+        root.exe.display()
+    ))
+}
+
+#[cfg(target_os = "macos")]
+fn launch(app: &ClientApp, root: &ProcessEntry) -> Result<String, String> {
+    // Reopen the bundle that was running, not just any app with that name
+    let exe = root.exe.to_string_lossy();
+    let target = match exe.find(".app/") {
+        Some(end) => exe[..end + 4].to_string(),
+        None => app.mac_app.to_string(),
+    };
+
+    let status = Command::new("open")
+        .arg("-a")
+        .arg(&target)
+        .status()
+        .map_err(|e| format!("Failed to start {}: {}", app.name, e))?;
+    if !status.success() {
+        return Err(format!("Failed to start {}", app.name));
+    }
+    Ok(target)
+}
+
+#[cfg(windows)]
+fn launch(app: &ClientApp, root: &ProcessEntry) -> Result<String, String> {
+    spawn_detached(&mut Command::new(&root.exe))
+        .map_err(|e| format!("Failed to start {}: {}", app.name, e))?;
+    Ok(root.exe.to_string_lossy().to_string())
+}
+
+pub fn restart(app: &ClientApp) -> Result<RestartResult, String> {
+    let processes = list_processes()?;
+    let roots = app_roots(app, &processes);
+    let Some(main) = roots.first().map(|p| (*p).clone()) else {
+        return Err(format!("{} is not running", app.name));
+    };
+
+    println!(
+        "Restarting {} (pids {:?})",
+        app.name,
+        roots.iter().map(|p| p.pid).collect::<Vec<_>>()
+    );
+    let stopped_pids = roots.iter().map(|p| p.pid).collect();
+    let forced = terminate(app, &roots, &processes)?;
+    let relaunched_with = launch(app, &main)?;
+
+    Ok(RestartResult {
+        client: app.id.to_string(),
+        stopped_pids,
+        forced,
+        relaunched_with,
+    })
+}
+
+#[tauri::command]
+pub fn restart_client_app(client: String) -> Result<RestartResult, String> {
+    restart(find_client_app(&client)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, ppid: u32, exe: &str) -> ProcessEntry {
+        ProcessEntry {
+            pid,
+            ppid,
+            exe: PathBuf::from(exe),
+            args: vec![exe.to_string()],
+            appimage: None,
+        }
+    }
+
+    fn app(id: &str) -> &'static ClientApp {
+        find_client_app(id).unwrap()
+    }
+
+    #[test]
+    fn matches_client_executables() {
+        let appimage = ProcessEntry {
+            appimage: Some(PathBuf::from("/home/a/Apps/Cursor-1.0.AppImage")),
+            ..process(1, 0, "/tmp/.mount_CursorA1b2/cursor")
+        };
+        assert!(app("cursor").matches(&appimage));
+        assert!(app("cursor").matches(&process(
+            1,
+            0,
+            "/Applications/Cursor.app/Contents/MacOS/Cursor"
+        )));
+
+        let insiders = process(1, 0, "/usr/share/code-insiders/code-insiders");
+        assert!(app("vscode-insiders").matches(&insiders));
+        assert!(!app("vscode").matches(&insiders));
+        let windows = process(
+            1,
+            0,
+            "C:\\Program Files\\Microsoft VS Code Insiders\\Code - Insiders.exe",
+        );
+        assert!(app("vscode-insiders").matches(&windows));
+        assert!(app("vscode").matches(&process(1, 0, "/usr/share/code/code")));
+    }
+
+    #[test]
+    fn excluded_paths_are_not_the_app() {
+        for exe in [
+            "/home/a/.local/share/claude/versions/1.0.0/claude",
+            "/home/a/.claude/local/claude",
+            "/usr/lib/node_modules/@anthropic-ai/claude-code/claude",
+        ] {
+            assert!(!app("claude").matches(&process(1, 0, exe)), "{}", exe);
+        }
+        assert!(app("claude").matches(&process(1, 0, "/usr/bin/claude-desktop")));
+        assert!(!app("cursor").matches(&process(1, 0, "/home/a/.cursor-server/bin/cursor")));
+        assert!(!app("vscode").matches(&process(1, 0, "/home/a/.vscode-server/bin/code")));
+    }
+
+    #[test]
+    fn collects_the_tree_below_the_roots() {
+        let processes = vec![
+            process(1, 0, "/sbin/init"),
+            process(10, 1, "/usr/share/code/code"),
+            // Renderer and helper processes of the same app
+            process(11, 10, "/usr/share/code/code"),
+            process(12, 11, "/usr/bin/node"),
+            process(13, 12, "/usr/bin/python3"),
+            // A second window started on its own
+            process(20, 1, "/usr/share/code/code"),
+            process(30, 1, "/usr/bin/node"),
+        ];
+        let roots = app_roots(app("vscode"), &processes);
+        let pids: Vec<u32> = roots.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, [10, 20]);
+
+        let tree = process_tree(&roots, &processes);
+        assert_eq!(tree, HashSet::from([10, 11, 12, 13, 20]));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn relaunches_with_the_opened_folders() {
+        let mut root = process(10, 1, "/definitely/missing/code");
+        root.args = [
+            "/definitely/missing/code",
+            "--type=renderer",
+            "--inspect-brk=9229",
+            "/home/a/project",
+            "--new-window",
+        ]
+        .map(String::from)
+        .into();
+        assert_eq!(relaunch_args(&root), ["/home/a/project", "--new-window"]);
+
+        // A replaced binary is left to the desktop entry
+        assert_eq!(relaunch_program(&root), None);
+        root.appimage = Some(PathBuf::from("/home/a/Apps/Code.AppImage"));
+        assert_eq!(
+            relaunch_program(&root),
+            Some(Path::new("/home/a/Apps/Code.AppImage"))
+        );
+        let running = process(1, 0, "/bin/sh");
+        assert_eq!(relaunch_program(&running), Some(Path::new("/bin/sh")));
+    }
+}
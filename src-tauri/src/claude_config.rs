@@ -1,182 +1,394 @@
+use crate::config_history::{self, HistorySource};
+use crate::config_io::{self, ConfigSnapshot, FileVersion};
+use crate::config_merge::ConflictSet;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::{Map as JsonMap, Value as JsonValue};
 use std::env;
-use std::fs;
 use std::path::PathBuf;
-use serde_json::Value as JsonValue;
+use tauri::AppHandle;
 
-#[derive(Debug, Serialize, Deserialize)]
+// A single server entry, kept as written so unknown keys survive edits
+pub type ServerConfig = JsonMap<String, JsonValue>;
+
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct ClaudeConfig {
-    #[serde(rename = "mcpServers")]
-    pub mcp_servers: HashMap<String, HashMap<String, JsonValue>>,
-    #[serde(rename = "globalShortcut")]
-    pub global_shortcut: String,
+    #[serde(rename = "mcpServers", default)]
+    pub mcp_servers: IndexMap<String, ServerConfig>,
+    #[serde(
+        rename = "globalShortcut",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub global_shortcut: Option<String>,
+    // Every other top-level setting Claude stores
+    #[serde(flatten)]
+    pub extra: JsonMap<String, JsonValue>,
 }
 
-#[tauri::command]
-pub fn get_claude_config() -> Result<ClaudeConfig, String> {
-    let config_path = if cfg!(target_os = "macos") {
+pub fn config_path() -> Result<PathBuf, String> {
+    if cfg!(target_os = "macos") {
         let home = env::var("HOME").map_err(|_| "Could not find home directory".to_string())?;
-        PathBuf::from(home)
+        Ok(PathBuf::from(home)
             .join("Library")
             .join("Application Support")
             .join("Claude")
-            .join("claude_desktop_config.json")
+            .join("claude_desktop_config.json"))
     } else if cfg!(target_os = "windows") {
         let app_data =
             env::var("APPDATA").map_err(|_| "Could not find APPDATA directory".to_string())?;
-        PathBuf::from(app_data)
+        Ok(PathBuf::from(app_data)
             .join("Claude")
-            .join("claude_desktop_config.json")
+            .join("claude_desktop_config.json"))
     } else {
         let home = env::var("HOME").map_err(|_| "Could not find home directory".to_string())?;
-        PathBuf::from(home)
+        Ok(PathBuf::from(home)
             .join(".config")
             .join("Claude")
-            .join("claude_desktop_config.json")
-    };
+            .join("claude_desktop_config.json"))
+    }
+}
+
+pub fn parse_claude_config(config_str: &str) -> Result<ClaudeConfig, String> {
+    serde_json::from_str(config_str).map_err(|e| format!("Failed to parse config: {}", e))
+}
+
+// Render the config over the text it was read from, keeping key order and formatting
+pub fn render_claude_config(
+    original: Option<&str>,
+    config: &ClaudeConfig,
+) -> Result<String, String> {
+    crate::json_edit::render_over(original, config)
+}
+
+#[tauri::command]
+pub fn get_claude_config() -> Result<ClaudeConfig, String> {
+    load_claude_config().map(|(config, _)| config)
+}
 
-    let config_str = fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read config file: {}", e))?;
+// Read the config together with the version it was read at
+pub fn load_claude_config() -> Result<(ClaudeConfig, ConfigSnapshot), String> {
+    let snapshot = config_io::read_config(&config_path()?)?;
+    let config_str = snapshot
+        .content
+        .as_deref()
+        .ok_or_else(|| "Failed to read config file: file not found".to_string())?;
+
+    // Keep the version that was read, so a later save can merge against it
+    if let Err(e) = config_history::record(&snapshot.path, config_str, HistorySource::External) {
+        eprintln!("Failed to record config history: {}", e);
+    }
 
-    serde_json::from_str(&config_str).map_err(|e| format!("Failed to parse config: {}", e))
+    Ok((parse_claude_config(config_str)?, snapshot))
+}
+
+// Write the config back, failing if it changed on disk since `snapshot` was taken
+pub fn write_claude_config(
+    config: &ClaudeConfig,
+    snapshot: &ConfigSnapshot,
+) -> Result<FileVersion, String> {
+    let config_str = render_claude_config(snapshot.content.as_deref(), config)?;
+    config_io::write_config(&snapshot.path, &config_str, &snapshot.version)
 }
 
 #[tauri::command]
 pub fn get_config_path() -> String {
-    if cfg!(target_os = "macos") {
-        let home = env::var("HOME").unwrap_or_default();
-        format!(
-            "{}/Library/Application Support/Claude/claude_desktop_config.json",
-            home
-        )
-    } else if cfg!(target_os = "windows") {
-        let app_data = env::var("APPDATA").unwrap_or_default();
-        format!("{}/Claude/claude_desktop_config.json", app_data)
-    } else {
-        let home = env::var("HOME").unwrap_or_default();
-        format!("{}/.config/Claude/claude_desktop_config.json", home)
+    config_path()
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn get_claude_config_version() -> Result<FileVersion, String> {
+    Ok(config_io::read_config(&config_path()?)?.version)
+}
+
+#[derive(Debug, Serialize)]
+pub struct SaveOutcome {
+    // Version on disk after saving, or the current one when nothing was written
+    pub version: FileVersion,
+    pub saved: bool,
+    // Set when changes made on disk since the base version were merged in
+    pub merged: bool,
+    pub conflicts: ConflictSet,
+}
+
+fn to_document(config: &ClaudeConfig) -> Result<JsonMap<String, JsonValue>, String> {
+    match serde_json::to_value(config).map_err(|e| format!("Failed to serialize config: {}", e))? {
+        JsonValue::Object(map) => Ok(map),
+        _ => Err("Config must be a JSON object".to_string()),
     }
 }
 
+// Save an edit of the config read at `base_version`; if the file changed since, the edit is
+// merged with it per server entry and nothing is written when both changed the same entry
 #[tauri::command]
-pub fn save_claude_config(config: ClaudeConfig) -> Result<(), String> {
-    let config_path = if cfg!(target_os = "macos") {
-        let home = env::var("HOME").map_err(|_| "Could not find home directory".to_string())?;
-        PathBuf::from(home)
-            .join("Library")
-            .join("Application Support")
-            .join("Claude")
-            .join("claude_desktop_config.json")
-    } else if cfg!(target_os = "windows") {
-        let app_data =
-            env::var("APPDATA").map_err(|_| "Could not find APPDATA directory".to_string())?;
-        PathBuf::from(app_data)
-            .join("Claude")
-            .join("claude_desktop_config.json")
-    } else {
-        let home = env::var("HOME").map_err(|_| "Could not find home directory".to_string())?;
-        PathBuf::from(home)
-            .join(".config")
-            .join("Claude")
-            .join("claude_desktop_config.json")
+pub async fn save_claude_config(
+    app: AppHandle,
+    config: ClaudeConfig,
+    base_version: Option<FileVersion>,
+) -> Result<SaveOutcome, String> {
+    crate::transaction::run(&app, "Save Claude Desktop config", None, async {
+        save_config(config, base_version)
+    })
+    .await
+}
+
+fn save_config(
+    config: ClaudeConfig,
+    base_version: Option<FileVersion>,
+) -> Result<SaveOutcome, String> {
+    let snapshot = config_io::read_config(&config_path()?)?;
+
+    let base = match base_version {
+        Some(base) if base != snapshot.version => base,
+        _ => {
+            return Ok(SaveOutcome {
+                version: write_claude_config(&config, &snapshot)?,
+                saved: true,
+                merged: false,
+                conflicts: ConflictSet::default(),
+            })
+        }
     };
 
-    let config_str = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    // 调用方读取之后文件被其他程序修改过，基于读取时的版本合并
+    let theirs = match snapshot.content.as_deref() {
+        Some(content) => parse_claude_config(content)?,
+        None => ClaudeConfig::default(),
+    };
+    // 读取时文件不存在，基准就是空配置
+    let base_content = match base.hash.as_deref() {
+        Some(hash) => config_history::find_by_hash(&snapshot.path, hash),
+        None => Some("{}".to_string()),
+    };
+    let Some(base_content) = base_content else {
+        // 历史被关闭或基准版本已被清理，无法合并，只能逐项报告两边的差异
+        let conflicts = crate::config_merge::compare_documents(
+            &to_document(&config)?,
+            &to_document(&theirs)?,
+            "mcpServers",
+        );
+        // 两边完全相同时无需写入，也算保存成功
+        return Ok(SaveOutcome {
+            version: snapshot.version,
+            saved: conflicts.is_empty(),
+            merged: false,
+            conflicts,
+        });
+    };
+    let base = parse_claude_config(&base_content)?;
 
-    fs::write(&config_path, config_str)
-        .map_err(|e| format!("Failed to write config file: {}", e))?;
+    let (merged, conflicts) = crate::config_merge::merge_documents(
+        &to_document(&base)?,
+        &to_document(&config)?,
+        &to_document(&theirs)?,
+        "mcpServers",
+    );
+    if !conflicts.is_empty() {
+        return Ok(SaveOutcome {
+            version: snapshot.version,
+            saved: false,
+            merged: false,
+            conflicts,
+        });
+    }
 
-    Ok(())
+    let merged: ClaudeConfig = serde_json::from_value(JsonValue::Object(merged))
+        .map_err(|e| format!("Failed to merge config: {}", e))?;
+    Ok(SaveOutcome {
+        version: write_claude_config(&merged, &snapshot)?,
+        saved: true,
+        merged: true,
+        conflicts,
+    })
 }
 
-// 备份配置文件
+// 备份配置文件：在修改之前把当前内容记入历史
 pub fn backup_config() -> Result<(), String> {
-    let config_path = if cfg!(target_os = "macos") {
-        let home = env::var("HOME").map_err(|_| "Could not find home directory".to_string())?;
-        PathBuf::from(home)
-            .join("Library")
-            .join("Application Support")
-            .join("Claude")
-            .join("claude_desktop_config.json")
-    } else if cfg!(target_os = "windows") {
-        let app_data =
-            env::var("APPDATA").map_err(|_| "Could not find APPDATA directory".to_string())?;
-        PathBuf::from(app_data)
-            .join("Claude")
-            .join("claude_desktop_config.json")
-    } else {
-        let home = env::var("HOME").map_err(|_| "Could not find home directory".to_string())?;
-        PathBuf::from(home)
-            .join(".config")
-            .join("Claude")
-            .join("claude_desktop_config.json")
-    };
+    let snapshot = config_io::read_config(&config_path()?)?;
 
-    let backup_path = config_path.with_extension("json.backup");
-
-    // 如果原配置文件存在，就创建备份
-    if config_path.exists() {
-        fs::copy(&config_path, &backup_path)
-            .map_err(|e| format!("Failed to create backup: {}", e))?;
+    // 与历史中最新版本不同，说明是外部修改
+    if let Some(content) = &snapshot.content {
+        config_history::record(&snapshot.path, content, HistorySource::External)?;
     }
 
     Ok(())
 }
 
-// 添加这个函数来恢复备份
+// 恢复到当前内容之前的上一个版本
 #[tauri::command]
-pub fn restore_config_backup() -> Result<(), String> {
-    let config_path = if cfg!(target_os = "macos") {
-        let home = env::var("HOME").map_err(|_| "Could not find home directory".to_string())?;
-        PathBuf::from(home)
-            .join("Library")
-            .join("Application Support")
-            .join("Claude")
-            .join("claude_desktop_config.json")
-    } else if cfg!(target_os = "windows") {
-        let app_data =
-            env::var("APPDATA").map_err(|_| "Could not find APPDATA directory".to_string())?;
-        PathBuf::from(app_data)
-            .join("Claude")
-            .join("claude_desktop_config.json")
-    } else {
-        let home = env::var("HOME").map_err(|_| "Could not find home directory".to_string())?;
-        PathBuf::from(home)
-            .join(".config")
-            .join("Claude")
-            .join("claude_desktop_config.json")
-    };
+pub async fn restore_config_backup(app: AppHandle) -> Result<(), String> {
+    crate::transaction::run(&app, "Restore Claude Desktop config", None, async {
+        restore_backup()
+    })
+    .await
+}
+
+fn restore_backup() -> Result<(), String> {
+    let config_path = config_path()?;
+    let current = config_io::read_config(&config_path)?.version.hash;
 
-    let backup_path = config_path.with_extension("json.backup");
+    let previous = config_history::list(&config_path)
+        .into_iter()
+        .rev()
+        .find(|entry| Some(&entry.hash) != current.as_ref());
 
-    if backup_path.exists() {
-        fs::copy(&backup_path, &config_path)
-            .map_err(|e| format!("Failed to restore backup: {}", e))?;
-        fs::remove_file(&backup_path)
-            .map_err(|e| format!("Failed to remove backup file: {}", e))?;
+    if let Some(previous) = previous {
+        config_history::restore_version(&config_path, &previous.id)?;
     }
 
     Ok(())
 }
 
 #[tauri::command]
-pub async fn update_global_shortcut_command(shortcut: String) -> Result<(), String> {
-    // 在修改之前创建备份
-    backup_config()?;
-
-    let mut config = get_claude_config()?;
-    
-    // 更新快捷键
-    config.global_shortcut = shortcut;
-    
-    // 保存更新后的配置
-    if let Err(e) = save_claude_config(config) {
-        // 如果保存失败，尝试恢复备份
-        restore_config_backup()?;
-        return Err(e);
+pub async fn update_global_shortcut_command(
+    app: AppHandle,
+    shortcut: String,
+) -> Result<(), String> {
+    crate::transaction::run(&app, "Change global shortcut", None, async {
+        // 在修改之前创建备份
+        backup_config()?;
+
+        let (mut config, snapshot) = load_claude_config()?;
+
+        // 更新快捷键
+        config.global_shortcut = Some(shortcut);
+
+        // 保存更新后的配置，写入是原子的，失败时原文件保持不变
+        write_claude_config(&config, &snapshot)?;
+
+        Ok(())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: &[(&str, &str)] = &[
+        (
+            "basic",
+            include_str!("../tests/fixtures/claude_desktop/basic.json"),
+        ),
+        (
+            "no_shortcut_extra_keys",
+            include_str!("../tests/fixtures/claude_desktop/no_shortcut_extra_keys.json"),
+        ),
+        (
+            "four_space_no_newline",
+            include_str!("../tests/fixtures/claude_desktop/four_space_no_newline.json"),
+        ),
+        (
+            "tabs_crlf",
+            include_str!("../tests/fixtures/claude_desktop/tabs_crlf.json"),
+        ),
+        (
+            "inline_arrays_escapes",
+            include_str!("../tests/fixtures/claude_desktop/inline_arrays_escapes.json"),
+        ),
+        (
+            "tabs_inline_arrays",
+            include_str!("../tests/fixtures/claude_desktop/tabs_inline_arrays.json"),
+        ),
+    ];
+
+    #[test]
+    fn round_trip_is_byte_identical() {
+        for (name, sample) in SAMPLES {
+            let config = parse_claude_config(sample).unwrap();
+            let rendered = render_claude_config(Some(sample), &config).unwrap();
+            assert_eq!(&rendered, sample, "sample {} changed on round trip", name);
+        }
     }
 
-    Ok(())
+    #[test]
+    fn missing_global_shortcut_is_tolerated() {
+        let sample = include_str!("../tests/fixtures/claude_desktop/no_shortcut_extra_keys.json");
+        let config = parse_claude_config(sample).unwrap();
+        assert_eq!(config.global_shortcut, None);
+        assert!(config.extra.contains_key("preferences"));
+
+        let config = parse_claude_config("{}").unwrap();
+        assert!(config.mcp_servers.is_empty());
+    }
+
+    #[test]
+    fn edits_keep_unknown_keys_and_order() {
+        let sample = include_str!("../tests/fixtures/claude_desktop/four_space_no_newline.json");
+        let mut config = parse_claude_config(sample).unwrap();
+
+        let weather = config.mcp_servers.get_mut("weather").unwrap();
+        weather.insert("command".to_string(), JsonValue::from("uvx"));
+        let mut added = ServerConfig::new();
+        added.insert("command".to_string(), JsonValue::from("npx"));
+        config.mcp_servers.insert("added".to_string(), added);
+
+        let rendered = render_claude_config(Some(sample), &config).unwrap();
+        let expected = sample
+            .replace("\"command\": \"uv\"", "\"command\": \"uvx\"")
+            .replace(
+                "        }\n    },\n    \"globalShortcut\"",
+                "        },\n        \"added\": {\n            \"command\": \"npx\"\n        }\n    },\n    \"globalShortcut\"",
+            );
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn removed_servers_disappear() {
+        let sample = include_str!("../tests/fixtures/claude_desktop/basic.json");
+        let mut config = parse_claude_config(sample).unwrap();
+        config.mcp_servers.shift_remove("filesystem");
+
+        let rendered = render_claude_config(Some(sample), &config).unwrap();
+        let reparsed = parse_claude_config(&rendered).unwrap();
+        assert_eq!(
+            reparsed.mcp_servers.keys().collect::<Vec<_>>(),
+            vec!["github"]
+        );
+        assert_eq!(reparsed.global_shortcut.as_deref(), Some("Alt+Space"));
+    }
+
+    #[test]
+    fn editing_one_server_keeps_the_rest_byte_identical() {
+        let sample = include_str!("../tests/fixtures/claude_desktop/inline_arrays_escapes.json");
+        let mut config = parse_claude_config(sample).unwrap();
+        config.mcp_servers["fetch"].insert("command".to_string(), JsonValue::from("uv"));
+
+        // Inline arrays, `\u` escapes and the trailing newline all survive
+        let rendered = render_claude_config(Some(sample), &config).unwrap();
+        let expected = sample.replacen("\"command\": \"uvx\"", "\"command\": \"uv\"", 1);
+        assert_eq!(rendered, expected);
+
+        // A changed array is written in the file's indentation
+        config.mcp_servers["filesystem"].insert(
+            "args".to_string(),
+            serde_json::json!(["-y", "@modelcontextprotocol/server-filesystem"]),
+        );
+        let rendered = render_claude_config(Some(sample), &config).unwrap();
+        let expected = expected.replace(
+            "[\"-y\", \"@modelcontextprotocol/server-filesystem\", \"/Users/\\u00e9lise/Desktop\"]",
+            "[\n                \"-y\",\n                \"@modelcontextprotocol/server-filesystem\"\n            ]",
+        );
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn removing_a_server_keeps_tabs_and_trailing_newlines() {
+        let sample = include_str!("../tests/fixtures/claude_desktop/tabs_inline_arrays.json");
+        let mut config = parse_claude_config(sample).unwrap();
+        config.mcp_servers.shift_remove("time");
+        let mut added = ServerConfig::new();
+        added.insert("command".to_string(), JsonValue::from("npx"));
+        config.mcp_servers.insert("added".to_string(), added);
+
+        let rendered = render_claude_config(Some(sample), &config).unwrap();
+        let expected = sample.replace(
+            "\t\t\"time\": {\n\t\t\t\"command\": \"uvx\",\n\t\t\t\"args\": [\"mcp-server-time\"]\n\t\t}",
+            "\t\t\"added\": {\n\t\t\t\"command\": \"npx\"\n\t\t}",
+        );
+        assert_eq!(rendered, expected);
+        assert!(rendered.ends_with("}\n\n"));
+    }
 }
@@ -0,0 +1,325 @@
+use crate::mcp_client::RpcError;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+pub const SAMPLING_METHOD: &str = "sampling/createMessage";
+pub const ELICITATION_METHOD: &str = "elicitation/create";
+
+const PROMPT_EVENT: &str = "mcp_client_request";
+const PROMPT_TIMEOUT: Duration = Duration::from_secs(300);
+
+// MCP uses -1 for requests the user rejected
+const USER_REJECTED: i64 = -1;
+const INTERNAL_ERROR: i64 = -32603;
+
+// How the manager answers sampling and elicitation requests from a server
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum CallbackMode {
+    #[default]
+    Reject,
+    Prompt,
+    Scripted {
+        file: String,
+    },
+}
+
+// Canned replies, matched in order against the request text
+#[derive(Debug, Deserialize, Default)]
+struct ScriptFile {
+    #[serde(default)]
+    sampling: Vec<ScriptRule>,
+    #[serde(default)]
+    elicitation: Vec<ScriptRule>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScriptRule {
+    #[serde(rename = "match")]
+    pattern: Option<String>,
+    result: Option<JsonValue>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct PromptRequest {
+    request_id: u64,
+    server: String,
+    method: String,
+    params: JsonValue,
+}
+
+type PromptSender = mpsc::Sender<Result<JsonValue, String>>;
+
+static NEXT_PROMPT_ID: AtomicU64 = AtomicU64::new(1);
+
+// Prompts waiting for the user to answer in the UI
+static PENDING_PROMPTS: Lazy<Mutex<HashMap<u64, PromptSender>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn handle(
+    app: &AppHandle,
+    server: &str,
+    method: &str,
+    params: &JsonValue,
+) -> Result<JsonValue, RpcError> {
+    let mode = crate::store::get_callback_mode(app, server)
+        .map_err(|e| RpcError::new(INTERNAL_ERROR, e))?;
+
+    match mode {
+        CallbackMode::Reject => reject(method),
+        CallbackMode::Prompt => prompt(app, server, method, params),
+        CallbackMode::Scripted { file } => scripted(&file, method, params),
+    }
+}
+
+fn reject(method: &str) -> Result<JsonValue, RpcError> {
+    if method == ELICITATION_METHOD {
+        return Ok(json!({ "action": "decline" }));
+    }
+    Err(RpcError::new(
+        USER_REJECTED,
+        "Request rejected by MCP Manager",
+    ))
+}
+
+fn prompt(
+    app: &AppHandle,
+    server: &str,
+    method: &str,
+    params: &JsonValue,
+) -> Result<JsonValue, RpcError> {
+    let request_id = NEXT_PROMPT_ID.fetch_add(1, Ordering::SeqCst);
+    let (tx, rx) = mpsc::channel();
+    PENDING_PROMPTS
+        .lock()
+        .map_err(|e| RpcError::new(INTERNAL_ERROR, format!("Failed to lock prompts: {}", e)))?
+        .insert(request_id, tx);
+
+    let request = PromptRequest {
+        request_id,
+        server: server.to_string(),
+        method: method.to_string(),
+        params: params.clone(),
+    };
+    if let Err(e) = app.emit(PROMPT_EVENT, request) {
+        remove_prompt(request_id);
+        return Err(RpcError::new(
+            INTERNAL_ERROR,
+            format!("Failed to ask the user: {}", e),
+        ));
+    }
+
+    let answer = rx.recv_timeout(PROMPT_TIMEOUT);
+    remove_prompt(request_id);
+    match answer {
+        Ok(Ok(result)) => Ok(result),
+        Ok(Err(message)) => Err(RpcError::new(USER_REJECTED, message)),
+        // Nobody answered; elicitation has a dedicated outcome for that
+        Err(_) if method == ELICITATION_METHOD => Ok(json!({ "action": "cancel" })),
+        Err(_) => Err(RpcError::new(
+            USER_REJECTED,
+            "Timed out waiting for the user",
+        )),
+    }
+}
+
+fn remove_prompt(request_id: u64) {
+    if let Ok(mut prompts) = PENDING_PROMPTS.lock() {
+        prompts.remove(&request_id);
+    }
+}
+
+fn scripted(file: &str, method: &str, params: &JsonValue) -> Result<JsonValue, RpcError> {
+    let content = fs::read_to_string(file).map_err(|e| {
+        RpcError::new(
+            INTERNAL_ERROR,
+            format!("Failed to read script file {}: {}", file, e),
+        )
+    })?;
+    let script: ScriptFile = serde_json::from_str(&content).map_err(|e| {
+        RpcError::new(
+            INTERNAL_ERROR,
+            format!("Failed to parse script file {}: {}", file, e),
+        )
+    })?;
+
+    let rules = if method == ELICITATION_METHOD {
+        &script.elicitation
+    } else {
+        &script.sampling
+    };
+    let text = request_text(method, params);
+
+    let rule = rules
+        .iter()
+        .find(|rule| {
+            rule.pattern
+                .as_ref()
+                .is_none_or(|pattern| text.contains(pattern.as_str()))
+        })
+        .ok_or_else(|| {
+            RpcError::new(
+                USER_REJECTED,
+                format!("No scripted response matches this {} request", method),
+            )
+        })?;
+
+    if let Some(error) = &rule.error {
+        return Err(RpcError::new(USER_REJECTED, error.clone()));
+    }
+    Ok(rule
+        .result
+        .clone()
+        .unwrap_or_else(|| default_result(method)))
+}
+
+// Text the script patterns are matched against
+fn request_text(method: &str, params: &JsonValue) -> String {
+    if method == ELICITATION_METHOD {
+        return params
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or_default()
+            .to_string();
+    }
+
+    let mut text = String::new();
+    if let Some(prompt) = params.get("systemPrompt").and_then(|p| p.as_str()) {
+        text.push_str(prompt);
+        text.push('\n');
+    }
+    for message in params
+        .get("messages")
+        .and_then(|m| m.as_array())
+        .into_iter()
+        .flatten()
+    {
+        if let Some(content) = message.get("content").and_then(|c| c.get("text")) {
+            text.push_str(content.as_str().unwrap_or_default());
+            text.push('\n');
+        }
+    }
+    text
+}
+
+fn default_result(method: &str) -> JsonValue {
+    if method == ELICITATION_METHOD {
+        json!({ "action": "accept", "content": {} })
+    } else {
+        json!({
+            "role": "assistant",
+            "content": { "type": "text", "text": "" },
+            "model": "mcp-manager-scripted",
+            "stopReason": "endTurn",
+        })
+    }
+}
+
+// Answer a prompt shown in the UI; an error rejects the request
+#[tauri::command]
+pub fn respond_client_request(
+    request_id: u64,
+    result: Option<JsonValue>,
+    error: Option<String>,
+) -> Result<(), String> {
+    let sender = PENDING_PROMPTS
+        .lock()
+        .map_err(|e| format!("Failed to lock prompts: {}", e))?
+        .remove(&request_id)
+        .ok_or_else(|| format!("Request {} is no longer pending", request_id))?;
+
+    let answer = match (result, error) {
+        (_, Some(error)) => Err(error),
+        (Some(result), None) => Ok(result),
+        (None, None) => Err("Request rejected by the user".to_string()),
+    };
+    sender
+        .send(answer)
+        .map_err(|_| format!("Request {} is no longer pending", request_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sampling(system: &str, messages: &[&str]) -> JsonValue {
+        json!({
+            "systemPrompt": system,
+            "messages": messages
+                .iter()
+                .map(|text| json!({ "role": "user", "content": { "type": "text", "text": text } }))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    fn script(name: &str, content: JsonValue) -> String {
+        let path =
+            std::env::temp_dir().join(format!("mcp-script-{}-{}.json", name, std::process::id()));
+        fs::write(&path, content.to_string()).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn request_text_covers_prompt_and_messages() {
+        let params = sampling("Be brief", &["What is the weather?", "In Paris"]);
+        assert_eq!(
+            request_text(SAMPLING_METHOD, &params),
+            "Be brief\nWhat is the weather?\nIn Paris\n"
+        );
+
+        // Image content has no text to match
+        let params = json!({ "messages": [{ "role": "user", "content": { "type": "image" } }] });
+        assert_eq!(request_text(SAMPLING_METHOD, &params), "");
+
+        let params = json!({ "message": "Pick a branch", "requestedSchema": {} });
+        assert_eq!(request_text(ELICITATION_METHOD, &params), "Pick a branch");
+    }
+
+    #[test]
+    fn scripted_rules_match_in_order() {
+        let file = script(
+            "order",
+            json!({
+                "sampling": [
+                    { "match": "weather", "result": { "role": "assistant", "content": { "type": "text", "text": "sunny" } } },
+                    { "match": "delete", "error": "not allowed" },
+                    { "result": { "role": "assistant", "content": { "type": "text", "text": "fallback" } } }
+                ],
+                "elicitation": [
+                    { "match": "branch" }
+                ]
+            }),
+        );
+
+        let result =
+            scripted(&file, SAMPLING_METHOD, &sampling("", &["weather in Paris"])).unwrap();
+        assert_eq!(result["content"]["text"], "sunny");
+
+        let err = scripted(&file, SAMPLING_METHOD, &sampling("", &["delete all"])).unwrap_err();
+        assert_eq!(err.code, USER_REJECTED);
+        assert_eq!(err.message, "not allowed");
+
+        let result = scripted(&file, SAMPLING_METHOD, &sampling("", &["hello"])).unwrap();
+        assert_eq!(result["content"]["text"], "fallback");
+
+        // A rule without a result accepts with the default answer
+        let params = json!({ "message": "Pick a branch" });
+        assert_eq!(
+            scripted(&file, ELICITATION_METHOD, &params).unwrap(),
+            default_result(ELICITATION_METHOD)
+        );
+
+        let params = json!({ "message": "Enter a token" });
+        let err = scripted(&file, ELICITATION_METHOD, &params).unwrap_err();
+        assert_eq!(err.code, USER_REJECTED);
+        let _ = fs::remove_file(&file);
+    }
+}
@@ -0,0 +1,689 @@
+use crate::app_control::{self, RestartResult};
+use crate::claude_config::ServerConfig;
+use crate::config_io::{self, ConfigSnapshot, FileVersion};
+use crate::json_edit::JsonStyle;
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use std::env;
+use std::path::{Path, PathBuf};
+
+// Servers read from one client config, with the version they were read at
+#[derive(Debug, Clone)]
+pub struct ClientServers {
+    pub servers: IndexMap<String, ServerConfig>,
+    pub snapshot: ConfigSnapshot,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ClientInfo {
+    pub id: String,
+    pub name: String,
+    pub config_path: Option<String>,
+    pub exists: bool,
+    pub servers: Vec<String>,
+    pub error: Option<String>,
+}
+
+// An MCP client whose servers live in a JSON config file
+pub trait ClientAdapter: Send + Sync {
+    fn id(&self) -> &'static str;
+
+    fn name(&self) -> &'static str;
+
+    // Config file of the user scope, or of `project` for clients with project configs
+    fn config_path(&self, project: Option<&Path>) -> Result<PathBuf, String>;
+
+    // JSON pointer of the servers object inside the config
+    fn servers_pointer(&self) -> &'static str {
+        "/mcpServers"
+    }
+
+    // JSON pointers of the settings this app edits in the config; transactions journal
+    // and restore only these, the rest of the file belongs to the client
+    fn managed_pointers(&self) -> Vec<&'static str> {
+        vec![self.servers_pointer()]
+    }
+
+    // Id of the app in `app_control`, `None` for clients without a long-running app
+    fn app_id(&self) -> Option<&'static str> {
+        None
+    }
+
+    // A client counts as installed when the directory of its user config exists
+    fn is_installed(&self) -> bool {
+        self.config_path(None)
+            .ok()
+            .and_then(|path| path.parent().map(Path::exists))
+            .unwrap_or(false)
+    }
+
+    // Adjust an entry to the shape this client expects before it is written
+    fn prepare_entry(&self, entry: ServerConfig) -> ServerConfig {
+        entry
+    }
+
+    fn read_servers(&self, project: Option<&Path>) -> Result<ClientServers, String> {
+        let snapshot = config_io::read_config(&self.config_path(project)?)?;
+        let servers = match snapshot.content.as_deref() {
+            Some(content) => servers_at(content, self.servers_pointer())?,
+            None => IndexMap::new(),
+        };
+
+        Ok(ClientServers { servers, snapshot })
+    }
+
+    // Entries as they are written: only the ones added or changed since `before` are
+    // adjusted by `prepare_entry`, so untouched servers are left as the user wrote them
+    fn written_servers(
+        &self,
+        before: &IndexMap<String, ServerConfig>,
+        after: &IndexMap<String, ServerConfig>,
+    ) -> IndexMap<String, ServerConfig> {
+        after
+            .iter()
+            .map(|(name, entry)| {
+                let entry = if before.get(name) == Some(entry) {
+                    entry.clone()
+                } else {
+                    self.prepare_entry(entry.clone())
+                };
+                (name.clone(), entry)
+            })
+            .collect()
+    }
+
+    // Write the servers back, failing if the file changed since it was read. Only the
+    // entries that changed are rewritten, the rest of the file (comments included) is kept.
+    // Nothing is written when the servers are unchanged, so no file is created for an empty map.
+    fn write_servers(&self, current: &ClientServers) -> Result<FileVersion, String> {
+        write_entries(self, current, true)
+    }
+
+    // Like `write_servers`, but every entry is written exactly as given, e.g. one taken out
+    // of this client's config earlier
+    fn write_servers_unprepared(&self, current: &ClientServers) -> Result<FileVersion, String> {
+        write_entries(self, current, false)
+    }
+
+    fn restart(&self) -> Result<Option<RestartResult>, String> {
+        match self.app_id() {
+            Some(id) => app_control::restart(app_control::find_client_app(id)?).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+// Write `current` back over its file; `prepare` adjusts the changed entries for the client
+fn write_entries<C: ClientAdapter + ?Sized>(
+    client: &C,
+    current: &ClientServers,
+    prepare: bool,
+) -> Result<FileVersion, String> {
+    let original = current
+        .snapshot
+        .content
+        .as_deref()
+        .filter(|content| !content.trim().is_empty());
+    let before = match original {
+        Some(content) => servers_at(content, client.servers_pointer())?,
+        None => IndexMap::new(),
+    };
+    if before == current.servers {
+        return Ok(current.snapshot.version.clone());
+    }
+
+    let entries = if prepare {
+        client.written_servers(&before, &current.servers)
+    } else {
+        current.servers.clone()
+    };
+    let servers: JsonMap<String, JsonValue> = entries
+        .into_iter()
+        .map(|(name, entry)| (name, JsonValue::Object(entry)))
+        .collect();
+    let mut document = match original {
+        Some(content) => parse_document(content)?,
+        None => json!({}),
+    };
+    set_pointer(
+        &mut document,
+        client.servers_pointer(),
+        JsonValue::Object(servers),
+    )?;
+
+    let content = match original {
+        Some(original) => crate::json_edit::edit_over(original, &document)?,
+        None => crate::json_edit::to_string_styled(&document, &JsonStyle::default())?,
+    };
+    config_io::write_config(&current.snapshot.path, &content, &current.snapshot.version)
+}
+
+pub(crate) fn parse_document(content: &str) -> Result<JsonValue, String> {
+    let cleaned = crate::process_detection::strip_json_comments(content);
+    if cleaned.trim().is_empty() {
+        return Ok(json!({}));
+    }
+    serde_json::from_str(&cleaned).map_err(|e| format!("Failed to parse config: {}", e))
+}
+
+// Server entries at `pointer`; entries that are not objects are skipped
+fn servers_at(content: &str, pointer: &str) -> Result<IndexMap<String, ServerConfig>, String> {
+    Ok(parse_document(content)?
+        .pointer(pointer)
+        .and_then(|servers| servers.as_object())
+        .map(|servers| {
+            servers
+                .iter()
+                .filter_map(|(name, entry)| {
+                    entry.as_object().map(|entry| (name.clone(), entry.clone()))
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+// Set the value at a JSON pointer, creating intermediate objects
+pub(crate) fn set_pointer(
+    document: &mut JsonValue,
+    pointer: &str,
+    value: JsonValue,
+) -> Result<(), String> {
+    let mut target = document;
+    let keys: Vec<String> = pointer
+        .split('/')
+        .skip(1)
+        .map(|key| key.replace("~1", "/").replace("~0", "~"))
+        .collect();
+    let Some((last, parents)) = keys.split_last() else {
+        return Err("Invalid servers pointer".to_string());
+    };
+
+    for key in parents {
+        target = target
+            .as_object_mut()
+            .ok_or_else(|| format!("Config value at `{}` is not an object", pointer))?
+            .entry(key.clone())
+            .or_insert_with(|| json!({}));
+    }
+    target
+        .as_object_mut()
+        .ok_or_else(|| format!("Config value at `{}` is not an object", pointer))?
+        .insert(last.clone(), value);
+    Ok(())
+}
+
+// Remove the value at a JSON pointer, if there is one
+pub(crate) fn remove_pointer(document: &mut JsonValue, pointer: &str) {
+    let Some((parent, last)) = pointer.rsplit_once('/') else {
+        return;
+    };
+    let last = last.replace("~1", "/").replace("~0", "~");
+    if let Some(parent) = document.pointer_mut(parent).and_then(|p| p.as_object_mut()) {
+        parent.remove(&last);
+    }
+}
+
+pub(crate) fn home_dir() -> Result<PathBuf, String> {
+    env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .map(PathBuf::from)
+        .map_err(|_| "Could not find home directory".to_string())
+}
+
+// Per-user settings directory of Electron apps and Zed
+fn app_config_dir() -> Result<PathBuf, String> {
+    if cfg!(target_os = "macos") {
+        Ok(home_dir()?.join("Library").join("Application Support"))
+    } else if cfg!(target_os = "windows") {
+        env::var("APPDATA")
+            .map(PathBuf::from)
+            .map_err(|_| "Could not find APPDATA directory".to_string())
+    } else {
+        Ok(home_dir()?.join(".config"))
+    }
+}
+
+fn no_project_scope(name: &str) -> String {
+    format!("{} has no project configs", name)
+}
+
+struct ClaudeDesktop;
+
+impl ClientAdapter for ClaudeDesktop {
+    fn id(&self) -> &'static str {
+        "claude"
+    }
+
+    fn name(&self) -> &'static str {
+        "Claude Desktop"
+    }
+
+    fn config_path(&self, project: Option<&Path>) -> Result<PathBuf, String> {
+        match project {
+            Some(_) => Err(no_project_scope(self.name())),
+            None => crate::claude_config::config_path(),
+        }
+    }
+
+    // The global shortcut is edited on the settings page
+    fn managed_pointers(&self) -> Vec<&'static str> {
+        vec![self.servers_pointer(), "/globalShortcut"]
+    }
+
+    fn app_id(&self) -> Option<&'static str> {
+        Some("claude")
+    }
+}
+
+// Claude Code reads `~/.claude.json` and `.mcp.json` in the project root
+struct ClaudeCode;
+
+impl ClientAdapter for ClaudeCode {
+    fn id(&self) -> &'static str {
+        "claude-code"
+    }
+
+    fn name(&self) -> &'static str {
+        "Claude Code"
+    }
+
+    fn config_path(&self, project: Option<&Path>) -> Result<PathBuf, String> {
+        match project {
+            Some(project) => Ok(project.join(".mcp.json")),
+            None => Ok(home_dir()?.join(".claude.json")),
+        }
+    }
+
+    // The user config sits in the home directory, so look for Claude Code's data directory
+    fn is_installed(&self) -> bool {
+        home_dir().is_ok_and(|home| home.join(".claude").is_dir())
+    }
+}
+
+struct Cursor;
+
+impl ClientAdapter for Cursor {
+    fn id(&self) -> &'static str {
+        "cursor"
+    }
+
+    fn name(&self) -> &'static str {
+        "Cursor"
+    }
+
+    fn config_path(&self, project: Option<&Path>) -> Result<PathBuf, String> {
+        let root = match project {
+            Some(project) => project.to_path_buf(),
+            None => home_dir()?,
+        };
+        Ok(root.join(".cursor").join("mcp.json"))
+    }
+
+    fn app_id(&self) -> Option<&'static str> {
+        Some("cursor")
+    }
+}
+
+struct Windsurf;
+
+impl ClientAdapter for Windsurf {
+    fn id(&self) -> &'static str {
+        "windsurf"
+    }
+
+    fn name(&self) -> &'static str {
+        "Windsurf"
+    }
+
+    fn config_path(&self, project: Option<&Path>) -> Result<PathBuf, String> {
+        match project {
+            Some(_) => Err(no_project_scope(self.name())),
+            None => Ok(home_dir()?
+                .join(".codeium")
+                .join("windsurf")
+                .join("mcp_config.json")),
+        }
+    }
+
+    fn app_id(&self) -> Option<&'static str> {
+        Some("windsurf")
+    }
+}
+
+// VS Code keeps servers in `mcp.json` under `servers`, each with a `type`
+struct VsCode;
+
+impl ClientAdapter for VsCode {
+    fn id(&self) -> &'static str {
+        "vscode"
+    }
+
+    fn name(&self) -> &'static str {
+        "VS Code"
+    }
+
+    fn config_path(&self, project: Option<&Path>) -> Result<PathBuf, String> {
+        match project {
+            Some(project) => Ok(project.join(".vscode").join("mcp.json")),
+            None => Ok(app_config_dir()?.join("Code").join("User").join("mcp.json")),
+        }
+    }
+
+    fn servers_pointer(&self) -> &'static str {
+        "/servers"
+    }
+
+    fn app_id(&self) -> Option<&'static str> {
+        Some("vscode")
+    }
+
+    fn prepare_entry(&self, mut entry: ServerConfig) -> ServerConfig {
+        if !entry.contains_key("type") {
+            let kind = if entry.contains_key("url") {
+                "http"
+            } else {
+                "stdio"
+            };
+            entry.insert("type".to_string(), json!(kind));
+        }
+        entry
+    }
+}
+
+// Zed keeps servers in its settings file under `context_servers`
+struct Zed;
+
+impl ClientAdapter for Zed {
+    fn id(&self) -> &'static str {
+        "zed"
+    }
+
+    fn name(&self) -> &'static str {
+        "Zed"
+    }
+
+    fn config_path(&self, project: Option<&Path>) -> Result<PathBuf, String> {
+        match project {
+            Some(project) => Ok(project.join(".zed").join("settings.json")),
+            // Zed uses ~/.config/zed on macOS as well
+            None if cfg!(target_os = "windows") => {
+                Ok(app_config_dir()?.join("Zed").join("settings.json"))
+            }
+            None => Ok(home_dir()?
+                .join(".config")
+                .join("zed")
+                .join("settings.json")),
+        }
+    }
+
+    fn servers_pointer(&self) -> &'static str {
+        "/context_servers"
+    }
+
+    fn app_id(&self) -> Option<&'static str> {
+        Some("zed")
+    }
+
+    fn prepare_entry(&self, mut entry: ServerConfig) -> ServerConfig {
+        if entry.contains_key("command") && !entry.contains_key("source") {
+            entry.insert("source".to_string(), json!("custom"));
+        }
+        entry
+    }
+}
+
+pub static CLIENTS: &[&dyn ClientAdapter] = &[
+    &ClaudeDesktop,
+    &ClaudeCode,
+    &Cursor,
+    &Windsurf,
+    &VsCode,
+    &Zed,
+];
+
+pub fn find_client(id: &str) -> Result<&'static dyn ClientAdapter, String> {
+    CLIENTS
+        .iter()
+        .copied()
+        .find(|client| client.id() == id)
+        .ok_or_else(|| format!("Unknown client: {}", id))
+}
+
+// The client a config file belongs to, by its user config or the file name of its project
+// configs, e.g. `.vscode/mcp.json`
+pub fn client_for_path(path: &Path) -> Option<&'static dyn ClientAdapter> {
+    CLIENTS.iter().copied().find(|client| {
+        client.config_path(None).is_ok_and(|user| user == path)
+            || client
+                .config_path(Some(Path::new("")))
+                .is_ok_and(|project| path.ends_with(project))
+    })
+}
+
+// Resolve requested client ids; `None` means Claude Desktop and "all" every installed client
+pub fn resolve_targets(
+    clients: Option<Vec<String>>,
+) -> Result<Vec<&'static dyn ClientAdapter>, String> {
+    let ids = clients.unwrap_or_else(|| vec!["claude".to_string()]);
+    if ids.iter().any(|id| id == "all") {
+        return Ok(CLIENTS
+            .iter()
+            .copied()
+            .filter(|c| c.is_installed())
+            .collect());
+    }
+    ids.iter().map(|id| find_client(id)).collect()
+}
+
+// Apply `edit` to the servers of every target, collecting failures per client. Targets
+// whose servers end up unchanged are not written.
+pub fn edit_servers<F>(
+    targets: &[&'static dyn ClientAdapter],
+    project: Option<&Path>,
+    mut edit: F,
+) -> Result<(), String>
+where
+    F: FnMut(&mut IndexMap<String, ServerConfig>) -> Result<(), String>,
+{
+    let mut errors = Vec::new();
+    for client in targets {
+        let result = client.read_servers(project).and_then(|mut current| {
+            edit(&mut current.servers)?;
+            client.write_servers(&current)
+        });
+        if let Err(e) = result {
+            errors.push(format!("{}: {}", client.name(), e));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+#[tauri::command]
+pub fn list_mcp_clients(project: Option<String>) -> Vec<ClientInfo> {
+    let project = project.map(PathBuf::from);
+    CLIENTS
+        .iter()
+        .map(|client| {
+            let path = client.config_path(project.as_deref());
+            let (servers, error) = match client.read_servers(project.as_deref()) {
+                Ok(current) => (current.servers.keys().cloned().collect(), None),
+                Err(e) => (Vec::new(), Some(e)),
+            };
+            ClientInfo {
+                id: client.id().to_string(),
+                name: client.name().to_string(),
+                exists: path.as_ref().is_ok_and(|p| p.exists()),
+                config_path: path.ok().map(|p| p.to_string_lossy().to_string()),
+                servers,
+                error,
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn get_client_servers(
+    client: String,
+    project: Option<String>,
+) -> Result<IndexMap<String, ServerConfig>, String> {
+    let project = project.map(PathBuf::from);
+    Ok(find_client(&client)?
+        .read_servers(project.as_deref())?
+        .servers)
+}
+
+#[tauri::command]
+pub fn restart_client(client: String) -> Result<Option<RestartResult>, String> {
+    find_client(&client)?.restart()
+}
+
+// A VS Code style client whose config lives in a temporary directory, for tests of modules
+// that read and write client configs
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::*;
+    use std::fs;
+
+    pub(crate) struct TestClient(pub PathBuf);
+
+    impl ClientAdapter for TestClient {
+        fn id(&self) -> &'static str {
+            "test"
+        }
+
+        fn name(&self) -> &'static str {
+            "Test"
+        }
+
+        fn config_path(&self, _project: Option<&Path>) -> Result<PathBuf, String> {
+            Ok(self.0.join("mcp.json"))
+        }
+
+        fn servers_pointer(&self) -> &'static str {
+            "/servers"
+        }
+
+        fn prepare_entry(&self, entry: ServerConfig) -> ServerConfig {
+            VsCode.prepare_entry(entry)
+        }
+    }
+
+    // `name` must be unique among all tests
+    pub(crate) fn test_client(name: &str) -> &'static TestClient {
+        let dir = env::temp_dir().join(format!("mcp-clients-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        Box::leak(Box::new(TestClient(dir)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::test_client;
+    use super::*;
+    use std::fs;
+
+    const COMMENTED: &str = include_str!("../tests/fixtures/clients/vscode_commented.json");
+
+    fn entry(value: JsonValue) -> ServerConfig {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn edits_keep_comments_and_untouched_entries() {
+        let client = test_client("comments");
+        let path = client.config_path(None).unwrap();
+        fs::write(&path, COMMENTED).unwrap();
+
+        let mut current = client.read_servers(None).unwrap();
+        current.servers.insert(
+            "git".to_string(),
+            entry(json!({ "command": "uvx", "args": ["mcp-server-git"] })),
+        );
+        current
+            .servers
+            .get_mut("docs")
+            .unwrap()
+            .insert("url".to_string(), json!("https://docs.example.com/v2/mcp"));
+        client.write_servers(&current).unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        let expected = COMMENTED
+            .replace(
+                "      \"url\": \"https://docs.example.com/mcp\"\n    }\n",
+                "      \"url\": \"https://docs.example.com/v2/mcp\",\n      \"type\": \"http\"\n    },\n    \"git\": {\n      \"command\": \"uvx\",\n      \"args\": [\n        \"mcp-server-git\"\n      ],\n      \"type\": \"stdio\"\n    }\n",
+            );
+        assert_eq!(written, expected);
+
+        // The untouched entry did not get a `type`
+        let servers = client.read_servers(None).unwrap().servers;
+        assert!(!servers["files"].contains_key("type"));
+        let _ = fs::remove_dir_all(&client.0);
+    }
+
+    #[test]
+    fn removing_an_entry_keeps_the_rest() {
+        let client = test_client("remove");
+        let path = client.config_path(None).unwrap();
+        fs::write(&path, COMMENTED).unwrap();
+
+        edit_servers(&[client], None, |servers| {
+            servers.shift_remove("files");
+            Ok(())
+        })
+        .unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(!written.contains("server-filesystem"));
+        assert!(!written.contains("Local files"));
+        assert!(written.contains("// Remote docs search"));
+        assert!(written.contains("\"inputs\": [] // prompted values"));
+        let _ = fs::remove_dir_all(&client.0);
+    }
+
+    #[test]
+    fn unchanged_servers_are_not_written() {
+        let client = test_client("unchanged");
+        let target: &'static dyn ClientAdapter = client;
+        let path = client.config_path(None).unwrap();
+
+        // No file is created for an empty map
+        edit_servers(&[target], None, |_| Ok(())).unwrap();
+        assert!(!path.exists());
+
+        // A file with the same servers in a different form is left alone
+        let compact = "{\"servers\":{\"a\":{\"command\":\"x\"}}}";
+        fs::write(&path, compact).unwrap();
+        edit_servers(&[target], None, |servers| {
+            servers.insert("a".to_string(), entry(json!({ "command": "x" })));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), compact);
+        let _ = fs::remove_dir_all(&client.0);
+    }
+
+    #[test]
+    fn missing_file_is_created_for_new_servers() {
+        let client = test_client("create");
+        let path = client.config_path(None).unwrap();
+
+        let mut current = client.read_servers(None).unwrap();
+        current
+            .servers
+            .insert("a".to_string(), entry(json!({ "command": "x" })));
+        client.write_servers(&current).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "{\n  \"servers\": {\n    \"a\": {\n      \"command\": \"x\",\n      \"type\": \"stdio\"\n    }\n  }\n}"
+        );
+        let _ = fs::remove_dir_all(&client.0);
+    }
+}
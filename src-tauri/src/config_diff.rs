@@ -0,0 +1,233 @@
+use crate::claude_config::ServerConfig;
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct FieldChange {
+    // JSON pointer relative to the compared value, e.g. `/args/1`
+    pub path: String,
+    pub before: Option<JsonValue>,
+    pub after: Option<JsonValue>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ServerChange {
+    pub name: String,
+    pub kind: ChangeKind,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ConfigDiff {
+    pub servers: Vec<ServerChange>,
+    // Changes outside the servers section
+    pub settings: Vec<FieldChange>,
+}
+
+pub(crate) fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+// Collect the differences between two JSON values as pointer-addressed changes
+pub fn diff_values(path: &str, before: &JsonValue, after: &JsonValue, out: &mut Vec<FieldChange>) {
+    match (before, after) {
+        (JsonValue::Object(a), JsonValue::Object(b)) => {
+            for (key, value) in a {
+                let child = format!("{}/{}", path, escape_pointer(key));
+                match b.get(key) {
+                    Some(other) => diff_values(&child, value, other, out),
+                    None => out.push(FieldChange {
+                        path: child,
+                        before: Some(value.clone()),
+                        after: None,
+                    }),
+                }
+            }
+            for (key, value) in b {
+                if !a.contains_key(key) {
+                    out.push(FieldChange {
+                        path: format!("{}/{}", path, escape_pointer(key)),
+                        before: None,
+                        after: Some(value.clone()),
+                    });
+                }
+            }
+        }
+        (JsonValue::Array(a), JsonValue::Array(b)) if a.len() == b.len() => {
+            for (i, (x, y)) in a.iter().zip(b).enumerate() {
+                diff_values(&format!("{}/{}", path, i), x, y, out);
+            }
+        }
+        _ if before != after => out.push(FieldChange {
+            path: path.to_string(),
+            before: Some(before.clone()),
+            after: Some(after.clone()),
+        }),
+        _ => {}
+    }
+}
+
+fn servers_of<'a>(
+    document: &'a JsonValue,
+    servers_key: &str,
+) -> Option<&'a JsonMap<String, JsonValue>> {
+    document.get(servers_key).and_then(|s| s.as_object())
+}
+
+// Diff two server maps entry by entry
+pub fn diff_servers(
+    before: Option<&JsonMap<String, JsonValue>>,
+    after: Option<&JsonMap<String, JsonValue>>,
+) -> Vec<ServerChange> {
+    let empty = JsonMap::new();
+    let before = before.unwrap_or(&empty);
+    let after = after.unwrap_or(&empty);
+    let mut servers = Vec::new();
+
+    for (name, old) in before {
+        match after.get(name) {
+            Some(new) => {
+                let mut changes = Vec::new();
+                diff_values("", old, new, &mut changes);
+                if !changes.is_empty() {
+                    servers.push(ServerChange {
+                        name: name.clone(),
+                        kind: ChangeKind::Modified,
+                        changes,
+                    });
+                }
+            }
+            None => servers.push(ServerChange {
+                name: name.clone(),
+                kind: ChangeKind::Removed,
+                changes: vec![FieldChange {
+                    path: String::new(),
+                    before: Some(old.clone()),
+                    after: None,
+                }],
+            }),
+        }
+    }
+    for (name, new) in after {
+        if !before.contains_key(name) {
+            servers.push(ServerChange {
+                name: name.clone(),
+                kind: ChangeKind::Added,
+                changes: vec![FieldChange {
+                    path: String::new(),
+                    before: None,
+                    after: Some(new.clone()),
+                }],
+            });
+        }
+    }
+
+    servers
+}
+
+// Diff two clients' server maps as read by the client adapters
+pub fn diff_server_maps(
+    before: &IndexMap<String, ServerConfig>,
+    after: &IndexMap<String, ServerConfig>,
+) -> Vec<ServerChange> {
+    let to_map = |servers: &IndexMap<String, ServerConfig>| -> JsonMap<String, JsonValue> {
+        servers
+            .iter()
+            .map(|(name, entry)| (name.clone(), JsonValue::Object(entry.clone())))
+            .collect()
+    };
+    diff_servers(Some(&to_map(before)), Some(&to_map(after)))
+}
+
+// Structured diff of two config documents whose servers live under `servers_key`
+pub fn diff_documents(before: &JsonValue, after: &JsonValue, servers_key: &str) -> ConfigDiff {
+    let servers = diff_servers(
+        servers_of(before, servers_key),
+        servers_of(after, servers_key),
+    );
+
+    let strip = |document: &JsonValue| {
+        let mut document = document.clone();
+        if let Some(map) = document.as_object_mut() {
+            map.remove(servers_key);
+        }
+        document
+    };
+    let mut settings = Vec::new();
+    diff_values("", &strip(before), &strip(after), &mut settings);
+
+    ConfigDiff { servers, settings }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diff_values_reports_pointer_paths() {
+        let before =
+            json!({ "command": "npx", "args": ["-y", "a"], "env": { "A/B": "1", "C": "2" } });
+        let after = json!({ "command": "npx", "args": ["-y", "b"], "env": { "A/B": "1", "D": "3" }, "cwd": "/tmp" });
+        let mut changes = Vec::new();
+        diff_values("", &before, &after, &mut changes);
+
+        let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["/args/1", "/env/C", "/env/D", "/cwd"]);
+        assert_eq!(changes[0].before, Some(json!("a")));
+        assert_eq!(changes[0].after, Some(json!("b")));
+        assert_eq!(changes[1].after, None);
+        assert_eq!(changes[2].before, None);
+
+        // Arrays of different lengths change as a whole
+        let mut changes = Vec::new();
+        diff_values("/args", &json!(["a"]), &json!(["a", "b"]), &mut changes);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, "/args");
+    }
+
+    #[test]
+    fn diff_documents_separates_servers_and_settings() {
+        let before = json!({
+            "mcpServers": { "a": { "command": "x" }, "b": { "command": "y" } },
+            "globalShortcut": "Alt+Space"
+        });
+        let after = json!({
+            "mcpServers": { "a": { "command": "z" }, "c": { "command": "w" } },
+            "globalShortcut": "Alt+Space",
+            "locale": "en"
+        });
+        let diff = diff_documents(&before, &after, "mcpServers");
+
+        let servers: Vec<(&str, ChangeKind)> = diff
+            .servers
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind.clone()))
+            .collect();
+        assert_eq!(
+            servers,
+            [
+                ("a", ChangeKind::Modified),
+                ("b", ChangeKind::Removed),
+                ("c", ChangeKind::Added)
+            ]
+        );
+        assert_eq!(diff.servers[0].changes[0].path, "/command");
+        assert_eq!(diff.settings.len(), 1);
+        assert_eq!(diff.settings[0].path, "/locale");
+
+        // A document without servers diffs as an empty map
+        let diff = diff_documents(&json!({}), &before, "mcpServers");
+        assert_eq!(diff.servers.len(), 2);
+        assert_eq!(diff.settings[0].path, "/globalShortcut");
+    }
+}
@@ -0,0 +1,398 @@
+use crate::clients;
+use crate::config_diff::{self, ConfigDiff};
+use crate::config_io;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+const INDEX_FILE: &str = "index.json";
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+static HISTORY_ROOT: OnceCell<PathBuf> = OnceCell::new();
+static RETENTION: Lazy<Mutex<RetentionPolicy>> =
+    Lazy::new(|| Mutex::new(RetentionPolicy::default()));
+// Serializes index updates across threads
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HistorySource {
+    // Written by MCP Manager
+    Manager,
+    // Changed by the user or another tool
+    External,
+    // Written by restoring an earlier version
+    Restore { from: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub config_path: String,
+    pub timestamp: u64,
+    pub source: HistorySource,
+    pub hash: String,
+    pub size: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RetentionPolicy {
+    pub max_entries: usize,
+    pub max_age_days: u64,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy {
+            max_entries: 100,
+            max_age_days: 90,
+        }
+    }
+}
+
+pub fn init(root: PathBuf, retention: RetentionPolicy) {
+    let _ = HISTORY_ROOT.set(root);
+    set_retention(retention);
+}
+
+pub fn set_retention(retention: RetentionPolicy) {
+    if let Ok(mut current) = RETENTION.lock() {
+        *current = retention;
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+// Each config file gets its own directory, named after its path
+fn history_dir(config_path: &Path) -> Option<PathBuf> {
+    let root = HISTORY_ROOT.get()?;
+    let key = config_io::content_hash(&config_path.to_string_lossy());
+    Some(root.join(&key[..16]))
+}
+
+fn load_index(dir: &Path) -> Vec<HistoryEntry> {
+    fs::read_to_string(dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(dir: &Path, entries: &[HistoryEntry]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize history: {}", e))?;
+    config_io::replace_file(&dir.join(INDEX_FILE), &content)
+}
+
+fn apply_retention(dir: &Path, entries: &mut Vec<HistoryEntry>, policy: &RetentionPolicy) {
+    let cutoff = now_ms().saturating_sub(policy.max_age_days.saturating_mul(DAY_MS));
+
+    // Entries are oldest first; the newest one is always kept
+    let keep_from = entries.len().saturating_sub(policy.max_entries.max(1));
+    let last = entries.len().saturating_sub(1);
+    let mut index = 0;
+    entries.retain(|entry| {
+        let keep = index == last || (index >= keep_from && entry.timestamp >= cutoff);
+        if !keep {
+            let _ = fs::remove_file(dir.join(format!("{}.json", entry.id)));
+        }
+        index += 1;
+        keep
+    });
+}
+
+// Record a version of a config file unless it equals the latest recorded one
+pub fn record(
+    config_path: &Path,
+    content: &str,
+    source: HistorySource,
+) -> Result<Option<HistoryEntry>, String> {
+    let Some(dir) = history_dir(config_path) else {
+        return Ok(None);
+    };
+    let _guard = INDEX_LOCK
+        .lock()
+        .map_err(|e| format!("Failed to lock history: {}", e))?;
+
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create history directory: {}", e))?;
+    let mut entries = load_index(&dir);
+
+    let hash = config_io::content_hash(content);
+    if entries.last().is_some_and(|last| last.hash == hash) {
+        return Ok(None);
+    }
+
+    let timestamp = now_ms();
+    let entry = HistoryEntry {
+        id: format!(
+            "{:013}-{:04}",
+            timestamp,
+            SEQUENCE.fetch_add(1, Ordering::SeqCst) % 10_000
+        ),
+        config_path: config_path.to_string_lossy().to_string(),
+        timestamp,
+        source,
+        hash,
+        size: content.len(),
+    };
+    config_io::replace_file(&dir.join(format!("{}.json", entry.id)), content)?;
+
+    entries.push(entry.clone());
+    let policy = RETENTION.lock().map(|p| p.clone()).unwrap_or_default();
+    apply_retention(&dir, &mut entries, &policy);
+    save_index(&dir, &entries)?;
+
+    Ok(Some(entry))
+}
+
+pub fn list(config_path: &Path) -> Vec<HistoryEntry> {
+    history_dir(config_path)
+        .map(|dir| load_index(&dir))
+        .unwrap_or_default()
+}
+
+pub fn read_version(config_path: &Path, id: &str) -> Result<String, String> {
+    let dir = history_dir(config_path).ok_or_else(|| "History is not available".to_string())?;
+    if !load_index(&dir).iter().any(|entry| entry.id == id) {
+        return Err(format!("Version {} not found", id));
+    }
+    fs::read_to_string(dir.join(format!("{}.json", id)))
+        .map_err(|e| format!("Failed to read version {}: {}", id, e))
+}
+
+// Content of the most recent recorded version with the given hash
+pub fn find_by_hash(config_path: &Path, hash: &str) -> Option<String> {
+    let entry = list(config_path)
+        .into_iter()
+        .rev()
+        .find(|e| e.hash == hash)?;
+    read_version(config_path, &entry.id).ok()
+}
+
+fn resolve_path(config_path: Option<String>) -> Result<PathBuf, String> {
+    match config_path {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => crate::claude_config::config_path(),
+    }
+}
+
+// Content of a version id, or of the file on disk when `id` is `None`
+fn version_content(path: &Path, id: Option<&str>) -> Result<String, String> {
+    match id {
+        Some(id) => read_version(path, id),
+        None => Ok(config_io::read_config(path)?.content.unwrap_or_default()),
+    }
+}
+
+#[tauri::command]
+pub fn list_config_history(config_path: Option<String>) -> Result<Vec<HistoryEntry>, String> {
+    let path = resolve_path(config_path)?;
+    let mut entries = list(&path);
+    entries.reverse();
+    Ok(entries)
+}
+
+#[tauri::command]
+pub fn get_config_history_version(
+    config_path: Option<String>,
+    id: String,
+) -> Result<String, String> {
+    read_version(&resolve_path(config_path)?, &id)
+}
+
+// Diff two versions; a missing `to` compares against the file on disk
+#[tauri::command]
+pub fn diff_config_versions(
+    config_path: Option<String>,
+    from: String,
+    to: Option<String>,
+) -> Result<ConfigDiff, String> {
+    diff_versions(&resolve_path(config_path)?, &from, to.as_deref())
+}
+
+// Servers are compared at the servers key of the client owning the file
+pub fn diff_versions(path: &Path, from: &str, to: Option<&str>) -> Result<ConfigDiff, String> {
+    let before = clients::parse_document(&read_version(path, from)?)?;
+    let after = clients::parse_document(&version_content(path, to)?)?;
+    let pointer = clients::client_for_path(path).map_or("/mcpServers", |c| c.servers_pointer());
+    Ok(config_diff::diff_documents(
+        &before,
+        &after,
+        pointer.trim_start_matches('/'),
+    ))
+}
+
+#[tauri::command]
+pub async fn restore_config_version(
+    app: AppHandle,
+    config_path: Option<String>,
+    id: String,
+) -> Result<HistoryEntry, String> {
+    let path = resolve_path(config_path)?;
+    crate::transaction::run(&app, "Restore config version", None, async {
+        restore_version(&path, &id)
+    })
+    .await
+}
+
+pub fn restore_version(path: &Path, id: &str) -> Result<HistoryEntry, String> {
+    let content = read_version(path, id)?;
+    let snapshot = config_io::read_config(path)?;
+
+    let (_, entry) = config_io::write_config_as(
+        path,
+        &content,
+        &snapshot.version,
+        HistorySource::Restore {
+            from: id.to_string(),
+        },
+    )?;
+
+    // Restoring the latest content again records nothing new; that entry is the result
+    let hash = config_io::content_hash(&content);
+    entry
+        .or_else(|| list(path).into_iter().rev().find(|e| e.hash == hash))
+        .ok_or_else(|| "History is not available".to_string())
+}
+
+#[tauri::command]
+pub async fn set_history_retention(
+    app: AppHandle,
+    retention: RetentionPolicy,
+) -> Result<(), String> {
+    crate::store::save_history_retention(&app, &retention)?;
+    set_retention(retention);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let _ = HISTORY_ROOT
+            .set(std::env::temp_dir().join(format!("mcp-config-history-{}", std::process::id())));
+        let dir = std::env::temp_dir().join(format!(
+            "mcp-config-history-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(path: &Path, content: &str) {
+        let version = config_io::read_config(path).unwrap().version;
+        config_io::write_config(path, content, &version).unwrap();
+    }
+
+    fn entry(id: &str, timestamp: u64) -> HistoryEntry {
+        HistoryEntry {
+            id: id.to_string(),
+            config_path: String::new(),
+            timestamp,
+            source: HistorySource::Manager,
+            hash: id.to_string(),
+            size: 0,
+        }
+    }
+
+    #[test]
+    fn retention_prunes_old_and_surplus_versions() {
+        let dir = temp_dir("retention");
+        let now = now_ms();
+        let mut entries = vec![
+            entry("old", now - 10 * DAY_MS),
+            entry("a", now - 3),
+            entry("b", now - 2),
+            entry("c", now - 1),
+        ];
+        for e in &entries {
+            fs::write(dir.join(format!("{}.json", e.id)), "{}").unwrap();
+        }
+        let policy = RetentionPolicy {
+            max_entries: 2,
+            max_age_days: 5,
+        };
+        apply_retention(&dir, &mut entries, &policy);
+        let ids: Vec<_> = entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["b", "c"]);
+        assert!(!dir.join("old.json").exists() && !dir.join("a.json").exists());
+        assert!(dir.join("b.json").exists());
+
+        // The newest version survives even when it is past the maximum age
+        let mut entries = vec![entry("stale", now - 10 * DAY_MS)];
+        apply_retention(&dir, &mut entries, &policy);
+        assert_eq!(entries.len(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn restoring_records_a_new_version() {
+        let dir = temp_dir("restore");
+        let path = dir.join("claude_desktop_config.json");
+        write(&path, "{ \"mcpServers\": {} }");
+        write(&path, "{ \"mcpServers\": { \"a\": {} } }");
+        let first = list(&path)[0].clone();
+
+        let restored = restore_version(&path, &first.id).unwrap();
+        assert_ne!(restored.id, first.id);
+        assert_eq!(restored.hash, first.hash);
+        assert_eq!(
+            restored.source,
+            HistorySource::Restore {
+                from: first.id.clone()
+            }
+        );
+        assert_eq!(list(&path).len(), 3);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{ \"mcpServers\": {} }");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn diffs_servers_of_the_owning_client() {
+        let dir = temp_dir("diff");
+        let path = dir.join(".vscode").join("mcp.json");
+        write(
+            &path,
+            "{\n  // servers\n  \"servers\": { \"a\": { \"command\": \"a\" } }\n}",
+        );
+        write(
+            &path,
+            "{\n  // servers\n  \"servers\": { \"a\": { \"command\": \"b\" }, \"c\": {} }\n}",
+        );
+        let versions = list(&path);
+
+        let diff = diff_versions(&path, &versions[0].id, Some(&versions[1].id)).unwrap();
+        let servers: Vec<_> = diff
+            .servers
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind.clone()))
+            .collect();
+        assert_eq!(
+            servers,
+            [
+                ("a", config_diff::ChangeKind::Modified),
+                ("c", config_diff::ChangeKind::Added)
+            ]
+        );
+        assert!(diff.settings.is_empty());
+        // Without `to` the file on disk is compared
+        assert!(diff_versions(&path, &versions[1].id, None)
+            .unwrap()
+            .servers
+            .is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
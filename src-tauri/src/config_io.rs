@@ -0,0 +1,267 @@
+use crate::config_history::{self, HistoryEntry, HistorySource};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+// Prefix of the error returned when the file changed since it was read
+pub const CONFLICT_ERROR: &str = "Config conflict";
+
+// Identifies the content of a config file at the time it was read
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileVersion {
+    // SHA-256 of the content, `None` when the file did not exist
+    pub hash: Option<String>,
+    // Modification time in milliseconds since the epoch, informational only
+    pub modified: Option<u64>,
+}
+
+impl PartialEq for FileVersion {
+    // Touching a file without changing it is not a conflict
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigSnapshot {
+    pub path: PathBuf,
+    pub content: Option<String>,
+    pub version: FileVersion,
+}
+
+pub fn content_hash(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn modified_ms(path: &Path) -> Option<u64> {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+}
+
+pub fn read_config(path: &Path) -> Result<ConfigSnapshot, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        Err(e) => return Err(format!("Failed to read config file: {}", e)),
+    };
+
+    Ok(ConfigSnapshot {
+        path: path.to_path_buf(),
+        version: FileVersion {
+            hash: content.as_deref().map(content_hash),
+            modified: modified_ms(path),
+        },
+        content,
+    })
+}
+
+// Advisory lock on a sidecar file, so the config itself can be replaced by rename
+struct ConfigLock {
+    file: File,
+}
+
+impl ConfigLock {
+    fn acquire(path: &Path) -> Result<Self, String> {
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let lock_path = path.with_file_name(format!(".{}.lock", file_name));
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| format!("Failed to open lock file: {}", e))?;
+
+        let deadline = Instant::now() + LOCK_TIMEOUT;
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(ConfigLock { file }),
+                Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    thread::sleep(LOCK_RETRY_INTERVAL);
+                }
+                Err(TryLockError::WouldBlock) => {
+                    return Err(format!("{} is locked by another process", path.display()))
+                }
+                Err(TryLockError::Error(e)) => {
+                    return Err(format!("Failed to lock config file: {}", e))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for ConfigLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+// Write through a temp file in the same directory and rename it over the file, so readers
+// never see it half written
+pub(crate) fn replace_file(path: &Path, content: &str) -> Result<(), String> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| format!("Invalid config path: {}", path.display()))?;
+
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp_path = dir.join(format!(".{}.tmp-{}", file_name, std::process::id()));
+
+    let result = (|| {
+        let mut temp = File::create(&temp_path)?;
+        if let Ok(metadata) = fs::metadata(path) {
+            temp.set_permissions(metadata.permissions())?;
+        }
+        temp.write_all(content.as_bytes())?;
+        temp.sync_all()?;
+        drop(temp);
+
+        fs::rename(&temp_path, path)?;
+
+        // Persist the rename itself
+        #[cfg(unix)]
+        File::open(dir)?.sync_all()?;
+
+        Ok::<(), std::io::Error>(())
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("Failed to write {}: {}", path.display(), e));
+    }
+    Ok(())
+}
+
+// Replace the file if it still matches `expected`, otherwise fail with a conflict
+pub fn write_config(
+    path: &Path,
+    content: &str,
+    expected: &FileVersion,
+) -> Result<FileVersion, String> {
+    write_config_as(path, content, expected, HistorySource::Manager).map(|(version, _)| version)
+}
+
+// Write recorded under `source`, also returning the history entry of the new content.
+// History failures are logged, they never block the write.
+pub fn write_config_as(
+    path: &Path,
+    content: &str,
+    expected: &FileVersion,
+    source: HistorySource,
+) -> Result<(FileVersion, Option<HistoryEntry>), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let _lock = ConfigLock::acquire(path)?;
+
+    let current = read_config(path)?;
+    if &current.version != expected {
+        return Err(format!(
+            "{}: {} was changed on disk since it was read",
+            CONFLICT_ERROR,
+            path.display()
+        ));
+    }
+
+    // Keep the version being replaced if the history has not seen it yet
+    if let Some(previous) = &current.content {
+        if let Err(e) = config_history::record(path, previous, HistorySource::External) {
+            eprintln!("Failed to record config history: {}", e);
+        }
+    }
+    replace_file(path, content)?;
+    let entry = config_history::record(path, content, source).unwrap_or_else(|e| {
+        eprintln!("Failed to record config history: {}", e);
+        None
+    });
+
+    let version = FileVersion {
+        hash: Some(content_hash(content)),
+        modified: modified_ms(path),
+    };
+    Ok((version, entry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_config(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("mcp-config-io-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("config.json")
+    }
+
+    #[test]
+    fn missing_file_is_created() {
+        let path = temp_config("create");
+        let snapshot = read_config(&path).unwrap();
+        assert_eq!(snapshot.content, None);
+        assert_eq!(snapshot.version.hash, None);
+
+        let version = write_config(&path, "{}", &snapshot.version).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{}");
+        assert_eq!(version, read_config(&path).unwrap().version);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn stale_version_is_a_conflict() {
+        let path = temp_config("conflict");
+        fs::write(&path, "{\"a\": 1}").unwrap();
+        let snapshot = read_config(&path).unwrap();
+
+        // Another program writes after the snapshot was taken
+        fs::write(&path, "{\"a\": 2}").unwrap();
+        let err = write_config(&path, "{\"a\": 3}", &snapshot.version).unwrap_err();
+        assert!(err.starts_with(CONFLICT_ERROR), "{}", err);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"a\": 2}");
+
+        // A file that disappeared is a conflict too
+        fs::remove_file(&path).unwrap();
+        let err = write_config(&path, "{\"a\": 3}", &snapshot.version).unwrap_err();
+        assert!(err.starts_with(CONFLICT_ERROR), "{}", err);
+        assert!(!path.exists());
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn writers_wait_for_the_lock() {
+        let path = temp_config("lock");
+        let snapshot = read_config(&path).unwrap();
+
+        let lock = ConfigLock::acquire(&path).unwrap();
+        let writer = {
+            let path = path.clone();
+            let version = snapshot.version.clone();
+            thread::spawn(move || write_config(&path, "{}", &version))
+        };
+        thread::sleep(LOCK_RETRY_INTERVAL * 4);
+        assert!(!path.exists(), "wrote while the lock was held");
+
+        drop(lock);
+        writer.join().unwrap().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{}");
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,275 @@
+use serde::Serialize;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+// An entry both sides changed differently since the base version
+#[derive(Debug, Serialize, Clone)]
+pub struct EntryConflict {
+    pub name: String,
+    pub base: Option<JsonValue>,
+    pub ours: Option<JsonValue>,
+    pub theirs: Option<JsonValue>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ConflictSet {
+    pub servers: Vec<EntryConflict>,
+    // Top-level settings outside the servers section
+    pub settings: Vec<EntryConflict>,
+}
+
+impl ConflictSet {
+    pub fn is_empty(&self) -> bool {
+        self.servers.is_empty() && self.settings.is_empty()
+    }
+}
+
+// Three-way merge of two maps entry by entry; conflicting entries keep their version
+fn merge_maps(
+    base: &JsonMap<String, JsonValue>,
+    ours: &JsonMap<String, JsonValue>,
+    theirs: &JsonMap<String, JsonValue>,
+    skip: Option<&str>,
+    conflicts: &mut Vec<EntryConflict>,
+) -> JsonMap<String, JsonValue> {
+    // Theirs is what is on disk, so its order wins; entries only we added follow
+    let mut names: Vec<&String> = theirs.keys().collect();
+    names.extend(ours.keys().filter(|k| !theirs.contains_key(*k)));
+    names.extend(
+        base.keys()
+            .filter(|k| !theirs.contains_key(*k) && !ours.contains_key(*k)),
+    );
+
+    let mut merged = JsonMap::new();
+    for name in names {
+        if Some(name.as_str()) == skip {
+            continue;
+        }
+        let (b, o, t) = (base.get(name), ours.get(name), theirs.get(name));
+        let value = if o == t || o == b {
+            t
+        } else if t == b {
+            o
+        } else {
+            conflicts.push(EntryConflict {
+                name: name.clone(),
+                base: b.cloned(),
+                ours: o.cloned(),
+                theirs: t.cloned(),
+            });
+            t
+        };
+        if let Some(value) = value {
+            merged.insert(name.clone(), value.clone());
+        }
+    }
+    merged
+}
+
+fn servers_of<'a>(
+    document: &'a JsonMap<String, JsonValue>,
+    servers_key: &str,
+    empty: &'a JsonMap<String, JsonValue>,
+) -> &'a JsonMap<String, JsonValue> {
+    document
+        .get(servers_key)
+        .and_then(|s| s.as_object())
+        .unwrap_or(empty)
+}
+
+// Merge our edit of `base` with the version on disk, server entry by server entry
+pub fn merge_documents(
+    base: &JsonMap<String, JsonValue>,
+    ours: &JsonMap<String, JsonValue>,
+    theirs: &JsonMap<String, JsonValue>,
+    servers_key: &str,
+) -> (JsonMap<String, JsonValue>, ConflictSet) {
+    let mut conflicts = ConflictSet::default();
+    let empty = JsonMap::new();
+
+    let settings = merge_maps(
+        base,
+        ours,
+        theirs,
+        Some(servers_key),
+        &mut conflicts.settings,
+    );
+    let servers = merge_maps(
+        servers_of(base, servers_key, &empty),
+        servers_of(ours, servers_key, &empty),
+        servers_of(theirs, servers_key, &empty),
+        None,
+        &mut conflicts.servers,
+    );
+
+    // Keep the servers section where the file on disk has it
+    let position = theirs
+        .keys()
+        .position(|k| k == servers_key)
+        .unwrap_or(settings.len());
+    let mut merged = JsonMap::new();
+    let mut servers = Some(JsonValue::Object(servers));
+    for (index, (key, value)) in settings.into_iter().enumerate() {
+        if index == position {
+            if let Some(servers) = servers.take() {
+                merged.insert(servers_key.to_string(), servers);
+            }
+        }
+        merged.insert(key, value);
+    }
+    if let Some(servers) = servers {
+        merged.insert(servers_key.to_string(), servers);
+    }
+
+    (merged, conflicts)
+}
+
+// Without a base version nobody can tell which side changed what, so every entry that
+// differs between the two is reported as a conflict
+pub fn compare_documents(
+    ours: &JsonMap<String, JsonValue>,
+    theirs: &JsonMap<String, JsonValue>,
+    servers_key: &str,
+) -> ConflictSet {
+    let compare = |ours: &JsonMap<String, JsonValue>,
+                   theirs: &JsonMap<String, JsonValue>,
+                   skip: Option<&str>| {
+        let mut names: Vec<&String> = theirs.keys().collect();
+        names.extend(ours.keys().filter(|k| !theirs.contains_key(*k)));
+        names
+            .into_iter()
+            .filter(|name| Some(name.as_str()) != skip && ours.get(*name) != theirs.get(*name))
+            .map(|name| EntryConflict {
+                name: name.clone(),
+                base: None,
+                ours: ours.get(name).cloned(),
+                theirs: theirs.get(name).cloned(),
+            })
+            .collect()
+    };
+
+    let empty = JsonMap::new();
+    ConflictSet {
+        servers: compare(
+            servers_of(ours, servers_key, &empty),
+            servers_of(theirs, servers_key, &empty),
+            None,
+        ),
+        settings: compare(ours, theirs, Some(servers_key)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn document(value: JsonValue) -> JsonMap<String, JsonValue> {
+        value.as_object().unwrap().clone()
+    }
+
+    fn names(conflicts: &[EntryConflict]) -> Vec<&str> {
+        conflicts.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn separate_edits_merge() {
+        let base = document(json!({ "mcpServers": { "a": { "command": "x" } }, "locale": "en" }));
+        let ours = document(json!({
+            "mcpServers": { "a": { "command": "y" }, "b": { "command": "b" } },
+            "locale": "en"
+        }));
+        let theirs = document(json!({
+            "locale": "de",
+            "mcpServers": { "a": { "command": "x" }, "c": { "command": "c" } }
+        }));
+
+        let (merged, conflicts) = merge_documents(&base, &ours, &theirs, "mcpServers");
+        assert!(conflicts.is_empty());
+        // The servers section stays where the file on disk has it
+        assert_eq!(
+            JsonValue::Object(merged),
+            json!({
+                "locale": "de",
+                "mcpServers": {
+                    "a": { "command": "y" },
+                    "c": { "command": "c" },
+                    "b": { "command": "b" }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn both_sides_editing_an_entry_conflict() {
+        let base =
+            document(json!({ "mcpServers": { "a": { "command": "x" }, "b": { "command": "b" } } }));
+        let ours = document(
+            json!({ "mcpServers": { "a": { "command": "ours" }, "b": { "command": "b2" } } }),
+        );
+        let theirs = document(json!({ "mcpServers": { "a": { "command": "theirs" } } }));
+
+        let (merged, conflicts) = merge_documents(&base, &ours, &theirs, "mcpServers");
+        // `b` was deleted on disk while we modified it
+        assert_eq!(names(&conflicts.servers), ["a", "b"]);
+        assert_eq!(conflicts.servers[1].theirs, None);
+        assert_eq!(conflicts.servers[1].ours, Some(json!({ "command": "b2" })));
+        // Conflicting entries keep the version on disk
+        assert_eq!(
+            merged["mcpServers"],
+            json!({ "a": { "command": "theirs" } })
+        );
+    }
+
+    #[test]
+    fn identical_changes_do_not_conflict() {
+        let base = document(json!({ "mcpServers": { "a": { "command": "x" } } }));
+        let both = document(json!({
+            "mcpServers": { "a": { "command": "x" }, "new": { "command": "n" } },
+            "theme": "dark"
+        }));
+
+        let (merged, conflicts) = merge_documents(&base, &both, &both, "mcpServers");
+        assert!(conflicts.is_empty());
+        assert_eq!(merged, both);
+
+        // Both deleting the same entry is fine too
+        let deleted = document(json!({ "mcpServers": {} }));
+        let (merged, conflicts) = merge_documents(&base, &deleted, &deleted, "mcpServers");
+        assert!(conflicts.is_empty());
+        assert_eq!(merged["mcpServers"], json!({}));
+    }
+
+    #[test]
+    fn settings_merge_by_key() {
+        let base =
+            document(json!({ "mcpServers": {}, "globalShortcut": "Alt+Space", "locale": "en" }));
+        let ours =
+            document(json!({ "mcpServers": {}, "globalShortcut": "Ctrl+Space", "locale": "fr" }));
+        let theirs = document(
+            json!({ "mcpServers": {}, "globalShortcut": "Alt+Space", "locale": "de", "extra": true }),
+        );
+
+        let (merged, conflicts) = merge_documents(&base, &ours, &theirs, "mcpServers");
+        assert!(conflicts.servers.is_empty());
+        assert_eq!(names(&conflicts.settings), ["locale"]);
+        assert_eq!(merged["globalShortcut"], "Ctrl+Space");
+        assert_eq!(merged["locale"], "de");
+        assert_eq!(merged["extra"], true);
+    }
+
+    #[test]
+    fn without_a_base_every_difference_is_reported() {
+        let ours = document(
+            json!({ "mcpServers": { "a": { "command": "x" }, "b": { "command": "b" } }, "locale": "en" }),
+        );
+        let theirs = document(
+            json!({ "mcpServers": { "a": { "command": "x" }, "c": { "command": "c" } }, "locale": "de" }),
+        );
+
+        let conflicts = compare_documents(&ours, &theirs, "mcpServers");
+        assert_eq!(names(&conflicts.servers), ["c", "b"]);
+        assert!(conflicts.servers.iter().all(|c| c.base.is_none()));
+        assert_eq!(names(&conflicts.settings), ["locale"]);
+        assert!(compare_documents(&ours, &ours, "mcpServers").is_empty());
+    }
+}
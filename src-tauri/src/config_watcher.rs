@@ -0,0 +1,415 @@
+use crate::config_diff::{self, ChangeKind};
+use crate::config_history::{self, HistorySource};
+use crate::config_io;
+use notify::{Event, RecursiveMode, Watcher};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime};
+
+const CHANGE_EVENT: &str = "config_changed";
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+// A config file and where its servers live inside it
+#[derive(Debug, Clone)]
+pub struct WatchedConfig {
+    pub client: String,
+    pub path: PathBuf,
+    pub servers_pointer: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ConfigChangeEvent {
+    pub client: String,
+    pub path: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<String>,
+    // Set when the file no longer parses; the last good state is kept
+    pub error: Option<String>,
+}
+
+// Last state seen for a file, to diff the next change against
+#[derive(Default)]
+struct KnownState {
+    hash: Option<String>,
+    servers: Option<JsonMap<String, JsonValue>>,
+}
+
+pub fn watched_configs() -> Vec<WatchedConfig> {
+    let mut configs = Vec::new();
+
+    for client in crate::clients::CLIENTS {
+        if !client.is_installed() {
+            continue;
+        }
+        if let Ok(path) = client.config_path(None) {
+            configs.push(WatchedConfig {
+                client: client.id().to_string(),
+                path,
+                servers_pointer: client.servers_pointer().to_string(),
+            });
+        }
+    }
+    // Project configs of registered projects
+    for project in crate::projects::registered_paths() {
+        for client in crate::clients::CLIENTS {
+            if let Ok(path) = client.config_path(Some(&project)) {
+                if path.is_file() {
+                    configs.push(WatchedConfig {
+                        client: client.id().to_string(),
+                        path,
+                        servers_pointer: client.servers_pointer().to_string(),
+                    });
+                }
+            }
+        }
+    }
+    // Older VS Code versions keep servers in settings.json
+    for path in crate::process_detection::get_vscode_settings_paths() {
+        configs.push(WatchedConfig {
+            client: "vscode".to_string(),
+            path,
+            servers_pointer: "/mcp/servers".to_string(),
+        });
+    }
+
+    configs
+}
+
+fn parse_servers(
+    content: &str,
+    servers_pointer: &str,
+) -> Result<JsonMap<String, JsonValue>, String> {
+    // Parsed like the client adapters do: comments allowed, an empty file has no servers
+    Ok(crate::clients::parse_document(content)?
+        .pointer(servers_pointer)
+        .and_then(|servers| servers.as_object())
+        .cloned()
+        .unwrap_or_default())
+}
+
+fn read_state(config: &WatchedConfig) -> (KnownState, Option<String>) {
+    match config_io::read_config(&config.path) {
+        Ok(snapshot) => {
+            let parsed = snapshot
+                .content
+                .as_deref()
+                .map(|content| parse_servers(content, &config.servers_pointer))
+                .transpose();
+            match parsed {
+                Ok(servers) => (
+                    KnownState {
+                        hash: snapshot.version.hash,
+                        servers,
+                    },
+                    None,
+                ),
+                Err(e) => (
+                    KnownState {
+                        hash: snapshot.version.hash,
+                        servers: None,
+                    },
+                    Some(e),
+                ),
+            }
+        }
+        Err(e) => (KnownState::default(), Some(e)),
+    }
+}
+
+fn handle_change<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &WatchedConfig,
+    known: &mut HashMap<PathBuf, KnownState>,
+) {
+    let previous = known.remove(&config.path).unwrap_or_default();
+    let (current, error) = read_state(config);
+
+    if current.hash == previous.hash && error.is_none() {
+        known.insert(config.path.clone(), previous);
+        return;
+    }
+
+    let mut event = ConfigChangeEvent {
+        client: config.client.clone(),
+        path: config.path.to_string_lossy().to_string(),
+        added: Vec::new(),
+        removed: Vec::new(),
+        modified: Vec::new(),
+        error,
+    };
+
+    if event.error.is_some() {
+        // Keep diffing against the last state that parsed
+        known.insert(
+            config.path.clone(),
+            KnownState {
+                hash: current.hash,
+                servers: previous.servers,
+            },
+        );
+    } else {
+        for change in config_diff::diff_servers(previous.servers.as_ref(), current.servers.as_ref())
+        {
+            match change.kind {
+                ChangeKind::Added => event.added.push(change.name),
+                ChangeKind::Removed => event.removed.push(change.name),
+                ChangeKind::Modified => event.modified.push(change.name),
+            }
+        }
+
+        // Our own writes are already the latest history entry and are skipped
+        if let Ok(content) = fs::read_to_string(&config.path) {
+            if let Err(e) = config_history::record(&config.path, &content, HistorySource::External)
+            {
+                eprintln!("Failed to record config history: {}", e);
+            }
+        }
+        known.insert(config.path.clone(), current);
+    }
+
+    if let Err(e) = app.emit(CHANGE_EVENT, &event) {
+        eprintln!("Failed to emit config change: {}", e);
+    }
+    if let Err(e) = crate::tray::update_tray_status(app.clone()) {
+        eprintln!("Failed to update tray status: {}", e);
+    }
+}
+
+enum Message {
+    Fs(notify::Result<Event>),
+    // The set of watched configs changed
+    Reload,
+}
+
+// Sender of the running watcher, set once it started
+static WATCHER: OnceCell<Mutex<mpsc::Sender<Message>>> = OnceCell::new();
+
+// Rebuild the watched set, e.g. after projects were added or removed
+pub fn reload() {
+    if let Some(sender) = WATCHER.get().and_then(|s| s.lock().ok()) {
+        let _ = sender.send(Message::Reload);
+    }
+}
+
+// Watched configs by path, with the directories they live in
+struct WatchSet {
+    by_path: HashMap<PathBuf, WatchedConfig>,
+    dirs: HashSet<PathBuf>,
+}
+
+impl WatchSet {
+    fn new(configs: Vec<WatchedConfig>) -> Self {
+        WatchSet {
+            dirs: configs
+                .iter()
+                .filter_map(|c| c.path.parent().map(Path::to_path_buf))
+                .collect(),
+            by_path: configs.into_iter().map(|c| (c.path.clone(), c)).collect(),
+        }
+    }
+
+    // Paths of an event that are watched configs
+    fn relevant(&self, event: notify::Result<Event>) -> Vec<PathBuf> {
+        event
+            .map(|event| {
+                event
+                    .paths
+                    .into_iter()
+                    .filter(|p| self.by_path.contains_key(p))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+// Collect the configs changed in a burst of events. Only events touching a watched config
+// extend the window, so unrelated activity in the same directories cannot delay it forever.
+fn collect_burst(
+    rx: &mpsc::Receiver<Message>,
+    set: &WatchSet,
+    mut pending: HashSet<PathBuf>,
+    debounce: Duration,
+) -> (HashSet<PathBuf>, bool) {
+    let mut deadline = Instant::now() + debounce;
+    let mut reload = false;
+    loop {
+        let wait = deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(wait) {
+            Ok(Message::Fs(event)) => {
+                let paths = set.relevant(event);
+                if !paths.is_empty() {
+                    pending.extend(paths);
+                    deadline = Instant::now() + debounce;
+                }
+            }
+            Ok(Message::Reload) => reload = true,
+            Err(_) => return (pending, reload),
+        }
+    }
+}
+
+// Watch the directories of `set` that `watching` does not cover yet and stop watching the
+// ones no longer needed; editors and our own writes replace files by rename
+fn update_watches(watcher: &mut impl Watcher, watching: &mut HashSet<PathBuf>, set: &WatchSet) {
+    for dir in watching.difference(&set.dirs) {
+        let _ = watcher.unwatch(dir);
+    }
+    watching.retain(|dir| set.dirs.contains(dir));
+    for dir in &set.dirs {
+        if watching.contains(dir) {
+            continue;
+        }
+        match watcher.watch(dir, RecursiveMode::NonRecursive) {
+            Ok(()) => {
+                watching.insert(dir.clone());
+            }
+            Err(e) => eprintln!("Failed to watch {}: {}", dir.display(), e),
+        }
+    }
+}
+
+// Watch every known config file and report external edits
+pub fn start<R: Runtime>(app: AppHandle<R>) {
+    let (tx, rx) = mpsc::channel::<Message>();
+    let fs_tx = tx.clone();
+    if WATCHER.set(Mutex::new(tx)).is_err() {
+        return;
+    }
+
+    thread::spawn(move || {
+        let mut watcher = match notify::recommended_watcher(move |event| {
+            let _ = fs_tx.send(Message::Fs(event));
+        }) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                eprintln!("Failed to create config watcher: {}", e);
+                return;
+            }
+        };
+
+        let mut set = WatchSet::new(watched_configs());
+        let mut known: HashMap<PathBuf, KnownState> = set
+            .by_path
+            .values()
+            .map(|config| (config.path.clone(), read_state(config).0))
+            .collect();
+        let mut watching = HashSet::new();
+        update_watches(&mut watcher, &mut watching, &set);
+
+        while let Ok(message) = rx.recv() {
+            let (pending, reload) = match message {
+                Message::Fs(event) => {
+                    let paths = set.relevant(event);
+                    if paths.is_empty() {
+                        continue;
+                    }
+                    // Wait until the burst of events settles
+                    collect_burst(&rx, &set, paths.into_iter().collect(), DEBOUNCE)
+                }
+                Message::Reload => (HashSet::new(), true),
+            };
+
+            for path in pending {
+                if let Some(config) = set.by_path.get(&path) {
+                    handle_change(&app, config, &mut known);
+                }
+            }
+
+            if reload {
+                set = WatchSet::new(watched_configs());
+                known.retain(|path, _| set.by_path.contains_key(path));
+                for config in set.by_path.values() {
+                    if !known.contains_key(&config.path) {
+                        known.insert(config.path.clone(), read_state(config).0);
+                    }
+                }
+                update_watches(&mut watcher, &mut watching, &set);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::EventKind;
+
+    fn config(path: &str) -> WatchedConfig {
+        WatchedConfig {
+            client: "claude".to_string(),
+            path: PathBuf::from(path),
+            servers_pointer: "/mcpServers".to_string(),
+        }
+    }
+
+    fn event(path: &str) -> Message {
+        Message::Fs(Ok(Event::new(EventKind::Any).add_path(PathBuf::from(path))))
+    }
+
+    #[test]
+    fn only_watched_paths_are_relevant() {
+        let set = WatchSet::new(vec![config("/home/a/.cursor/mcp.json")]);
+        assert_eq!(set.dirs, HashSet::from([PathBuf::from("/home/a/.cursor")]));
+
+        let Message::Fs(other) = event("/home/a/.cursor/state.db") else {
+            unreachable!()
+        };
+        assert!(set.relevant(other).is_empty());
+        let Message::Fs(watched) = event("/home/a/.cursor/mcp.json") else {
+            unreachable!()
+        };
+        assert_eq!(
+            set.relevant(watched),
+            [PathBuf::from("/home/a/.cursor/mcp.json")]
+        );
+    }
+
+    #[test]
+    fn unrelated_events_do_not_extend_the_debounce() {
+        let set = WatchSet::new(vec![config("/home/a/.cursor/mcp.json")]);
+        let (tx, rx) = mpsc::channel();
+        let debounce = Duration::from_millis(50);
+
+        // A busy directory keeps producing events for much longer than the window
+        let noise = thread::spawn(move || {
+            for _ in 0..40 {
+                if tx.send(event("/home/a/.cursor/state.db")).is_err() {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+            tx
+        });
+
+        let started = Instant::now();
+        let pending = HashSet::from([PathBuf::from("/home/a/.cursor/mcp.json")]);
+        let (pending, reload) = collect_burst(&rx, &set, pending, debounce);
+        assert!(started.elapsed() < Duration::from_millis(500));
+        assert_eq!(pending.len(), 1);
+        assert!(!reload);
+
+        let tx = noise.join().unwrap();
+        tx.send(Message::Reload).unwrap();
+        let (_, reload) = collect_burst(&rx, &set, HashSet::new(), debounce);
+        assert!(reload);
+    }
+
+    #[test]
+    fn parses_servers_from_commented_configs() {
+        let content =
+            "{\n  // servers\n  \"mcp\": { \"servers\": { \"a\": { \"command\": \"x\" } } }\n}";
+        let servers = parse_servers(content, "/mcp/servers").unwrap();
+        assert_eq!(servers.keys().collect::<Vec<_>>(), ["a"]);
+        assert!(parse_servers(content, "/mcpServers").unwrap().is_empty());
+        assert!(parse_servers("{ \"mcpServers\": ", "/mcpServers").is_err());
+        assert!(parse_servers(" \n", "/mcpServers").unwrap().is_empty());
+    }
+}
@@ -1,6 +1,11 @@
+use crate::server_entry::Severity;
 use serde::Serialize;
 use std::env;
-use std::path::PathBuf;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 use tauri::AppHandle;
 use tauri_plugin_shell::ShellExt;
 
@@ -184,3 +189,354 @@ pub fn check_claude_installed() -> ClaudeCheckResult {
         install_url: "https://claude.ai/download".to_string(),
     }
 }
+
+// Locate an executable the way a shell would, on this process's PATH
+pub fn resolve_command(command: &str) -> Option<PathBuf> {
+    resolve_command_in(command, &env::var_os("PATH")?)
+}
+
+// Look a command up on the given PATH value
+pub fn resolve_command_in(command: &str, path: &OsStr) -> Option<PathBuf> {
+    let extensions: Vec<String> = if cfg!(windows) {
+        env::var("PATHEXT")
+            .unwrap_or_else(|_| ".EXE;.CMD;.BAT;.COM".to_string())
+            .split(';')
+            .map(|ext| ext.to_lowercase())
+            .chain([String::new()])
+            .collect()
+    } else {
+        vec![String::new()]
+    };
+    let candidates = |base: PathBuf| {
+        extensions
+            .iter()
+            .map(move |ext| PathBuf::from(format!("{}{}", base.to_string_lossy(), ext)))
+    };
+
+    // Commands with a directory part are not looked up on PATH
+    if command.contains('/') || command.contains('\\') {
+        return candidates(PathBuf::from(command)).find(|path| path.is_file());
+    }
+
+    env::split_paths(path)
+        .flat_map(|dir| candidates(dir.join(command)))
+        .find(|path| path.is_file())
+}
+
+// PATH a client spawns its servers with. Claude Desktop on macOS is started by launchd
+// and does not see the login shell's PATH this app picks up at startup.
+pub fn client_search_path(client: &str) -> Option<OsString> {
+    if cfg!(target_os = "macos") && client == "claude" {
+        return Some(OsString::from("/usr/bin:/bin:/usr/sbin:/sbin"));
+    }
+    env::var_os("PATH")
+}
+
+// What a launcher command needs installed, and the oldest version servers run with
+struct RuntimeRule {
+    commands: &'static [&'static str],
+    name: &'static str,
+    // The executable whose `--version` is checked; None checks the command itself
+    probe: Option<&'static str>,
+    min_version: Option<[u32; 3]>,
+    install_url: &'static str,
+}
+
+const RUNTIME_RULES: &[RuntimeRule] = &[
+    RuntimeRule {
+        commands: &["npx", "node"],
+        name: "Node.js",
+        probe: Some("node"),
+        min_version: Some([18, 0, 0]),
+        install_url: "https://nodejs.org/",
+    },
+    RuntimeRule {
+        commands: &["uvx", "uv"],
+        name: "uv",
+        probe: Some("uv"),
+        min_version: Some([0, 4, 0]),
+        install_url: "https://docs.astral.sh/uv/getting-started/installation/",
+    },
+    RuntimeRule {
+        commands: &["python", "python3"],
+        name: "Python",
+        probe: None,
+        min_version: Some([3, 10, 0]),
+        install_url: "https://www.python.org/downloads/",
+    },
+    RuntimeRule {
+        commands: &["docker"],
+        name: "Docker",
+        probe: None,
+        min_version: None,
+        install_url: "https://docs.docker.com/get-docker/",
+    },
+    RuntimeRule {
+        commands: &["bun", "bunx"],
+        name: "Bun",
+        probe: Some("bun"),
+        min_version: Some([1, 0, 0]),
+        install_url: "https://bun.sh/",
+    },
+    RuntimeRule {
+        commands: &["deno"],
+        name: "Deno",
+        probe: None,
+        min_version: Some([1, 40, 0]),
+        install_url: "https://deno.com/",
+    },
+];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PreflightIssue {
+    // The client that cannot start the server, when only some can
+    pub client: Option<String>,
+    pub severity: Severity,
+    pub message: String,
+    pub remediation_url: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PreflightResult {
+    pub command: String,
+    // The runtime the command needs, or the command itself for a direct binary
+    pub runtime: String,
+    pub path: Option<String>,
+    pub version: Option<String>,
+    pub min_version: Option<String>,
+    // Installing is refused while any issue is an error
+    pub blocked: bool,
+    pub issues: Vec<PreflightIssue>,
+}
+
+// The first `x.y[.z]` in a `--version` output, e.g. "v20.11.1" or "Python 3.12.2"
+fn parse_version(output: &str) -> Option<[u32; 3]> {
+    output.split_whitespace().find_map(|word| {
+        let word = word.trim_start_matches('v');
+        let mut parts = word
+            .split(|c: char| !c.is_ascii_digit())
+            .take_while(|part| !part.is_empty())
+            .map(|part| part.parse::<u32>().ok());
+        let major = parts.next()??;
+        let minor = parts.next()??;
+        let patch = parts.next().flatten().unwrap_or(0);
+        Some([major, minor, patch])
+    })
+}
+
+fn format_version(version: [u32; 3]) -> String {
+    format!("{}.{}.{}", version[0], version[1], version[2])
+}
+
+// A runtime whose `--version` hangs, e.g. a shim waiting for input, is treated as missing
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn probe_version(executable: &Path) -> Option<(String, Option<[u32; 3]>)> {
+    probe_version_within(executable, PROBE_TIMEOUT)
+}
+
+fn probe_version_within(
+    executable: &Path,
+    timeout: Duration,
+) -> Option<(String, Option<[u32; 3]>)> {
+    let mut child = Command::new(executable)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+    let deadline = Instant::now() + timeout;
+    while child.try_wait().ok()?.is_none() {
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    let output = child.wait_with_output().ok()?;
+    let text = if output.stdout.is_empty() {
+        String::from_utf8_lossy(&output.stderr).to_string()
+    } else {
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    let line = text.lines().next().unwrap_or_default().trim().to_string();
+    let parsed = parse_version(&line);
+    Some((line, parsed))
+}
+
+// Check that the runtime `command` needs is installed, new enough and visible to every
+// target client. Runs commands, so call it off the async runtime.
+pub fn preflight(command: &str, clients: &[(&str, &str)]) -> PreflightResult {
+    let stem = Path::new(command)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let rule = RUNTIME_RULES
+        .iter()
+        .find(|rule| rule.commands.contains(&stem.as_str()));
+    let install_url = rule.map(|r| r.install_url.to_string());
+    let mut result = PreflightResult {
+        command: command.to_string(),
+        runtime: rule.map_or_else(|| command.to_string(), |r| r.name.to_string()),
+        path: None,
+        version: None,
+        min_version: rule.and_then(|r| r.min_version).map(format_version),
+        blocked: false,
+        issues: Vec::new(),
+    };
+    let mut issues = Vec::new();
+    let mut issue = |client: Option<String>, severity, message| {
+        issues.push(PreflightIssue {
+            client,
+            severity,
+            message,
+            remediation_url: install_url.clone(),
+        });
+    };
+
+    let Some(resolved) = resolve_command(command) else {
+        let message = match rule {
+            Some(rule) => format!(
+                "{} is not installed: `{}` was not found",
+                rule.name, command
+            ),
+            None => format!("`{}` was not found", command),
+        };
+        issue(None, Severity::Error, message);
+        result.issues = issues;
+        result.blocked = true;
+        return result;
+    };
+
+    // Launchers share a directory with the runtime they start
+    if let Some(rule) = rule {
+        let probe = match rule.probe {
+            Some(probe) => resolved
+                .parent()
+                .and_then(|dir| resolve_command(&dir.join(probe).to_string_lossy()))
+                .or_else(|| resolve_command(probe)),
+            None => Some(resolved.clone()),
+        };
+        match probe.as_deref().and_then(probe_version) {
+            Some((line, Some(version))) => {
+                result.version = Some(format_version(version));
+                if let Some(min) = rule.min_version.filter(|min| version < *min) {
+                    issue(
+                        None,
+                        Severity::Error,
+                        format!(
+                            "{} {} is too old; servers need {} or newer",
+                            rule.name,
+                            line,
+                            format_version(min)
+                        ),
+                    );
+                }
+            }
+            Some((line, None)) => issue(
+                None,
+                Severity::Warning,
+                format!("Could not read the {} version from '{}'", rule.name, line),
+            ),
+            None => issue(
+                None,
+                Severity::Warning,
+                format!("Could not run {} to check its version", rule.name),
+            ),
+        }
+    }
+
+    // The config still works once the command is given by its full path, so this only warns
+    for (client, client_name) in clients {
+        let visible = client_search_path(client)
+            .is_some_and(|path| resolve_command_in(command, &path).is_some());
+        if !visible {
+            issue(
+                Some(client.to_string()),
+                Severity::Warning,
+                format!(
+                    "{} is not on the PATH {} starts servers with; use the full path {}",
+                    command,
+                    client_name,
+                    resolved.display()
+                ),
+            );
+        }
+    }
+
+    result.path = Some(resolved.to_string_lossy().to_string());
+    result.blocked = issues.iter().any(|i| i.severity == Severity::Error);
+    result.issues = issues;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_version_banners() {
+        assert_eq!(parse_version("v20.11.1"), Some([20, 11, 1]));
+        assert_eq!(parse_version("Python 3.12.2"), Some([3, 12, 2]));
+        assert_eq!(
+            parse_version("uv 0.4.18 (7b55e9790 2024-10-01)"),
+            Some([0, 4, 18])
+        );
+        assert_eq!(parse_version("Docker version 27.3"), Some([27, 3, 0]));
+        assert_eq!(parse_version("not installed"), None);
+    }
+
+    // A directory of its own per test, so parallel tests never share scripts
+    #[cfg(unix)]
+    fn script_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("mcp-env-check-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // The file is closed before it is made executable and run, which avoids ETXTBSY
+    #[cfg(unix)]
+    fn write_script(dir: &Path, name: &str, body: &str) -> PathBuf {
+        use std::io::Write;
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = dir.join(name);
+        let mut file = std::fs::File::create(&script).unwrap();
+        file.write_all(body.as_bytes()).unwrap();
+        file.sync_all().unwrap();
+        drop(file);
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        script
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hanging_version_probes_time_out() {
+        let dir = script_dir("hangs");
+        let script = write_script(&dir, "hangs", "#!/bin/sh\nsleep 30\n");
+
+        let started = Instant::now();
+        assert_eq!(
+            probe_version_within(&script, Duration::from_millis(200)),
+            None
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn version_probes_read_the_output() {
+        let dir = script_dir("prints");
+        let script = write_script(&dir, "prints", "#!/bin/sh\necho 'tool v1.2.3'\n");
+
+        assert_eq!(
+            probe_version(&script),
+            Some(("tool v1.2.3".to_string(), Some([1, 2, 3])))
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,388 @@
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+// Formatting of an existing JSON file, reused when writing it back
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonStyle {
+    pub indent: String,
+    pub crlf: bool,
+    pub trailing_newline: bool,
+}
+
+impl Default for JsonStyle {
+    fn default() -> Self {
+        JsonStyle {
+            indent: "  ".to_string(),
+            crlf: false,
+            trailing_newline: false,
+        }
+    }
+}
+
+impl JsonStyle {
+    pub fn detect(text: &str) -> Self {
+        // The first indented line holds exactly one level of indentation
+        let indent = text
+            .lines()
+            .skip(1)
+            .map(|line| {
+                let trimmed = line.trim_start_matches([' ', '\t']);
+                &line[..line.len() - trimmed.len()]
+            })
+            .find(|indent| !indent.is_empty())
+            .unwrap_or("  ")
+            .to_string();
+
+        JsonStyle {
+            indent,
+            crlf: text.contains("\r\n"),
+            trailing_newline: text.ends_with('\n'),
+        }
+    }
+}
+
+pub fn to_string_styled<T: Serialize>(value: &T, style: &JsonStyle) -> Result<String, String> {
+    let mut buffer = Vec::new();
+    let formatter = PrettyFormatter::with_indent(style.indent.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+    value
+        .serialize(&mut serializer)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    let mut text =
+        String::from_utf8(buffer).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    if style.trailing_newline {
+        text.push('\n');
+    }
+    if style.crlf {
+        text = text.replace('\n', "\r\n");
+    }
+    Ok(text)
+}
+
+// Byte ranges of a JSON or JSONC text, so parts of it can be replaced in place
+enum Node {
+    Object(ObjectNode),
+    // Arrays and scalars are only ever replaced as a whole
+    Other,
+}
+
+struct ObjectNode {
+    open: usize,
+    close: usize,
+    members: Vec<Member>,
+    // Start of the text between the last member and `}`
+    tail: usize,
+}
+
+struct Member {
+    key: String,
+    // Start of the whitespace and comments before the key
+    lead: usize,
+    value_start: usize,
+    value_end: usize,
+    value: Node,
+    comma: Option<usize>,
+}
+
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    fn error(&self, what: &str) -> String {
+        format!("Invalid JSON at byte {}: {}", self.pos, what)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_trivia(&mut self) -> Result<(), String> {
+        loop {
+            let rest = &self.text[self.pos..];
+            match self.peek() {
+                Some(b' ' | b'\t' | b'\r' | b'\n') => self.pos += 1,
+                _ if rest.starts_with("//") => {
+                    self.pos += rest.find('\n').unwrap_or(rest.len());
+                }
+                _ if rest.starts_with("/*") => {
+                    let end = rest[2..]
+                        .find("*/")
+                        .ok_or_else(|| self.error("unterminated comment"))?;
+                    self.pos += end + 4;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<(), String> {
+        self.pos += 1;
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(b'\\') => self.pos += 2,
+                Some(_) => self.pos += 1,
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    // Scan one value, returning where it starts; the scanner stops right after it
+    fn value(&mut self) -> Result<(usize, Node), String> {
+        self.skip_trivia()?;
+        let start = self.pos;
+        let node = match self.peek() {
+            Some(b'{') => Node::Object(self.object()?),
+            Some(b'[') => {
+                self.array()?;
+                Node::Other
+            }
+            Some(b'"') => {
+                self.string()?;
+                Node::Other
+            }
+            Some(_) => {
+                while self
+                    .peek()
+                    .is_some_and(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'+' | b'.'))
+                {
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    return Err(self.error("unexpected character"));
+                }
+                Node::Other
+            }
+            None => return Err(self.error("unexpected end")),
+        };
+        Ok((start, node))
+    }
+
+    fn array(&mut self) -> Result<(), String> {
+        self.pos += 1;
+        loop {
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(b',') => self.pos += 1,
+                _ => {
+                    self.value()?;
+                }
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<ObjectNode, String> {
+        let open = self.pos;
+        self.pos += 1;
+        let mut members = Vec::new();
+        let mut tail = self.pos;
+        loop {
+            let lead = tail;
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b'}') => {
+                    let close = self.pos;
+                    self.pos += 1;
+                    return Ok(ObjectNode {
+                        open,
+                        close,
+                        members,
+                        tail,
+                    });
+                }
+                Some(b'"') => {}
+                _ => return Err(self.error("expected a key")),
+            }
+
+            let key_start = self.pos;
+            self.string()?;
+            let key = serde_json::from_str(&self.text[key_start..self.pos])
+                .map_err(|e| self.error(&e.to_string()))?;
+            self.skip_trivia()?;
+            if self.peek() != Some(b':') {
+                return Err(self.error("expected ':'"));
+            }
+            self.pos += 1;
+            let (value_start, value) = self.value()?;
+            let value_end = self.pos;
+
+            self.skip_trivia()?;
+            let comma = (self.peek() == Some(b',')).then_some(self.pos);
+            tail = match comma {
+                Some(comma) => {
+                    self.pos = comma + 1;
+                    self.pos
+                }
+                None => value_end,
+            };
+            members.push(Member {
+                key,
+                lead,
+                value_start,
+                value_end,
+                value,
+                comma,
+            });
+        }
+    }
+}
+
+struct Editor<'a> {
+    text: &'a str,
+    style: JsonStyle,
+}
+
+impl Editor<'_> {
+    // Leading whitespace of the line `pos` is on
+    fn line_indent(&self, pos: usize) -> &str {
+        let start = self.text[..pos].rfind('\n').map_or(0, |i| i + 1);
+        let line = &self.text[start..];
+        &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+    }
+
+    fn render(&self, value: &JsonValue, indent: &str) -> Result<String, String> {
+        let style = JsonStyle {
+            trailing_newline: false,
+            ..self.style.clone()
+        };
+        Ok(to_string_styled(value, &style)?.replace('\n', &format!("\n{}", indent)))
+    }
+
+    fn value(
+        &self,
+        start: usize,
+        end: usize,
+        node: &Node,
+        new: &JsonValue,
+    ) -> Result<String, String> {
+        let slice = &self.text[start..end];
+        let old = serde_json::from_str::<JsonValue>(
+            &crate::process_detection::strip_json_comments(slice),
+        );
+        if old.is_ok_and(|old| &old == new) {
+            return Ok(slice.to_string());
+        }
+        match (node, new) {
+            // Objects written on one line are rendered again as a whole
+            (Node::Object(object), JsonValue::Object(map))
+                if self.text[object.open..object.close].contains('\n') =>
+            {
+                self.object(object, map)
+            }
+            _ => self.render(new, self.line_indent(start)),
+        }
+    }
+
+    // Unchanged members keep their text, changed ones are replaced, missing ones dropped
+    // together with the comments before them and new ones inserted where `map` has them
+    fn object(
+        &self,
+        object: &ObjectNode,
+        map: &JsonMap<String, JsonValue>,
+    ) -> Result<String, String> {
+        let newline = if self.style.crlf { "\r\n" } else { "\n" };
+        let indent = match object.members.first() {
+            Some(first) => self.line_indent(first.value_start).to_string(),
+            None => format!("{}{}", self.line_indent(object.open), self.style.indent),
+        };
+
+        let mut parts = Vec::new();
+        for (key, new) in map {
+            match object.members.iter().find(|member| &member.key == key) {
+                Some(member) => {
+                    let value =
+                        self.value(member.value_start, member.value_end, &member.value, new)?;
+                    let trail = member
+                        .comma
+                        .map_or("", |comma| &self.text[member.value_end..comma]);
+                    parts.push(format!(
+                        "{}{}{}",
+                        &self.text[member.lead..member.value_start],
+                        value,
+                        trail
+                    ));
+                }
+                None => {
+                    let key = serde_json::to_string(key)
+                        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+                    parts.push(format!(
+                        "{}{}{}: {}",
+                        newline,
+                        indent,
+                        key,
+                        self.render(new, &indent)?
+                    ));
+                }
+            }
+        }
+
+        let trailing_comma = object
+            .members
+            .last()
+            .is_some_and(|member| member.comma.is_some());
+        let mut text = format!("{{{}", parts.join(","));
+        if trailing_comma && !parts.is_empty() {
+            text.push(',');
+        }
+        text.push_str(&self.text[object.tail..object.close]);
+        text.push('}');
+        Ok(text)
+    }
+}
+
+// Rewrite `original` so it holds `value`, touching only the text of the members that
+// changed. Formatting and comments elsewhere stay byte for byte.
+pub fn edit_over(original: &str, value: &JsonValue) -> Result<String, String> {
+    let mut scanner = Scanner {
+        text: original,
+        pos: 0,
+    };
+    let (start, node) = scanner.value()?;
+    let end = scanner.pos;
+    scanner.skip_trivia()?;
+    if scanner.pos < original.len() {
+        return Err(scanner.error("unexpected content after the document"));
+    }
+
+    let editor = Editor {
+        text: original,
+        style: JsonStyle::detect(original),
+    };
+    let edited = editor.value(start, end, &node, value)?;
+    Ok(format!(
+        "{}{}{}",
+        &original[..start],
+        edited,
+        &original[end..]
+    ))
+}
+
+// Serialize `value` over the text it was read from, keeping its key order and formatting
+pub fn render_over<T: Serialize>(original: Option<&str>, value: &T) -> Result<String, String> {
+    let updated = match serde_json::to_value(value)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?
+    {
+        JsonValue::Object(map) => JsonValue::Object(map),
+        _ => return Err("Config must be a JSON object".to_string()),
+    };
+
+    let Some(original) = original else {
+        return to_string_styled(&updated, &JsonStyle::default());
+    };
+    match serde_json::from_str::<JsonValue>(original) {
+        Ok(JsonValue::Object(_)) => edit_over(original, &updated),
+        // Nothing worth preserving in an unreadable file
+        _ => to_string_styled(&updated, &JsonStyle::detect(original)),
+    }
+}
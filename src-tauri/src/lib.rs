@@ -1,9 +1,32 @@
+mod app_control;
+mod bundles;
 mod claude_config;
+mod client_requests;
+mod clients;
+mod config_diff;
+mod config_history;
+mod config_io;
+mod config_merge;
+mod config_watcher;
 mod env_check;
+mod json_edit;
+mod lint;
+mod lockfile;
+mod mcp_client;
 mod mcp_runner;
 mod mcp_servers;
+mod plan;
 mod process_detection;
+mod profiles;
+mod projects;
+mod registries;
+mod server_entry;
+mod server_json;
 mod store;
+mod sync;
+mod template_cache;
+mod template_params;
+mod transaction;
 mod tray;
 
 use claude_config::{
@@ -49,9 +72,31 @@ pub fn setup_app<R: Runtime>(app: &tauri::App<R>) -> Result<(), Box<dyn std::err
         eprintln!("Failed to create tray: {}", e);
     }
 
+    // 监听配置文件的外部修改
+    config_watcher::start(app_handle.clone());
+
     Ok(())
 }
 
+// 配置历史保存在应用数据目录下
+fn init_config_history(app: &AppHandle) {
+    match app.path().app_data_dir() {
+        Ok(dir) => {
+            let retention = store::get_history_retention(app).unwrap_or_default();
+            config_history::init(dir.join("config_history"), retention);
+        }
+        Err(e) => eprintln!("Config history disabled: {}", e),
+    }
+}
+
+// 模板缓存保存在应用缓存目录下
+fn init_template_cache(app: &AppHandle) {
+    match app.path().app_cache_dir() {
+        Ok(dir) => template_cache::init(dir.join("templates")),
+        Err(e) => eprintln!("Template cache disabled: {}", e),
+    }
+}
+
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
@@ -59,6 +104,9 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_store::Builder::default().build())
         .setup(|app| {
+            init_config_history(app.handle());
+            init_template_cache(app.handle());
+            projects::init(app.handle());
             setup_app(app)?;
             Ok(())
         })
@@ -69,9 +117,20 @@ pub fn run() {
             get_uv_path,
             get_claude_config,
             get_config_path,
+            claude_config::get_claude_config_version,
+            config_history::list_config_history,
+            config_history::get_config_history_version,
+            config_history::diff_config_versions,
+            config_history::restore_config_version,
+            config_history::set_history_retention,
             check_claude_installed,
             get_mcp_server_templates,
+            registries::list_template_registries,
+            registries::save_template_registries,
             install_mcp_server,
+            mcp_servers::install_cached_template,
+            server_json::read_server_json,
+            server_json::install_server_json,
             uninstall_mcp_server,
             is_mcp_server_installed,
             restore_config_backup,
@@ -81,10 +140,48 @@ pub fn run() {
             stop_server,
             select_folder,
             update_mcp_server_config,
+            mcp_servers::plan_install_mcp_server,
+            mcp_servers::preflight_mcp_server,
+            lockfile::get_package_lock,
+            lockfile::pin_server_versions,
+            lockfile::verify_package_lock,
+            mcp_servers::plan_uninstall_mcp_server,
+            mcp_servers::plan_update_mcp_server_config,
+            mcp_servers::disable_mcp_server,
+            mcp_servers::enable_mcp_server,
+            store::list_disabled_servers,
+            profiles::list_profiles,
+            profiles::save_profile,
+            profiles::delete_profile,
+            profiles::activate_profile,
+            projects::list_projects,
+            projects::add_project,
+            projects::remove_project,
+            projects::get_project_scan_settings,
+            projects::set_project_scan_settings,
+            projects::scan_projects,
+            projects::start_project_server,
+            server_entry::validate_mcp_server_config,
+            lint::lint_configs,
+            lint::apply_lint_fixes,
+            transaction::get_transaction_journal,
+            transaction::undo_transaction,
+            transaction::redo_transaction,
             install_environment,
             update_global_shortcut_command,
             restart_claude_app,
             restart_vscode_app,
+            app_control::restart_client_app,
+            clients::list_mcp_clients,
+            clients::get_client_servers,
+            clients::restart_client,
+            sync::get_sync_status,
+            sync::push_server_definitions,
+            sync::pull_server_definition,
+            bundles::export_server_bundle,
+            bundles::read_server_bundle,
+            bundles::preview_bundle_import,
+            bundles::import_server_bundle,
             detect_running_mcp_servers,
             detect_copilot_mcp_servers,
             get_all_mcp_servers,
@@ -93,6 +190,11 @@ pub fn run() {
             store::save_installed_server,
             store::get_installed_server,
             store::remove_installed_server,
+            store::get_server_roots,
+            store::remove_server_root,
+            store::get_server_callback_mode,
+            store::set_server_callback_mode,
+            client_requests::respond_client_request,
         ])
         .build(tauri::generate_context!())
         .expect("error while running tauri application")
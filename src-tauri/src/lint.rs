@@ -0,0 +1,625 @@
+use crate::claude_config::ServerConfig;
+use crate::clients::{self, ClientAdapter};
+use crate::env_check;
+use crate::server_entry::{self, ServerEntry, Severity};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tauri::AppHandle;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LintRule {
+    CommandNotFound,
+    MissingPath,
+    UnreadablePath,
+    EmptyEnv,
+    DuplicateServer,
+    RelativePath,
+    ToolCollision,
+}
+
+// An edit to one server entry; `from` guards against applying a fix to a changed entry
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum LintFix {
+    SetCommand {
+        from: String,
+        to: String,
+    },
+    SetArg {
+        index: usize,
+        from: String,
+        to: String,
+    },
+    SetCwd {
+        from: String,
+        to: String,
+    },
+    RemoveServer,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LintFinding {
+    pub client: String,
+    pub project: Option<String>,
+    pub config_path: String,
+    pub server: String,
+    // JSON pointer to the offending value
+    pub path: String,
+    pub rule: LintRule,
+    pub severity: Severity,
+    pub message: String,
+    pub fix: Option<LintFix>,
+    pub fix_description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FixReport {
+    pub applied: usize,
+    pub skipped: Vec<String>,
+}
+
+// Where a path-like value sits: the entry's command, one of its args, or its cwd
+#[derive(Clone, Copy)]
+enum Slot {
+    Command,
+    Arg(usize),
+    Cwd,
+}
+
+// Findings for one config file
+struct Linter<'a> {
+    client: &'static dyn ClientAdapter,
+    project: Option<&'a Path>,
+    config_path: String,
+    findings: Vec<LintFinding>,
+}
+
+impl Linter<'_> {
+    fn push(
+        &mut self,
+        server: &str,
+        path: String,
+        rule: LintRule,
+        severity: Severity,
+        message: String,
+        fix: Option<(LintFix, String)>,
+    ) {
+        let (fix, fix_description) = match fix {
+            Some((fix, description)) => (Some(fix), Some(description)),
+            None => (None, None),
+        };
+        self.findings.push(LintFinding {
+            client: self.client.id().to_string(),
+            project: self.project.map(|p| p.to_string_lossy().to_string()),
+            config_path: self.config_path.clone(),
+            server: server.to_string(),
+            path,
+            rule,
+            severity,
+            message,
+            fix,
+            fix_description,
+        });
+    }
+
+    fn lint_server(&mut self, name: &str, entry: &ServerEntry) {
+        let ServerEntry::Stdio {
+            command,
+            args,
+            env,
+            cwd,
+        } = entry
+        else {
+            return;
+        };
+        let base = server_entry::entry_path(self.client.servers_pointer(), name);
+
+        // Relative paths resolve against an absolute cwd, else the project directory
+        let base_dir = match (cwd.as_deref().map(Path::new), self.project) {
+            (Some(cwd), _) if cwd.is_absolute() => Some(cwd.to_path_buf()),
+            (Some(cwd), Some(project)) if !cwd.starts_with("~") => Some(project.join(cwd)),
+            (_, project) => project.map(Path::to_path_buf),
+        };
+
+        self.check_command(name, &base, command, env.get("PATH"), base_dir.as_deref());
+        for (index, arg) in args.iter().enumerate() {
+            self.check_path(
+                name,
+                format!("{}/args/{}", base, index),
+                Slot::Arg(index),
+                arg,
+                base_dir.as_deref(),
+            );
+        }
+        if let Some(cwd) = cwd {
+            self.check_path(name, format!("{}/cwd", base), Slot::Cwd, cwd, self.project);
+        }
+
+        for (key, value) in env {
+            let message = if value.trim().is_empty() {
+                format!("Environment variable {} is empty", key)
+            } else if value.starts_with("{{") && value.ends_with("}}") {
+                format!("Environment variable {} is an unfilled placeholder", key)
+            } else {
+                continue;
+            };
+            self.push(
+                name,
+                format!("{}/env/{}", base, crate::config_diff::escape_pointer(key)),
+                LintRule::EmptyEnv,
+                Severity::Warning,
+                message,
+                None,
+            );
+        }
+    }
+
+    fn check_command(
+        &mut self,
+        name: &str,
+        base: &str,
+        command: &str,
+        env_path: Option<&String>,
+        base_dir: Option<&Path>,
+    ) {
+        let path = format!("{}/command", base);
+        if command.contains('/') || command.contains('\\') {
+            self.check_path(name, path, Slot::Command, command, base_dir);
+            return;
+        }
+
+        let search_path = env_path
+            .map(OsString::from)
+            .or_else(|| env_check::client_search_path(self.client.id()))
+            .unwrap_or_default();
+        if env_check::resolve_command_in(command, &search_path).is_some() {
+            return;
+        }
+
+        // Found on the login shell's PATH but not the client's: point at it directly
+        let fix = env_check::resolve_command(command).map(|found| {
+            let to = found.to_string_lossy().to_string();
+            (
+                LintFix::SetCommand {
+                    from: command.to_string(),
+                    to: to.clone(),
+                },
+                format!("Use the full path {}", to),
+            )
+        });
+        let message = if fix.is_some() {
+            format!(
+                "{} is not on the PATH {} starts servers with",
+                command,
+                self.client.name()
+            )
+        } else {
+            format!("Command {} was not found on the PATH", command)
+        };
+        self.push(
+            name,
+            path,
+            LintRule::CommandNotFound,
+            Severity::Error,
+            message,
+            fix,
+        );
+    }
+
+    fn check_path(
+        &mut self,
+        name: &str,
+        path: String,
+        slot: Slot,
+        value: &str,
+        base_dir: Option<&Path>,
+    ) {
+        let Some((prefix, candidate, suffix)) = split_path_value(value) else {
+            return;
+        };
+        let rewrite =
+            |resolved: &Path| format!("{}{}{}", prefix, resolved.to_string_lossy(), suffix);
+
+        let resolved = if let Some(rest) = candidate
+            .strip_prefix("~/")
+            .or_else(|| candidate.strip_prefix("~\\"))
+        {
+            let Ok(home) = clients::home_dir() else {
+                return;
+            };
+            let resolved = home.join(rest);
+            let to = rewrite(&resolved);
+            self.push(
+                name,
+                path.clone(),
+                LintRule::RelativePath,
+                Severity::Warning,
+                format!("~ in {} is not expanded without a shell", value),
+                Some((slot_fix(slot, value, &to), format!("Expand to {}", to))),
+            );
+            resolved
+        } else if Path::new(candidate).is_absolute() {
+            PathBuf::from(candidate)
+        } else {
+            match base_dir {
+                Some(dir) => {
+                    let resolved = normalize(&dir.join(candidate));
+                    let to = rewrite(&resolved);
+                    self.push(
+                        name,
+                        path.clone(),
+                        LintRule::RelativePath,
+                        Severity::Warning,
+                        format!("{} depends on the directory the server starts in", value),
+                        Some((slot_fix(slot, value, &to), format!("Use {}", to))),
+                    );
+                    resolved
+                }
+                None => {
+                    self.push(
+                        name,
+                        path,
+                        LintRule::RelativePath,
+                        Severity::Warning,
+                        format!("{} depends on the directory the server starts in", value),
+                        None,
+                    );
+                    return;
+                }
+            }
+        };
+
+        let readable = match fs::metadata(&resolved) {
+            Err(_) => {
+                self.push(
+                    name,
+                    path,
+                    LintRule::MissingPath,
+                    Severity::Error,
+                    format!("{} does not exist", resolved.display()),
+                    None,
+                );
+                return;
+            }
+            Ok(meta) if meta.is_dir() => fs::read_dir(&resolved).is_ok(),
+            Ok(_) => fs::File::open(&resolved).is_ok(),
+        };
+        if !readable {
+            self.push(
+                name,
+                path,
+                LintRule::UnreadablePath,
+                Severity::Error,
+                format!("{} is not readable", resolved.display()),
+                None,
+            );
+        }
+    }
+
+    // Entries identical to an earlier one under another name
+    fn check_duplicates(&mut self, entries: &[(String, ServerEntry)]) {
+        for (index, (name, entry)) in entries.iter().enumerate() {
+            let Some((first, _)) = entries[..index].iter().find(|(_, e)| e == entry) else {
+                continue;
+            };
+            self.push(
+                name,
+                server_entry::entry_path(self.client.servers_pointer(), name),
+                LintRule::DuplicateServer,
+                Severity::Warning,
+                format!("{} is the same server as {}", name, first),
+                Some((LintFix::RemoveServer, format!("Remove {}", name))),
+            );
+        }
+    }
+
+    // Tools offered by more than one server; only servers running here can be asked
+    fn check_tools(&mut self, names: &[String]) {
+        let mut owners: HashMap<String, String> = HashMap::new();
+        for name in names {
+            let run_id = match self.project {
+                Some(project) => {
+                    crate::projects::project_server_id(&project.to_string_lossy(), name)
+                }
+                None if self.client.id() == "claude" => name.clone(),
+                None => continue,
+            };
+            let Ok(tools) = crate::mcp_client::list_tool_names(&run_id) else {
+                continue;
+            };
+            for tool in tools {
+                match owners.get(&tool) {
+                    Some(owner) => {
+                        let message = format!("Tool {} is also provided by {}", tool, owner);
+                        self.push(
+                            name,
+                            server_entry::entry_path(self.client.servers_pointer(), name),
+                            LintRule::ToolCollision,
+                            Severity::Warning,
+                            message,
+                            None,
+                        );
+                    }
+                    None => {
+                        owners.insert(tool, name.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Split a value into (prefix, path, suffix) when it looks like a filesystem path.
+// Handles `--flag=/path` and docker-style `/host:/container` mounts.
+fn split_path_value(value: &str) -> Option<(&str, &str, &str)> {
+    let (prefix, rest) = match value.find('=') {
+        Some(index) if value.starts_with('-') => value.split_at(index + 1),
+        _ => ("", value),
+    };
+    if rest.contains("://") {
+        return None;
+    }
+
+    let bytes = rest.as_bytes();
+    let is_drive = bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes[2] == b'\\' || bytes[2] == b'/');
+    let path_like = is_drive
+        || rest.starts_with('/')
+        || rest.starts_with("~/")
+        || rest.starts_with("~\\")
+        || rest.starts_with("./")
+        || rest.starts_with("../")
+        || rest.starts_with(".\\")
+        || rest.starts_with("..\\");
+    if !path_like {
+        return None;
+    }
+
+    let split = if is_drive { None } else { rest.find(':') };
+    Some(match split {
+        Some(index) => (prefix, &rest[..index], &rest[index..]),
+        None => (prefix, rest, ""),
+    })
+}
+
+fn slot_fix(slot: Slot, from: &str, to: &str) -> LintFix {
+    let (from, to) = (from.to_string(), to.to_string());
+    match slot {
+        Slot::Command => LintFix::SetCommand { from, to },
+        Slot::Arg(index) => LintFix::SetArg { index, from, to },
+        Slot::Cwd => LintFix::SetCwd { from, to },
+    }
+}
+
+// Resolve `.` and `..` without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+fn lint_config(client: &'static dyn ClientAdapter, project: Option<&Path>) -> Vec<LintFinding> {
+    let Ok(path) = client.config_path(project) else {
+        return Vec::new();
+    };
+    if !path.is_file() {
+        return Vec::new();
+    }
+    // Unparseable configs and entries are reported by validation, not here
+    let Ok(current) = client.read_servers(project) else {
+        return Vec::new();
+    };
+
+    let mut linter = Linter {
+        client,
+        project,
+        config_path: path.to_string_lossy().to_string(),
+        findings: Vec::new(),
+    };
+    let entries: Vec<(String, ServerEntry)> = current
+        .servers
+        .iter()
+        .filter_map(|(name, config)| Some((name.clone(), ServerEntry::parse(config).ok()?)))
+        .collect();
+    for (name, entry) in &entries {
+        linter.lint_server(name, entry);
+    }
+    linter.check_duplicates(&entries);
+    linter.check_tools(&current.servers.keys().cloned().collect::<Vec<_>>());
+    linter.findings
+}
+
+// Every installed client's user config, then every registered project's configs
+fn lint_all() -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    for client in clients::CLIENTS.iter().filter(|c| c.is_installed()) {
+        findings.extend(lint_config(*client, None));
+    }
+    for project in crate::projects::registered_paths() {
+        for client in clients::CLIENTS {
+            findings.extend(lint_config(*client, Some(&project)));
+        }
+    }
+    findings
+}
+
+fn string_at<'a>(entry: &'a ServerConfig, key: &str) -> Option<&'a str> {
+    entry.get(key).and_then(|v| v.as_str())
+}
+
+fn apply_fix(
+    servers: &mut indexmap::IndexMap<String, ServerConfig>,
+    server: &str,
+    fix: &LintFix,
+) -> Result<(), String> {
+    let gone = || format!("{} no longer exists", server);
+    let (key, from, to) = match fix {
+        LintFix::RemoveServer => {
+            return servers.shift_remove(server).map(|_| ()).ok_or_else(gone);
+        }
+        LintFix::SetArg { index, from, to } => {
+            let arg = servers
+                .get_mut(server)
+                .ok_or_else(gone)?
+                .get_mut("args")
+                .and_then(|a| a.as_array_mut())
+                .and_then(|a| a.get_mut(*index))
+                .filter(|a| a.as_str() == Some(from))
+                .ok_or_else(|| format!("{}: args changed since the lint ran", server))?;
+            *arg = JsonValue::String(to.clone());
+            return Ok(());
+        }
+        LintFix::SetCommand { from, to } => ("command", from, to),
+        LintFix::SetCwd { from, to } => ("cwd", from, to),
+    };
+
+    let entry = servers.get_mut(server).ok_or_else(gone)?;
+    if string_at(entry, key) != Some(from) {
+        return Err(format!("{}: {} changed since the lint ran", server, key));
+    }
+    entry.insert(key.to_string(), JsonValue::String(to.clone()));
+    Ok(())
+}
+
+// Client id and project of a config file
+type ConfigKey = (String, Option<String>);
+
+// Lint the servers in every known client and project config
+#[tauri::command]
+pub async fn lint_configs() -> Result<Vec<LintFinding>, String> {
+    // Asking running servers for their tools blocks, so stay off the async runtime
+    tauri::async_runtime::spawn_blocking(lint_all)
+        .await
+        .map_err(|e| format!("Lint failed: {}", e))
+}
+
+// Apply the suggested fixes of the given findings, writing each config once
+#[tauri::command]
+pub async fn apply_lint_fixes(
+    app: AppHandle,
+    findings: Vec<LintFinding>,
+) -> Result<FixReport, String> {
+    let mut groups: Vec<(ConfigKey, Vec<LintFinding>)> = Vec::new();
+    for finding in findings.into_iter().filter(|f| f.fix.is_some()) {
+        let key = (finding.client.clone(), finding.project.clone());
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, group)) => group.push(finding),
+            None => groups.push((key, vec![finding])),
+        }
+    }
+
+    let mut report = FixReport {
+        applied: 0,
+        skipped: Vec::new(),
+    };
+    for ((client, project), group) in groups {
+        let adapter = clients::find_client(&client)?;
+        let project = project.map(PathBuf::from);
+        let label = format!("Apply lint fixes to {}", adapter.name());
+
+        let skipped = &mut report.skipped;
+        report.applied += crate::transaction::run(&app, label, project.as_deref(), async {
+            let mut current = adapter.read_servers(project.as_deref())?;
+            let mut applied = 0;
+            for finding in &group {
+                if let Some(fix) = &finding.fix {
+                    match apply_fix(&mut current.servers, &finding.server, fix) {
+                        Ok(()) => applied += 1,
+                        Err(e) => skipped.push(format!("{}: {}", adapter.name(), e)),
+                    }
+                }
+            }
+            if applied > 0 {
+                adapter.write_servers(&current)?;
+            }
+            Ok(applied)
+        })
+        .await?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn splits_path_values() {
+        assert_eq!(split_path_value("/srv/data"), Some(("", "/srv/data", "")));
+        assert_eq!(
+            split_path_value("--root=~/projects"),
+            Some(("--root=", "~/projects", ""))
+        );
+        assert_eq!(
+            split_path_value("/host/data:/data:ro"),
+            Some(("", "/host/data", ":/data:ro"))
+        );
+        assert_eq!(
+            split_path_value("C:\\Users\\bob"),
+            Some(("", "C:\\Users\\bob", ""))
+        );
+        assert_eq!(split_path_value("..\\tools"), Some(("", "..\\tools", "")));
+
+        assert_eq!(split_path_value("https://example.com/a"), None);
+        assert_eq!(split_path_value("--url=file:///tmp"), None);
+        assert_eq!(split_path_value("-y"), None);
+        assert_eq!(split_path_value("key=/value"), None);
+    }
+
+    #[test]
+    fn normalizes_dot_components() {
+        assert_eq!(normalize(Path::new("/a/./b/../c")), PathBuf::from("/a/c"));
+        assert_eq!(normalize(Path::new("a/b/../../c")), PathBuf::from("c"));
+        assert_eq!(normalize(Path::new("/../a")), PathBuf::from("/a"));
+    }
+
+    #[test]
+    fn fixes_apply_only_to_unchanged_values() {
+        let mut servers = indexmap::IndexMap::new();
+        servers.insert(
+            "fs".to_string(),
+            json!({ "command": "npx", "args": ["-y", "/old"] })
+                .as_object()
+                .unwrap()
+                .clone(),
+        );
+
+        let set_arg = LintFix::SetArg {
+            index: 1,
+            from: "/old".to_string(),
+            to: "/new".to_string(),
+        };
+        apply_fix(&mut servers, "fs", &set_arg).unwrap();
+        assert_eq!(servers["fs"]["args"], json!(["-y", "/new"]));
+        assert!(apply_fix(&mut servers, "fs", &set_arg).is_err());
+
+        let set_command = LintFix::SetCommand {
+            from: "node".to_string(),
+            to: "/usr/bin/node".to_string(),
+        };
+        assert!(apply_fix(&mut servers, "fs", &set_command).is_err());
+
+        apply_fix(&mut servers, "fs", &LintFix::RemoveServer).unwrap();
+        assert!(servers.is_empty());
+        assert_eq!(
+            apply_fix(&mut servers, "fs", &LintFix::RemoveServer).unwrap_err(),
+            "fs no longer exists"
+        );
+    }
+}
@@ -0,0 +1,717 @@
+use crate::plan::{fetched_package, SideEffect};
+use crate::store::LockEntry;
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+const NPM_REGISTRY: &str = "https://registry.npmjs.org";
+const PYPI: &str = "https://pypi.org/pypi";
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Ecosystem {
+    Npm,
+    Pypi,
+    Oci,
+}
+
+fn ecosystem(runner: &str) -> Option<Ecosystem> {
+    match runner {
+        "npx" | "bunx" | "pnpm" | "yarn" => Some(Ecosystem::Npm),
+        "uvx" => Some(Ecosystem::Pypi),
+        "docker" | "podman" => Some(Ecosystem::Oci),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LockStatus {
+    // Resolves to the locked version and integrity
+    Matches,
+    // Resolves to something other than what was locked
+    Changed,
+    // Fetches a package but has no lock entry
+    Unlocked,
+    // Could not be resolved now
+    Failed,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LockCheck {
+    pub server: String,
+    pub package: String,
+    pub locked: Option<LockEntry>,
+    pub resolved: Option<LockEntry>,
+    pub status: LockStatus,
+    pub error: Option<String>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+// The package name as written and the version or tag it asks for, if any
+fn split_spec(ecosystem: Ecosystem, spec: &str) -> (String, Option<String>) {
+    let split = match ecosystem {
+        // A leading `@` belongs to the scope
+        Ecosystem::Npm => spec
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| *c == '@')
+            .map(|(i, _)| (&spec[..i], &spec[i + 1..])),
+        Ecosystem::Pypi => spec
+            .split_once("==")
+            .or_else(|| spec.split_once('@'))
+            .map(|(name, version)| (name.trim(), version.trim())),
+        Ecosystem::Oci => {
+            let last = spec.rfind('/').map_or(0, |i| i + 1);
+            spec.find('@')
+                .or_else(|| spec[last..].find(':').map(|i| i + last))
+                .map(|i| (&spec[..i], &spec[i + 1..]))
+        }
+    };
+    match split {
+        Some((name, version)) if !version.is_empty() => {
+            (name.to_string(), Some(version.to_string()))
+        }
+        _ => (spec.to_string(), None),
+    }
+}
+
+async fn get_json(client: &reqwest::Client, url: &str) -> Result<JsonValue, String> {
+    get_json_as(client, url, "application/json").await
+}
+
+async fn get_json_as(
+    client: &reqwest::Client,
+    url: &str,
+    accept: &str,
+) -> Result<JsonValue, String> {
+    let response = client
+        .get(url)
+        .header(reqwest::header::ACCEPT, accept)
+        .timeout(RESOLVE_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to query {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse {}: {}", url, e))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Ge,
+    Gt,
+    Lt,
+    Le,
+}
+
+// npm semver range: any of the sets matches when all of its bounds hold
+type Range = Vec<Vec<(Op, [u64; 3])>>;
+
+// A release version; prereleases are left out, like npm does for ranges
+fn release_version(version: &str) -> Option<[u64; 3]> {
+    let version = version.trim_start_matches(['v', '=']);
+    let version = version.split('+').next().unwrap_or(version);
+    let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+    let parsed = [parts.next()??, parts.next()??, parts.next()??];
+    parts.next().is_none().then_some(parsed)
+}
+
+// `1`, `1.2` or `1.2.3`, with `x` or `*` standing for any later part
+fn partial_version(version: &str) -> Option<Vec<u64>> {
+    let version = version.trim_start_matches(['v', '=']);
+    let mut parts = Vec::new();
+    for part in version.split('.') {
+        if matches!(part, "x" | "X" | "*" | "") && !version.is_empty() {
+            break;
+        }
+        parts.push(part.parse::<u64>().ok()?);
+    }
+    (parts.len() <= 3).then_some(parts)
+}
+
+fn lower(parts: &[u64]) -> [u64; 3] {
+    let mut version = [0; 3];
+    version[..parts.len()].copy_from_slice(parts);
+    version
+}
+
+// The first version past `parts` once the part at `index` is raised
+fn bump(parts: &[u64], index: usize) -> [u64; 3] {
+    let mut version = lower(&parts[..index]);
+    version[index] = parts[index] + 1;
+    version
+}
+
+// The first version past a partial version, e.g. 1.3.0 for `1.2`
+fn next(parts: &[u64]) -> Option<[u64; 3]> {
+    (!parts.is_empty()).then(|| bump(parts, parts.len() - 1))
+}
+
+fn comparator(token: &str) -> Option<Vec<(Op, [u64; 3])>> {
+    let (op, version) = [">=", "<=", ">", "<", "^", "~", "="]
+        .iter()
+        .find_map(|op| token.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("", token));
+    let parts = partial_version(version)?;
+    let exact = parts.len() == 3;
+    let mut bounds = Vec::new();
+    match op {
+        ">=" if !parts.is_empty() => bounds.push((Op::Ge, lower(&parts))),
+        ">" if exact => bounds.push((Op::Gt, lower(&parts))),
+        ">" => bounds.extend(next(&parts).map(|v| (Op::Ge, v))),
+        "<" if !parts.is_empty() => bounds.push((Op::Lt, lower(&parts))),
+        "<=" if exact => bounds.push((Op::Le, lower(&parts))),
+        "<=" => bounds.extend(next(&parts).map(|v| (Op::Lt, v))),
+        "^" if !parts.is_empty() => {
+            let index = parts
+                .iter()
+                .position(|part| *part != 0)
+                .unwrap_or(parts.len() - 1);
+            bounds.push((Op::Ge, lower(&parts)));
+            bounds.push((Op::Lt, bump(&parts, index)));
+        }
+        "~" if !parts.is_empty() => {
+            bounds.push((Op::Ge, lower(&parts)));
+            bounds.push((Op::Lt, bump(&parts, 1.min(parts.len() - 1))));
+        }
+        _ if !parts.is_empty() => {
+            bounds.push((Op::Ge, lower(&parts)));
+            if exact {
+                bounds.push((Op::Le, lower(&parts)));
+            } else {
+                bounds.extend(next(&parts).map(|v| (Op::Lt, v)));
+            }
+        }
+        _ => {}
+    }
+    Some(bounds)
+}
+
+// Parse an npm range such as `^1.2`, `~1.2.3`, `>=1 <3`, `1.x || 2` or `1.0 - 2`; None
+// for anything else, e.g. a dist-tag
+fn parse_range(spec: &str) -> Option<Range> {
+    spec.split("||")
+        .map(|set| {
+            // Operators may be written apart from their version, as in `>= 1.2`
+            let mut tokens: Vec<String> = Vec::new();
+            let mut words = set.split_whitespace();
+            while let Some(word) = words.next() {
+                if matches!(word, ">=" | "<=" | ">" | "<" | "^" | "~" | "=") {
+                    tokens.push(format!("{}{}", word, words.next()?));
+                } else {
+                    tokens.push(word.to_string());
+                }
+            }
+            if let [from, dash, to] = &tokens[..] {
+                if dash == "-" {
+                    let (from, to) = (partial_version(from)?, partial_version(to)?);
+                    let mut bounds = vec![(Op::Ge, lower(&from))];
+                    if to.len() == 3 {
+                        bounds.push((Op::Le, lower(&to)));
+                    } else {
+                        bounds.extend(next(&to).map(|v| (Op::Lt, v)));
+                    }
+                    return Some(bounds);
+                }
+            }
+            let mut bounds = Vec::new();
+            for token in &tokens {
+                bounds.extend(comparator(token)?);
+            }
+            Some(bounds)
+        })
+        .collect()
+}
+
+fn satisfies(range: &Range, version: [u64; 3]) -> bool {
+    range.iter().any(|set| {
+        set.iter().all(|(op, bound)| match op {
+            Op::Ge => version >= *bound,
+            Op::Gt => version > *bound,
+            Op::Lt => version < *bound,
+            Op::Le => version <= *bound,
+        })
+    })
+}
+
+// The version of a package document a range installs: the `latest` tag when it matches,
+// like npm, else the highest matching release
+fn max_satisfying(document: &JsonValue, range: &Range) -> Option<String> {
+    let versions = document["versions"].as_object()?;
+    let matching = |version: &str| {
+        versions.contains_key(version)
+            && release_version(version).is_some_and(|v| satisfies(range, v))
+    };
+    if let Some(latest) = document["dist-tags"]["latest"].as_str() {
+        if matching(latest) {
+            return Some(latest.to_string());
+        }
+    }
+    versions
+        .keys()
+        .filter_map(|key| Some((release_version(key)?, key)))
+        .filter(|(version, _)| satisfies(range, *version))
+        .max_by_key(|(version, _)| *version)
+        .map(|(_, key)| key.clone())
+}
+
+// The version a tag, exact version or range points at, with its integrity
+async fn resolve_npm(
+    client: &reqwest::Client,
+    name: &str,
+    version: Option<&str>,
+) -> Result<(String, Option<String>), String> {
+    let encoded = name.replace('/', "%2F");
+    let range = version
+        .filter(|v| release_version(v).is_none())
+        .and_then(|v| Some((v, parse_range(v)?)));
+    let manifest = match range {
+        // The registry serves tags and exact versions only; ranges are matched against
+        // the package's version list
+        Some((spec, range)) => {
+            let url = format!("{}/{}", NPM_REGISTRY, encoded);
+            let document = get_json_as(client, &url, "application/vnd.npm.install-v1+json").await?;
+            let chosen = max_satisfying(&document, &range)
+                .ok_or_else(|| format!("{} has no version matching {}", name, spec))?;
+            document["versions"][&chosen].clone()
+        }
+        None => {
+            let url = format!(
+                "{}/{}/{}",
+                NPM_REGISTRY,
+                encoded,
+                version.unwrap_or("latest")
+            );
+            get_json(client, &url).await?
+        }
+    };
+    let resolved = manifest["version"]
+        .as_str()
+        .ok_or_else(|| format!("{} has no version {}", name, version.unwrap_or("latest")))?;
+    let integrity = manifest["dist"]["integrity"]
+        .as_str()
+        .map(str::to_string)
+        .or_else(|| {
+            manifest["dist"]["shasum"]
+                .as_str()
+                .map(|s| format!("sha1:{}", s))
+        });
+    Ok((resolved.to_string(), integrity))
+}
+
+async fn resolve_pypi(
+    client: &reqwest::Client,
+    name: &str,
+    version: Option<&str>,
+) -> Result<(String, Option<String>), String> {
+    // Extras do not change which release is fetched
+    let project = name.split('[').next().unwrap_or(name);
+    let url = match version {
+        Some(version) => format!("{}/{}/{}/json", PYPI, project, version),
+        None => format!("{}/{}/json", PYPI, project),
+    };
+    let release = get_json(client, &url).await?;
+    let resolved = release["info"]["version"]
+        .as_str()
+        .ok_or_else(|| format!("{} has no release {}", project, version.unwrap_or("")))?;
+    // The wheel uv prefers, else whatever file the release has
+    let files = release["urls"].as_array().cloned().unwrap_or_default();
+    let integrity = files
+        .iter()
+        .find(|f| f["packagetype"] == "bdist_wheel")
+        .or(files.first())
+        .and_then(|f| f["digests"]["sha256"].as_str())
+        .map(|digest| format!("sha256:{}", digest));
+    Ok((resolved.to_string(), integrity))
+}
+
+// Docker Hub images go by short names: `node` is `docker.io/library/node`
+fn normalize_repository(name: &str) -> &str {
+    let name = name
+        .strip_prefix("docker.io/")
+        .or_else(|| name.strip_prefix("index.docker.io/"))
+        .unwrap_or(name);
+    name.strip_prefix("library/").unwrap_or(name)
+}
+
+// The digest of `name` among an image's RepoDigests; an image pulled from several
+// repositories lists one per repository
+fn repo_digest(digests: &[String], name: &str) -> Option<String> {
+    digests.iter().find_map(|entry| {
+        let (repository, digest) = entry.split_once('@')?;
+        (normalize_repository(repository) == normalize_repository(name)).then(|| digest.to_string())
+    })
+}
+
+// Images are resolved against the local engine; ones not pulled yet keep their tag only
+fn resolve_oci(runner: &str, name: &str, version: Option<&str>) -> (String, Option<String>) {
+    if let Some(digest) = version.filter(|v| v.starts_with("sha256:")) {
+        return (digest.to_string(), Some(digest.to_string()));
+    }
+    let tag = version.unwrap_or("latest").to_string();
+    let digest = crate::env_check::resolve_command(runner)
+        .and_then(|path| {
+            Command::new(path)
+                .args(["image", "inspect", "--format", "{{json .RepoDigests}}"])
+                .arg(format!("{}:{}", name, tag))
+                .output()
+                .ok()
+        })
+        .filter(|output| output.status.success())
+        .and_then(|output| serde_json::from_slice::<Vec<String>>(&output.stdout).ok())
+        .and_then(|digests| repo_digest(&digests, name));
+    (tag, digest)
+}
+
+async fn resolve(runner: &str, requested: &str) -> Result<LockEntry, String> {
+    let ecosystem = ecosystem(runner).ok_or_else(|| format!("Cannot lock {} packages", runner))?;
+    let (package, version) = split_spec(ecosystem, requested);
+    let client = reqwest::Client::new();
+    let (version, integrity) = match ecosystem {
+        Ecosystem::Npm => resolve_npm(&client, &package, version.as_deref()).await?,
+        Ecosystem::Pypi => resolve_pypi(&client, &package, version.as_deref()).await?,
+        Ecosystem::Oci => {
+            let (runner, name) = (runner.to_string(), package.clone());
+            tauri::async_runtime::spawn_blocking(move || {
+                resolve_oci(&runner, &name, version.as_deref())
+            })
+            .await
+            .map_err(|e| e.to_string())?
+        }
+    };
+    Ok(LockEntry {
+        runner: runner.to_string(),
+        requested: requested.to_string(),
+        package,
+        version,
+        integrity,
+        resolved_at: now_ms(),
+    })
+}
+
+// The lock entry for the package a command fetches; None when it fetches nothing or the
+// package cannot be resolved, which does not stop an install
+pub async fn resolve_command(command: &str, args: &[String]) -> Option<LockEntry> {
+    let Some(SideEffect::FetchPackage { runner, package }) = fetched_package(command, args) else {
+        return None;
+    };
+    match resolve(&runner, &package).await {
+        Ok(entry) => Some(entry),
+        Err(e) => {
+            println!("Could not lock {}: {}", package, e);
+            None
+        }
+    }
+}
+
+// The package argument that installs exactly the locked release
+fn pinned_spec(entry: &LockEntry) -> Option<String> {
+    let ecosystem = ecosystem(&entry.runner)?;
+    let pinned = match ecosystem {
+        Ecosystem::Npm => format!("{}@{}", entry.package, entry.version),
+        Ecosystem::Pypi => format!("{}=={}", entry.package, entry.version),
+        Ecosystem::Oci => match &entry.integrity {
+            Some(digest) => format!("{}@{}", entry.package, digest),
+            None => format!("{}:{}", entry.package, entry.version),
+        },
+    };
+    Some(pinned)
+}
+
+fn pin_args(args: &mut [String], requested: &str, pinned: &str) -> bool {
+    let mut changed = false;
+    for arg in args.iter_mut().filter(|arg| *arg == requested) {
+        *arg = pinned.to_string();
+        changed = true;
+    }
+    changed
+}
+
+#[tauri::command]
+pub fn get_package_lock(app: AppHandle) -> Result<IndexMap<String, LockEntry>, String> {
+    crate::store::get_package_lock(&app)
+}
+
+// Rewrite the args of locked servers to the exact locked release. `servers` defaults to
+// every locked server. Returns the servers whose args changed.
+#[tauri::command]
+pub async fn pin_server_versions(
+    app: AppHandle,
+    servers: Option<Vec<String>>,
+    clients: Option<Vec<String>>,
+    project: Option<String>,
+) -> Result<Vec<String>, String> {
+    let targets = crate::clients::resolve_targets(clients)?;
+    let project = project.map(PathBuf::from);
+    let mut lock = crate::store::get_package_lock(&app)?;
+    let pins: Vec<(String, String, String)> = lock
+        .iter()
+        .filter(|(name, _)| servers.as_ref().is_none_or(|s| s.contains(*name)))
+        .filter_map(|(name, entry)| {
+            let pinned = pinned_spec(entry)?;
+            (pinned != entry.requested).then(|| (name.clone(), entry.requested.clone(), pinned))
+        })
+        .collect();
+    if pins.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    crate::transaction::run(&app, "Pin server versions", project.as_deref(), async {
+        let mut pinned_servers = Vec::new();
+        crate::clients::edit_servers(&targets, project.as_deref(), |current| {
+            for (name, requested, pinned) in &pins {
+                let Some(JsonValue::Array(args)) = current
+                    .get_mut(name)
+                    .and_then(|entry| entry.get_mut("args"))
+                else {
+                    continue;
+                };
+                for arg in args
+                    .iter_mut()
+                    .filter(|arg| arg.as_str() == Some(requested))
+                {
+                    *arg = JsonValue::String(pinned.clone());
+                    if !pinned_servers.contains(name) {
+                        pinned_servers.push(name.clone());
+                    }
+                }
+            }
+            Ok(())
+        })?;
+
+        let mut installed = crate::store::get_installed_servers(&app)?;
+        for (name, requested, pinned) in &pins {
+            if let Some(mut server) = installed.remove(name) {
+                if pin_args(&mut server.args, requested, pinned) {
+                    crate::store::save_installed_server(app.clone(), server).await?;
+                }
+            }
+            if let Some(entry) = lock.get_mut(name) {
+                entry.requested = pinned.clone();
+            }
+        }
+        crate::store::save_package_lock(&app, &lock)?;
+        Ok(pinned_servers)
+    })
+    .await
+}
+
+fn lock_status(locked: Option<&LockEntry>, resolved: Option<&LockEntry>) -> LockStatus {
+    match (locked, resolved) {
+        (None, _) => LockStatus::Unlocked,
+        (_, None) => LockStatus::Failed,
+        // Integrity is compared only when both sides know it
+        (Some(locked), Some(resolved)) => {
+            let integrity_differs = matches!(
+                (&locked.integrity, &resolved.integrity),
+                (Some(a), Some(b)) if a != b
+            );
+            if locked.package != resolved.package
+                || locked.version != resolved.version
+                || integrity_differs
+            {
+                LockStatus::Changed
+            } else {
+                LockStatus::Matches
+            }
+        }
+    }
+}
+
+// Resolve every installed server's package again and compare it with the lock
+#[tauri::command]
+pub async fn verify_package_lock(app: AppHandle) -> Result<Vec<LockCheck>, String> {
+    let lock = crate::store::get_package_lock(&app)?;
+    let mut installed: Vec<_> = crate::store::get_installed_servers(&app)?
+        .into_values()
+        .collect();
+    installed.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut checks = Vec::new();
+    for server in installed {
+        let Some(SideEffect::FetchPackage { runner, package }) =
+            fetched_package(&server.command, &server.args)
+        else {
+            continue;
+        };
+        let locked = lock.get(&server.name).cloned();
+        let (resolved, error) = match resolve(&runner, &package).await {
+            Ok(entry) => (Some(entry), None),
+            Err(e) => (None, Some(e)),
+        };
+        let status = lock_status(locked.as_ref(), resolved.as_ref());
+        checks.push(LockCheck {
+            server: server.name,
+            package,
+            locked,
+            resolved,
+            status,
+            error,
+        });
+    }
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_package_specs() {
+        let split = |ecosystem, spec| split_spec(ecosystem, spec);
+        assert_eq!(
+            split(Ecosystem::Npm, "@scope/pkg@1.2.3"),
+            ("@scope/pkg".to_string(), Some("1.2.3".to_string()))
+        );
+        assert_eq!(
+            split(Ecosystem::Npm, "@scope/pkg"),
+            ("@scope/pkg".to_string(), None)
+        );
+        assert_eq!(
+            split(Ecosystem::Pypi, "mcp-server-fetch==0.6.2"),
+            ("mcp-server-fetch".to_string(), Some("0.6.2".to_string()))
+        );
+        assert_eq!(
+            split(Ecosystem::Oci, "localhost:5000/tools/mcp:1.0"),
+            (
+                "localhost:5000/tools/mcp".to_string(),
+                Some("1.0".to_string())
+            )
+        );
+        assert_eq!(
+            split(Ecosystem::Oci, "ghcr.io/org/mcp@sha256:abc"),
+            (
+                "ghcr.io/org/mcp".to_string(),
+                Some("sha256:abc".to_string())
+            )
+        );
+        assert_eq!(
+            split(Ecosystem::Oci, "localhost:5000/mcp"),
+            ("localhost:5000/mcp".to_string(), None)
+        );
+    }
+
+    fn entry(runner: &str, package: &str, version: &str, integrity: Option<&str>) -> LockEntry {
+        LockEntry {
+            runner: runner.to_string(),
+            requested: package.to_string(),
+            package: package.to_string(),
+            version: version.to_string(),
+            integrity: integrity.map(str::to_string),
+            resolved_at: 0,
+        }
+    }
+
+    #[test]
+    fn pins_the_locked_release() {
+        let pinned = |e: LockEntry| pinned_spec(&e);
+        assert_eq!(
+            pinned(entry("npx", "@scope/pkg", "1.2.3", Some("sha512-x"))).as_deref(),
+            Some("@scope/pkg@1.2.3")
+        );
+        assert_eq!(
+            pinned(entry("uvx", "mcp-server-fetch", "0.6.2", None)).as_deref(),
+            Some("mcp-server-fetch==0.6.2")
+        );
+        assert_eq!(
+            pinned(entry(
+                "docker",
+                "ghcr.io/org/mcp",
+                "1.0",
+                Some("sha256:abc")
+            ))
+            .as_deref(),
+            Some("ghcr.io/org/mcp@sha256:abc")
+        );
+        assert_eq!(
+            pinned(entry("docker", "ghcr.io/org/mcp", "1.0", None)).as_deref(),
+            Some("ghcr.io/org/mcp:1.0")
+        );
+        assert_eq!(pinned(entry("cargo", "mcp", "1.0", None)), None);
+
+        let mut args: Vec<String> = ["-y", "@scope/pkg", "--flag"].map(String::from).into();
+        assert!(pin_args(&mut args, "@scope/pkg", "@scope/pkg@1.2.3"));
+        assert_eq!(args, ["-y", "@scope/pkg@1.2.3", "--flag"]);
+        assert!(!pin_args(&mut args, "@scope/pkg", "@scope/pkg@1.2.3"));
+    }
+
+    #[test]
+    fn compares_locked_and_resolved_packages() {
+        let locked = entry("npx", "pkg", "1.2.3", Some("sha512-a"));
+        let status = |resolved: Option<LockEntry>| lock_status(Some(&locked), resolved.as_ref());
+        assert_eq!(lock_status(None, Some(&locked)), LockStatus::Unlocked);
+        assert_eq!(status(None), LockStatus::Failed);
+        assert_eq!(status(Some(locked.clone())), LockStatus::Matches);
+        assert_eq!(
+            status(Some(entry("npx", "pkg", "1.2.3", None))),
+            LockStatus::Matches
+        );
+        assert_eq!(
+            status(Some(entry("npx", "pkg", "1.2.3", Some("sha512-b")))),
+            LockStatus::Changed
+        );
+        assert_eq!(
+            status(Some(entry("npx", "pkg", "1.2.4", Some("sha512-a")))),
+            LockStatus::Changed
+        );
+    }
+
+    #[test]
+    fn npm_ranges_pick_the_highest_match() {
+        let document = serde_json::json!({
+            "dist-tags": { "latest": "1.4.0", "next": "2.0.0-beta.1" },
+            "versions": {
+                "0.9.0": {}, "1.2.0": {}, "1.2.7": {}, "1.4.0": {}, "1.5.0": {},
+                "2.0.0-beta.1": {}, "2.1.0": {}
+            }
+        });
+        let pick = |spec: &str| max_satisfying(&document, &parse_range(spec).unwrap());
+        // `latest` wins whenever it matches, as with npm install
+        assert_eq!(pick("^1.2").as_deref(), Some("1.4.0"));
+        assert_eq!(pick("~1.2.0").as_deref(), Some("1.2.7"));
+        assert_eq!(pick("1.2.x").as_deref(), Some("1.2.7"));
+        assert_eq!(pick(">= 1.5 <3").as_deref(), Some("2.1.0"));
+        assert_eq!(pick("^0.9 || ^2").as_deref(), Some("2.1.0"));
+        assert_eq!(pick("1.0 - 1.2").as_deref(), Some("1.2.7"));
+        assert_eq!(pick("*").as_deref(), Some("1.4.0"));
+        assert_eq!(pick("^3"), None);
+
+        let range = parse_range("^0.2.3").unwrap();
+        assert!(satisfies(&range, [0, 2, 9]) && !satisfies(&range, [0, 3, 0]));
+        // Dist-tags are not ranges and are fetched as they are
+        assert!(parse_range("next").is_none() && parse_range("beta").is_none());
+    }
+
+    #[test]
+    fn repo_digests_match_the_repository() {
+        let digests = [
+            "ghcr.io/other/mcp@sha256:other".to_string(),
+            "node@sha256:node".to_string(),
+            "ghcr.io/org/mcp@sha256:org".to_string(),
+        ];
+        assert_eq!(
+            repo_digest(&digests, "ghcr.io/org/mcp").as_deref(),
+            Some("sha256:org")
+        );
+        assert_eq!(
+            repo_digest(&digests, "docker.io/library/node").as_deref(),
+            Some("sha256:node")
+        );
+        assert_eq!(repo_digest(&digests, "ghcr.io/org/tools"), None);
+    }
+}
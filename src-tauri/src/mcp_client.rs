@@ -0,0 +1,386 @@
+use once_cell::sync::Lazy;
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{ChildStdin, ChildStdout};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+
+const PROTOCOL_VERSION: &str = "2025-06-18";
+const INITIALIZE_TIMEOUT: Duration = Duration::from_secs(30);
+const LIST_TOOLS_TIMEOUT: Duration = Duration::from_secs(10);
+
+const ROOTS_CHANGED: &str = "notifications/roots/list_changed";
+
+// JSON-RPC error codes used when answering server requests
+const METHOD_NOT_FOUND: i64 = -32601;
+const INTERNAL_ERROR: i64 = -32603;
+
+// Client sessions for the servers the manager started, keyed by server name
+static SESSIONS: Lazy<Mutex<HashMap<String, Arc<McpClientSession>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+// The manager's side of an MCP connection to a server it spawned over stdio
+pub struct McpClientSession {
+    name: String,
+    app: AppHandle,
+    stdin: Mutex<ChildStdin>,
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<u64, mpsc::Sender<Result<JsonValue, RpcError>>>>,
+}
+
+impl McpClientSession {
+    fn send(&self, message: &JsonValue) -> Result<(), String> {
+        let mut line = serde_json::to_string(message)
+            .map_err(|e| format!("Failed to serialize message: {}", e))?;
+        line.push('\n');
+
+        let mut stdin = self
+            .stdin
+            .lock()
+            .map_err(|e| format!("Failed to lock stdin: {}", e))?;
+        stdin
+            .write_all(line.as_bytes())
+            .and_then(|()| stdin.flush())
+            .map_err(|e| format!("Failed to write to server {}: {}", self.name, e))
+    }
+
+    pub fn request(
+        &self,
+        method: &str,
+        params: JsonValue,
+        timeout: Duration,
+    ) -> Result<JsonValue, String> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = mpsc::channel();
+        self.pending
+            .lock()
+            .map_err(|e| format!("Failed to lock pending requests: {}", e))?
+            .insert(id, tx);
+
+        let message = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        });
+        if let Err(e) = self.send(&message) {
+            if let Ok(mut pending) = self.pending.lock() {
+                pending.remove(&id);
+            }
+            return Err(e);
+        }
+
+        let response = rx.recv_timeout(timeout);
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(&id);
+        }
+        match response {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(e)) => Err(format!("{} failed ({}): {}", method, e.code, e.message)),
+            Err(_) => Err(format!("{} timed out for server {}", method, self.name)),
+        }
+    }
+
+    pub fn notify(&self, method: &str, params: Option<JsonValue>) -> Result<(), String> {
+        self.send(&notification(method, params))
+    }
+
+    fn initialize(&self) -> Result<(), String> {
+        let params = json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": capabilities(),
+            "clientInfo": {
+                "name": "mcp-manager",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        });
+        let result = self.request("initialize", params, INITIALIZE_TIMEOUT)?;
+        println!(
+            "[{}] initialized, server protocol version: {}",
+            self.name,
+            result
+                .get("protocolVersion")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+        );
+        self.notify("notifications/initialized", None)
+    }
+
+    // Answer a request the server sent to us
+    fn handle_request(&self, method: &str, params: &JsonValue) -> Result<JsonValue, RpcError> {
+        match method {
+            "ping" => Ok(json!({})),
+            "roots/list" => {
+                let grants = crate::store::get_root_grants(&self.app, &self.name)
+                    .map_err(|e| RpcError::new(INTERNAL_ERROR, e))?;
+                Ok(roots_result(&grants))
+            }
+            crate::client_requests::SAMPLING_METHOD
+            | crate::client_requests::ELICITATION_METHOD => {
+                crate::client_requests::handle(&self.app, &self.name, method, params)
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {}", method),
+            )),
+        }
+    }
+
+    fn handle_message(&self, message: JsonValue) {
+        let method = message.get("method").and_then(|m| m.as_str());
+        let id = message.get("id").cloned();
+
+        match (method, id) {
+            // Request from the server
+            (Some(method), Some(id)) => {
+                let params = message.get("params").cloned().unwrap_or(JsonValue::Null);
+                let response = match self.handle_request(method, &params) {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Err(e) => json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": e.code, "message": e.message },
+                    }),
+                };
+                if let Err(e) = self.send(&response) {
+                    eprintln!("[{}] failed to answer {}: {}", self.name, method, e);
+                }
+            }
+            // Notification from the server, nothing to answer
+            (Some(_), None) => {}
+            // Response to one of our requests
+            (None, Some(id)) => {
+                let Some(id) = id.as_u64() else {
+                    return;
+                };
+                let sender = self.pending.lock().ok().and_then(|mut p| p.remove(&id));
+                if let Some(sender) = sender {
+                    let outcome = match message.get("error") {
+                        Some(error) => Err(RpcError::new(
+                            error.get("code").and_then(|c| c.as_i64()).unwrap_or(0),
+                            error
+                                .get("message")
+                                .and_then(|m| m.as_str())
+                                .unwrap_or_default(),
+                        )),
+                        None => Ok(message.get("result").cloned().unwrap_or(JsonValue::Null)),
+                    };
+                    let _ = sender.send(outcome);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+// Build a `Root` entry for a granted folder
+fn notification(method: &str, params: Option<JsonValue>) -> JsonValue {
+    let mut message = json!({
+        "jsonrpc": "2.0",
+        "method": method,
+    });
+    if let Some(params) = params {
+        message["params"] = params;
+    }
+    message
+}
+
+// Servers may ask for the granted folders and are told when they change
+fn capabilities() -> JsonValue {
+    json!({
+        "roots": { "listChanged": true },
+        "sampling": {},
+        "elicitation": {},
+    })
+}
+
+// The `roots/list` result for the folders granted to a server
+fn roots_result(grants: &[String]) -> JsonValue {
+    let roots: Vec<JsonValue> = grants.iter().map(|path| root_entry(path)).collect();
+    json!({ "roots": roots })
+}
+
+fn root_entry(path: &str) -> JsonValue {
+    let normalized = path.replace('\\', "/");
+    let name = normalized
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or(path);
+    json!({
+        "uri": path_to_file_uri(path),
+        "name": name,
+    })
+}
+
+fn path_to_file_uri(path: &str) -> String {
+    let normalized = path.replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !normalized.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in normalized.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+// Take over a spawned server's stdio and run the client side of the protocol
+pub fn attach(app: &AppHandle, name: &str, stdin: ChildStdin, stdout: ChildStdout) {
+    let session = Arc::new(McpClientSession {
+        name: name.to_string(),
+        app: app.clone(),
+        stdin: Mutex::new(stdin),
+        next_id: AtomicU64::new(1),
+        pending: Mutex::new(HashMap::new()),
+    });
+
+    if let Ok(mut sessions) = SESSIONS.lock() {
+        sessions.insert(name.to_string(), session.clone());
+    }
+
+    let reader_session = session.clone();
+    thread::spawn(move || {
+        let reader = BufReader::new(stdout);
+        for line in reader.lines().map_while(Result::ok) {
+            println!("[{}] stdout: {}", reader_session.name, line);
+            if let Ok(message) = serde_json::from_str::<JsonValue>(&line) {
+                let session = reader_session.clone();
+                // Requests may block on the UI, so never handle them on the reader thread
+                thread::spawn(move || session.handle_message(message));
+            }
+        }
+        detach_session(&reader_session);
+    });
+
+    thread::spawn(move || {
+        if let Err(e) = session.initialize() {
+            eprintln!("[{}] MCP initialize failed: {}", session.name, e);
+        }
+    });
+}
+
+// Remove the session only if it is still the one registered under its name
+fn detach_session(session: &Arc<McpClientSession>) {
+    if let Ok(mut sessions) = SESSIONS.lock() {
+        if sessions
+            .get(&session.name)
+            .is_some_and(|current| Arc::ptr_eq(current, session))
+        {
+            sessions.remove(&session.name);
+        }
+    }
+}
+
+pub fn detach(name: &str) {
+    if let Ok(mut sessions) = SESSIONS.lock() {
+        sessions.remove(name);
+    }
+}
+
+pub fn detach_all() {
+    if let Ok(mut sessions) = SESSIONS.lock() {
+        sessions.clear();
+    }
+}
+
+pub fn get_session(name: &str) -> Option<Arc<McpClientSession>> {
+    SESSIONS.lock().ok().and_then(|s| s.get(name).cloned())
+}
+
+// Tell a running server that the folders granted to it changed
+pub fn notify_roots_changed(name: &str) {
+    if let Some(session) = get_session(name) {
+        if let Err(e) = session.notify(ROOTS_CHANGED, None) {
+            eprintln!("[{}] failed to send roots/list_changed: {}", name, e);
+        }
+    }
+}
+
+// Names of the tools a running server offers, following pagination cursors
+pub fn list_tool_names(name: &str) -> Result<Vec<String>, String> {
+    let session = get_session(name).ok_or_else(|| format!("Server {} is not running", name))?;
+    let mut names = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let params = match &cursor {
+            Some(cursor) => json!({ "cursor": cursor }),
+            None => json!({}),
+        };
+        let result = session.request("tools/list", params, LIST_TOOLS_TIMEOUT)?;
+        if let Some(tools) = result.get("tools").and_then(|t| t.as_array()) {
+            names.extend(
+                tools
+                    .iter()
+                    .filter_map(|tool| tool.get("name").and_then(|n| n.as_str()))
+                    .map(str::to_string),
+            );
+        }
+        cursor = result
+            .get("nextCursor")
+            .and_then(|c| c.as_str())
+            .map(str::to_string);
+        if cursor.is_none() {
+            return Ok(names);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn granted_folders_are_listed_as_file_uris() {
+        let grants = [
+            "/home/a/projects/my app".to_string(),
+            "/home/a/notes".to_string(),
+            "C:\\Users\\a\\Documents".to_string(),
+        ];
+        assert_eq!(
+            roots_result(&grants),
+            json!({
+                "roots": [
+                    { "uri": "file:///home/a/projects/my%20app", "name": "my app" },
+                    { "uri": "file:///home/a/notes", "name": "notes" },
+                    { "uri": "file:///C:/Users/a/Documents", "name": "Documents" },
+                ]
+            })
+        );
+        assert_eq!(roots_result(&[]), json!({ "roots": [] }));
+    }
+
+    #[test]
+    fn roots_changes_are_advertised_and_announced() {
+        assert_eq!(capabilities()["roots"], json!({ "listChanged": true }));
+        assert_eq!(
+            notification(ROOTS_CHANGED, None),
+            json!({ "jsonrpc": "2.0", "method": "notifications/roots/list_changed" })
+        );
+    }
+}
@@ -1,7 +1,11 @@
+use crate::claude_config::ServerConfig;
+use crate::server_entry::{self, ServerEntry};
 use crate::tray::update_tray_status;
+use indexmap::IndexMap;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::sync::Mutex;
 use std::thread;
@@ -12,10 +16,12 @@ static RUNNING_SERVERS: Lazy<Mutex<HashMap<String, Child>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
 pub fn start_mcp_server(
+    app: &AppHandle,
     name: &str,
     command: &str,
     args: &[String],
-    env: Option<&HashMap<String, String>>,
+    env: &IndexMap<String, String>,
+    cwd: Option<&Path>,
 ) -> Result<(), String> {
     println!(
         "Starting MCP server: {} with command: {} {}",
@@ -31,15 +37,18 @@ pub fn start_mcp_server(
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
-    if let Some(env_vars) = env {
-        for (key, value) in env_vars {
-            command.env(key, value);
-        }
+    command.envs(env);
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
     }
 
     match command.spawn() {
         Ok(mut child) => {
-            // 获取进程的输出流
+            // 获取进程的输入输出流
+            let stdin = child
+                .stdin
+                .take()
+                .ok_or_else(|| "Failed to capture stdin".to_string())?;
             let stdout = child
                 .stdout
                 .take()
@@ -49,16 +58,8 @@ pub fn start_mcp_server(
                 .take()
                 .ok_or_else(|| "Failed to capture stderr".to_string())?;
 
-            let name_clone = name.to_string();
-            // 在新线程中监控输出
-            thread::spawn(move || {
-                let reader = BufReader::new(stdout);
-                for line in reader.lines() {
-                    if let Ok(line) = line {
-                        println!("[{}] stdout: {}", name_clone, line);
-                    }
-                }
-            });
+            // stdout 交给 MCP 客户端会话处理
+            crate::mcp_client::attach(app, name, stdin, stdout);
 
             let name_clone = name.to_string();
             thread::spawn(move || {
@@ -75,6 +76,7 @@ pub fn start_mcp_server(
 
             match child.try_wait() {
                 Ok(Some(status)) => {
+                    crate::mcp_client::detach(name);
                     return Err(format!(
                         "MCP server failed to start: process exited with status {}",
                         status
@@ -100,6 +102,8 @@ pub fn start_mcp_server(
 }
 
 pub fn stop_mcp_server(name: &str) -> Result<(), String> {
+    crate::mcp_client::detach(name);
+
     let mut servers = RUNNING_SERVERS
         .lock()
         .map_err(|e| format!("Failed to lock servers: {}", e))?;
@@ -139,6 +143,8 @@ pub fn stop_mcp_server(name: &str) -> Result<(), String> {
 }
 
 pub fn stop_all_servers() -> Result<(), String> {
+    crate::mcp_client::detach_all();
+
     let mut servers = RUNNING_SERVERS
         .lock()
         .map_err(|e| format!("Failed to lock servers: {}", e))?;
@@ -191,26 +197,41 @@ pub async fn start_server(app_handle: AppHandle, name: String) -> Result<(), Str
         .get(&name)
         .ok_or_else(|| format!("Server {} not found", name))?;
 
-    // 从 HashMap 中获取命令
-    let command = server_config
-        .get("command")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| "Command not found in config".to_string())?;
+    start_entry(&app_handle, &name, server_config, None)
+        .and_then(|()| update_tray_status(app_handle).map_err(|e| e.to_string()))
+}
 
-    // 从 HashMap 中获取参数数组
-    let args = server_config
-        .get("args")
-        .and_then(|v| v.as_array())
-        .ok_or_else(|| "Args not found in config".to_string())?
-        .iter()
-        .filter_map(|v| v.as_str().map(String::from))
-        .collect::<Vec<String>>();
+// 启动一个配置条目，`base_dir` 是相对 cwd 的起点，也是未设置 cwd 时的工作目录
+pub fn start_entry(
+    app: &AppHandle,
+    name: &str,
+    entry: &ServerConfig,
+    base_dir: Option<&Path>,
+) -> Result<(), String> {
+    let ServerEntry::Stdio {
+        command,
+        args,
+        env,
+        cwd,
+    } = ServerEntry::parse(entry)
+        .map_err(|diagnostics| server_entry::format_diagnostics(&diagnostics))?
+    else {
+        return Err(format!(
+            "Server {} is remote and cannot be started locally",
+            name
+        ));
+    };
+
+    let cwd = match (cwd, base_dir) {
+        (Some(cwd), Some(base)) => Some(base.join(cwd)),
+        (Some(cwd), None) => Some(PathBuf::from(cwd)),
+        (None, base) => base.map(Path::to_path_buf),
+    };
 
     println!("Starting server: {} with command: {}", name, command,);
 
     // 启动服务器
-    start_mcp_server(&name, command, &args, None)
-        .and_then(|()| update_tray_status(app_handle).map_err(|e| e.to_string()))
+    start_mcp_server(app, name, &command, &args, &env, cwd.as_deref())
 }
 
 #[tauri::command]
@@ -220,66 +241,10 @@ pub async fn stop_server(app_handle: AppHandle, name: String) -> Result<(), Stri
 
 #[tauri::command]
 pub fn restart_claude_app() -> Result<(), String> {
-    // 先终止 Claude 进程
-    let kill_status = Command::new("killall")
-        .arg("Claude")
-        .status()
-        .map_err(|e| format!("Failed to kill Claude: {}", e))?;
-
-    if !kill_status.success() {
-        return Err("Failed to kill Claude process".to_string());
-    }
-
-    // 短暂延迟确保进程完全终止
-    thread::sleep(std::time::Duration::from_millis(500));
-
-    // 重新启动 Claude
-    let open_status = Command::new("open")
-        .arg("-a")
-        .arg("Claude")
-        .status()
-        .map_err(|e| format!("Failed to start Claude: {}", e))?;
-
-    if !open_status.success() {
-        return Err("Failed to start Claude".to_string());
-    }
-
-    Ok(())
+    crate::app_control::restart(crate::app_control::find_client_app("claude")?).map(|_| ())
 }
 
 #[tauri::command]
 pub fn restart_vscode_app() -> Result<(), String> {
-    // 先终止 VS Code 进程
-    let kill_status = Command::new("killall")
-        .arg("Visual Studio Code")
-        .status()
-        .map_err(|e| format!("Failed to kill VS Code: {}", e))?;
-
-    if !kill_status.success() {
-        // 尝试使用更常见的进程名
-        let kill_status2 = Command::new("killall")
-            .arg("Code")
-            .status()
-            .map_err(|e| format!("Failed to kill Code process: {}", e))?;
-        
-        if !kill_status2.success() {
-            return Err("Failed to kill VS Code process (tried both 'Visual Studio Code' and 'Code')".to_string());
-        }
-    }
-
-    // 短暂延迟确保进程完全终止
-    thread::sleep(std::time::Duration::from_millis(1000));
-
-    // 重新启动 VS Code
-    let open_status = Command::new("open")
-        .arg("-a")
-        .arg("Visual Studio Code")
-        .status()
-        .map_err(|e| format!("Failed to start VS Code: {}", e))?;
-
-    if !open_status.success() {
-        return Err("Failed to start VS Code".to_string());
-    }
-
-    Ok(())
+    crate::app_control::restart(crate::app_control::find_client_app("vscode")?).map(|_| ())
 }
@@ -1,25 +1,38 @@
-use crate::store::InstalledMcpServer;
-use reqwest;
+use crate::claude_config::ServerConfig;
+use crate::env_check::PreflightResult;
+use crate::plan::{fetched_package, OperationPlan, PreparedOperation, SideEffect};
+use crate::registries::RegistryError;
+use crate::server_entry::{self, ServerEntry, Severity};
+use crate::store::{DisabledServer, InstalledMcpServer};
+use crate::template_params::TemplateParameter;
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::AppHandle;
 use tauri_plugin_dialog::DialogExt;
 use tauri_plugin_fs::FsExt;
-use serde_json::Value as JsonValue;
 
 // 新的数据结构匹配 API 返回
 #[derive(Debug, Serialize, Deserialize)]
-struct ApiResponse {
-    code: i32,
-    msg: String,
-    data: ApiData,
+pub(crate) struct ApiResponse {
+    pub code: i32,
+    pub msg: String,
+    pub data: ApiData,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ApiData {
-    total: i32,
-    list: Vec<McpServerTemplate>,
+    pub total: i32,
+    pub list: Vec<McpServerTemplate>,
+    // 查询失败的 registry，其余 registry 的结果照常返回
+    #[serde(default)]
+    pub errors: Vec<RegistryError>,
+    // 有 registry 无法访问、结果来自缓存时，为缓存最早的确认时间（毫秒）
+    #[serde(default)]
+    pub stale_since: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -30,12 +43,33 @@ pub struct McpServerTemplate {
     pub command: String,
     pub args: Vec<String>,
     pub env: Option<HashMap<String, String>>,
+    // 旧模板的标记，新模板用 `parameters` 声明需要的输入
+    #[serde(default)]
     pub require_file_path: bool,
+    #[serde(default)]
+    pub parameters: Vec<TemplateParameter>,
     pub repo_url: String,
     pub created_at: String,
     pub updated_at: String,
     pub downloads: i32,
     pub total_usage_time: i32,
+    // 模板来自哪个 registry
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+impl template_filter::Listed for McpServerTemplate {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn require_file_path(&self) -> bool {
+        self.require_file_path
+    }
 }
 
 // 分页参数结构
@@ -47,66 +81,55 @@ pub struct PaginationParams {
     pub require_file_path: Option<bool>,
 }
 
-// 修改获取模板的函数
+// 同时查询所有启用的 registry 并合并结果
 #[tauri::command]
-pub async fn get_mcp_server_templates(params: PaginationParams) -> Result<ApiData, String> {
+pub async fn get_mcp_server_templates(
+    app: AppHandle,
+    params: PaginationParams,
+) -> Result<ApiData, String> {
     println!("Fetching templates with params: {:?}", params);
 
-    let client = reqwest::Client::new();
-
-    // 构建查询参数
-    let mut query = vec![
-        ("page", params.page.to_string()),
-        ("page_size", params.page_size.to_string()),
-    ];
-
-    if let Some(keyword) = params.keyword {
-        query.push(("keyword", keyword));
-    }
-
-    if let Some(require_file_path) = params.require_file_path {
-        query.push(("require_file_path", require_file_path.to_string()));
-    }
-
-    println!("Sending request with query: {:?}", query);
-
-    // 发送请求
-    let response = client
-        .get("http://127.0.0.1:3988/api/v1/templates")
-        .query(&query)
-        .send()
-        .await
-        .map_err(|e| {
-            println!("Request failed: {}", e);
-            format!("Failed to fetch templates: {}", e)
-        })?;
-
-    println!("Received response status: {}", response.status());
-
-    // 解析响应
-    let api_response: ApiResponse = response.json().await.map_err(|e| {
-        println!("Failed to parse response: {}", e);
-        format!("Failed to parse response: {}", e)
-    })?;
-
-    // println!("Parsed response: {:?}", api_response);
-
-    if api_response.code != 0 {
-        println!("API error: {}", api_response.msg);
-        return Err(api_response.msg);
-    }
-
-    Ok(api_response.data)
+    let registries = crate::store::get_template_registries(&app)?;
+    crate::registries::query_registries(registries, params).await
 }
 
-#[tauri::command]
-pub async fn install_mcp_server(app: AppHandle, template: McpServerTemplate) -> Result<(), String> {
-    // 在修改之前创建备份
-    crate::claude_config::backup_config()?;
-
-    // 保存到 store
-    crate::store::save_installed_server(
-        app.clone(),
+// 计算安装会做的修改：store 中的记录、各目标配置中的条目以及需要下载的包
+fn prepare_install(
+    app: &AppHandle,
+    template: &McpServerTemplate,
+    values: &HashMap<String, JsonValue>,
+    clients: Option<Vec<String>>,
+    project: Option<&Path>,
+) -> Result<PreparedOperation, String> {
+    let targets = crate::clients::resolve_targets(clients)?;
+    // 校验参数并把它们填入 args 和 env
+    let template = &crate::template_params::render(template, values)?;
+
+    // 创建新的服务器配置，env 为空时不会写入
+    let server_config = ServerEntry::Stdio {
+        command: template.command.clone(),
+        args: template.args.clone(),
+        env: template
+            .env
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .collect(),
+        cwd: None,
+    }
+    .to_config();
+
+    let mut prepared = PreparedOperation::edit(
+        format!("Install {}", template.name),
+        &targets,
+        project,
+        |servers| {
+            servers.insert(template.name.clone(), server_config.clone());
+            Ok(())
+        },
+    );
+    prepared.save_server(
+        crate::store::get_installed_servers(app)?.get(&template.name),
         InstalledMcpServer {
             id: template.id.clone(),
             name: template.name.clone(),
@@ -116,35 +139,117 @@ pub async fn install_mcp_server(app: AppHandle, template: McpServerTemplate) ->
             require_file_path: template.require_file_path,
             repo_url: template.repo_url.clone(),
         },
-    )
-    .await?;
+    );
+    if let Some(fetch) = fetched_package(&template.command, &template.args) {
+        prepared.side_effect(fetch);
+    }
+    Ok(prepared)
+}
 
-    // 获取当前配置
-    let mut claude_config = crate::claude_config::get_claude_config()?;
-
-    // 创建新的服务器配置
-    let mut server_config = HashMap::new();
-    server_config.insert("command".to_string(), serde_json::json!(template.command));
-    server_config.insert("args".to_string(), serde_json::json!(template.args));
-    
-    // 只在 env 存在且不为空时才添加
-    if let Some(env) = template.env {
-        if !env.is_empty() {
-            server_config.insert("env".to_string(), serde_json::json!(env));
-        }
+// `values` fills in the template's parameters. `clients` selects the target clients,
+// defaulting to Claude Desktop; "all" targets every installed client. `project` writes to
+// the project configs of clients that support them.
+#[tauri::command]
+pub async fn install_mcp_server(
+    app: AppHandle,
+    template: McpServerTemplate,
+    values: Option<HashMap<String, JsonValue>>,
+    clients: Option<Vec<String>>,
+    project: Option<String>,
+) -> Result<(), String> {
+    let project = project.map(PathBuf::from);
+    let label = format!("Install {}", template.name);
+
+    // 运行环境缺失或版本过低时不写入任何配置
+    let preflight = run_preflight(template.command.clone(), clients.clone()).await?;
+    if preflight.blocked {
+        return Err(preflight
+            .issues
+            .iter()
+            .filter(|i| i.severity == Severity::Error)
+            .map(|i| i.message.clone())
+            .collect::<Vec<_>>()
+            .join("; "));
     }
 
-    println!("Installing server with config: {:?}", &server_config);
-    
-    // 更新配置
-    claude_config.mcp_servers.insert(template.name, server_config);
+    // 记录安装时解析到的包版本，解析失败不影响安装
+    let lock = crate::lockfile::resolve_command(&template.command, &template.args).await;
+
+    // store 和配置一起提交，任一步失败都会回滚；写入前的版本会自动记入历史
+    crate::transaction::run(&app, label, project.as_deref(), async {
+        let values = values.unwrap_or_default();
+        prepare_install(&app, &template, &values, clients, project.as_deref())?
+            .apply(&app)
+            .await?;
+        crate::store::set_lock_entry(&app, &template.name, lock)
+    })
+    .await
+}
 
-    // 保存配置
-    crate::claude_config::save_claude_config(claude_config)?;
+// 从缓存中安装模板，registry 无法访问时也可用；`env` 为用户填写的环境变量
+#[tauri::command]
+pub async fn install_cached_template(
+    app: AppHandle,
+    registry: String,
+    id: String,
+    env: Option<HashMap<String, String>>,
+    values: Option<HashMap<String, JsonValue>>,
+    clients: Option<Vec<String>>,
+    project: Option<String>,
+) -> Result<(), String> {
+    let url = crate::store::get_template_registries(&app)?
+        .into_iter()
+        .find(|r| r.id == registry)
+        .map(|r| r.url)
+        .ok_or_else(|| format!("Unknown registry '{}'", registry))?;
+    let mut template = crate::template_cache::find_template(&url, &id).ok_or_else(|| {
+        format!(
+            "Template '{}' is not cached for registry '{}'",
+            id, registry
+        )
+    })?;
+    // 填写的值覆盖模板声明的变量，未填写的变量保留模板中的键
+    if let (Some(declared), Some(env)) = (template.env.as_mut(), env) {
+        declared.extend(env);
+    }
+    template.source = Some(registry);
+    install_mcp_server(app, template, values, clients, project).await
+}
 
-    println!("Server installed successfully");
+async fn run_preflight(
+    command: String,
+    clients: Option<Vec<String>>,
+) -> Result<PreflightResult, String> {
+    let targets = crate::clients::resolve_targets(clients)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let clients: Vec<(&str, &str)> = targets.iter().map(|c| (c.id(), c.name())).collect();
+        crate::env_check::preflight(&command, &clients)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
 
-    Ok(())
+// 检查模板需要的运行环境，返回阻止安装的错误和警告以及安装链接
+#[tauri::command]
+pub async fn preflight_mcp_server(
+    template: McpServerTemplate,
+    clients: Option<Vec<String>>,
+) -> Result<PreflightResult, String> {
+    run_preflight(template.command, clients).await
+}
+
+// 预览安装，不写入任何文件
+#[tauri::command]
+pub fn plan_install_mcp_server(
+    app: AppHandle,
+    template: McpServerTemplate,
+    values: Option<HashMap<String, JsonValue>>,
+    clients: Option<Vec<String>>,
+    project: Option<String>,
+) -> Result<OperationPlan, String> {
+    let project = project.map(PathBuf::from);
+    let values = values.unwrap_or_default();
+    Ok(prepare_install(&app, &template, &values, clients, project.as_deref())?.plan)
 }
 
 #[tauri::command]
@@ -153,85 +258,364 @@ pub fn is_mcp_server_installed(name: String) -> Result<bool, String> {
     Ok(config.mcp_servers.contains_key(&name))
 }
 
-#[tauri::command]
-pub async fn uninstall_mcp_server(app: AppHandle, name: String) -> Result<(), String> {
-    // 在修改之前创建备份
-    crate::claude_config::backup_config()?;
-
-    // 停止运行中的 server
-    crate::mcp_runner::stop_mcp_server(&name)?;
-
-    let mut config = crate::claude_config::get_claude_config()?;
+fn prepare_uninstall(
+    app: &AppHandle,
+    name: &str,
+    clients: Option<Vec<String>>,
+    project: Option<&Path>,
+) -> Result<PreparedOperation, String> {
+    let targets = crate::clients::resolve_targets(clients)?;
 
     // 检查服务器是否存在
-    if !config.mcp_servers.contains_key(&name) {
+    let found = targets.iter().any(|client| {
+        client
+            .read_servers(project)
+            .is_ok_and(|current| current.servers.contains_key(name))
+    });
+    if !found {
         return Err(format!("MCP server '{}' not found", name));
     }
 
-    // 删除服务器
-    config.mcp_servers.remove(&name);
+    let mut prepared = PreparedOperation::edit(
+        format!("Uninstall {}", name),
+        &targets,
+        project,
+        |servers| {
+            servers.shift_remove(name);
+            Ok(())
+        },
+    );
+    prepared.remove_server(crate::store::get_installed_servers(app)?.get(name), name);
 
-    // 保存更新后的配置
-    if let Err(e) = crate::claude_config::save_claude_config(config) {
-        // 如果保存失败，尝试恢复备份
-        crate::claude_config::restore_config_backup()?;
-        return Err(e);
+    // 停止运行中的 server
+    if crate::mcp_runner::get_server_status(name) {
+        prepared.side_effect(SideEffect::StopServer {
+            name: name.to_string(),
+        });
     }
+    Ok(prepared)
+}
 
-    // 从 store 中删除服务器配置
-    crate::store::remove_installed_server(app, name).await?;
+#[tauri::command]
+pub async fn uninstall_mcp_server(
+    app: AppHandle,
+    name: String,
+    clients: Option<Vec<String>>,
+    project: Option<String>,
+) -> Result<(), String> {
+    let project = project.map(PathBuf::from);
+    let label = format!("Uninstall {}", name);
+
+    // 删除服务器，写入是原子的，失败时配置和 store 一起回滚
+    crate::transaction::run(&app, label, project.as_deref(), async {
+        prepare_uninstall(&app, &name, clients, project.as_deref())?
+            .apply(&app)
+            .await?;
+        crate::store::set_lock_entry(&app, &name, None)
+    })
+    .await
+}
 
-    Ok(())
+#[tauri::command]
+pub fn plan_uninstall_mcp_server(
+    app: AppHandle,
+    name: String,
+    clients: Option<Vec<String>>,
+    project: Option<String>,
+) -> Result<OperationPlan, String> {
+    let project = project.map(PathBuf::from);
+    Ok(prepare_uninstall(&app, &name, clients, project.as_deref())?.plan)
 }
 
 #[tauri::command]
-pub async fn select_folder(app: tauri::AppHandle) -> Result<Option<String>, String> {
+pub async fn select_folder(
+    app: tauri::AppHandle,
+    server_name: Option<String>,
+) -> Result<Option<String>, String> {
     let (tx, rx) = mpsc::channel();
 
     app.dialog().file().pick_folders(move |folders| {
-        if let Some(folders) = folders {
-            if let Some(first) = folders.first() {
-                println!("Selected folder: {:?}", first);
-                let _ = tx.send(Some(first.to_string()));
-                return;
-            }
+        let folders: Vec<String> = folders
+            .unwrap_or_default()
+            .iter()
+            .map(|folder| folder.to_string())
+            .collect();
+        if folders.is_empty() {
+            println!("No folder selected");
+        } else {
+            println!("Selected folders: {:?}", folders);
         }
-        println!("No folder selected");
-        let _ = tx.send(None);
+        let _ = tx.send(folders);
     });
 
-    let result = rx
+    let folders = rx
         .recv()
         .map_err(|e| format!("Failed to receive result: {}", e))?;
 
-    if let Some(path) = &result {
+    let fs = app.fs_scope();
+    for path in &folders {
         println!("Allowing directory access: {}", path);
-        let fs = app.fs_scope();
         fs.allow_directory(path, true);
     }
 
-    Ok(result)
+    // 记录授权给该 server 的目录，作为它的 roots
+    if let Some(name) = server_name {
+        if !folders.is_empty() {
+            let label = format!("Grant folders to {}", name);
+            crate::transaction::run(&app, label, None, async {
+                crate::store::add_root_grants(&app, &name, &folders)
+            })
+            .await?;
+        }
+    }
+
+    Ok(folders.into_iter().next())
+}
+
+fn prepare_update(
+    name: &str,
+    config: &ServerConfig,
+    clients: Option<Vec<String>>,
+    project: Option<&Path>,
+) -> Result<PreparedOperation, String> {
+    let targets = crate::clients::resolve_targets(clients)?;
+
+    // 写入前校验条目，只有错误级别的问题会阻止保存
+    for client in &targets {
+        let diagnostics = server_entry::validate_entry(
+            &server_entry::entry_path(client.servers_pointer(), name),
+            config,
+        );
+        if server_entry::has_errors(&diagnostics) {
+            return Err(format!(
+                "Invalid server config: {}",
+                server_entry::format_diagnostics(&diagnostics)
+            ));
+        }
+    }
+
+    let mut previous = Vec::new();
+    let mut prepared =
+        PreparedOperation::edit(format!("Edit {}", name), &targets, project, |servers| {
+            previous.extend(servers.insert(name.to_string(), config.clone()));
+            Ok(())
+        });
+
+    // 命令或参数换成了新的包时，客户端下次启动会去下载
+    if let Ok(ServerEntry::Stdio { command, args, .. }) = ServerEntry::parse(config) {
+        if let Some(fetch) = fetched_package(&command, &args) {
+            let known = previous
+                .iter()
+                .any(|entry| match ServerEntry::parse(entry) {
+                    Ok(ServerEntry::Stdio { command, args, .. }) => {
+                        fetched_package(&command, &args).as_ref() == Some(&fetch)
+                    }
+                    _ => false,
+                });
+            if !known {
+                prepared.side_effect(fetch);
+            }
+        }
+    }
+    Ok(prepared)
 }
 
 #[tauri::command]
-pub fn update_mcp_server_config(
-    name: String, 
-    config: HashMap<String, JsonValue>
+pub async fn update_mcp_server_config(
+    app: AppHandle,
+    name: String,
+    config: ServerConfig,
+    clients: Option<Vec<String>>,
+    project: Option<String>,
 ) -> Result<(), String> {
-    // 在修改之前创建备份
-    crate::claude_config::backup_config()?;
+    let project = project.map(PathBuf::from);
+    let label = format!("Edit {}", name);
+
+    // 更新配置，写入是原子的，失败时原文件保持不变
+    crate::transaction::run(&app, label, project.as_deref(), async {
+        prepare_update(&name, &config, clients, project.as_deref())?
+            .apply(&app)
+            .await
+    })
+    .await
+}
 
-    let mut claude_config = crate::claude_config::get_claude_config()?;
+#[tauri::command]
+pub fn plan_update_mcp_server_config(
+    name: String,
+    config: ServerConfig,
+    clients: Option<Vec<String>>,
+    project: Option<String>,
+) -> Result<OperationPlan, String> {
+    let project = project.map(PathBuf::from);
+    Ok(prepare_update(&name, &config, clients, project.as_deref())?.plan)
+}
 
-    // 更新配置
-    claude_config.mcp_servers.insert(name, config);
+// 从客户端配置中取出 server，返回它原来的位置和原样的定义
+fn take_entry(
+    adapter: &dyn crate::clients::ClientAdapter,
+    project: Option<&Path>,
+    name: &str,
+) -> Result<(usize, ServerConfig), String> {
+    let mut current = adapter.read_servers(project)?;
+    let (position, _, entry) = current
+        .servers
+        .shift_remove_full(name)
+        .ok_or_else(|| format!("MCP server '{}' not found", name))?;
+    adapter.write_servers(&current)?;
+    Ok((position, entry))
+}
 
-    // 保存更新后的配置
-    if let Err(e) = crate::claude_config::save_claude_config(claude_config) {
-        // 如果保存失败，尝试恢复备份
-        crate::claude_config::restore_config_backup()?;
-        return Err(e);
+// 把取出的定义原样放回原来的位置，不经过 prepare_entry
+fn put_back_entry(
+    adapter: &dyn crate::clients::ClientAdapter,
+    project: Option<&Path>,
+    name: &str,
+    position: usize,
+    entry: ServerConfig,
+) -> Result<(), String> {
+    let mut current = adapter.read_servers(project)?;
+    if current.servers.contains_key(name) {
+        return Err(format!(
+            "MCP server '{}' already exists in {}",
+            name,
+            adapter.name()
+        ));
     }
-
+    let position = position.min(current.servers.len());
+    current
+        .servers
+        .shift_insert(position, name.to_string(), entry);
+    adapter.write_servers_unprepared(&current)?;
     Ok(())
 }
+
+// 禁用：从客户端配置中移除，但把原样的定义保存在 store 中
+#[tauri::command]
+pub async fn disable_mcp_server(
+    app: AppHandle,
+    name: String,
+    client: Option<String>,
+    project: Option<String>,
+) -> Result<(), String> {
+    let dir = project.as_deref().map(PathBuf::from);
+    let label = format!("Disable {}", name);
+
+    crate::transaction::run(&app, label, dir.as_deref(), async {
+        let client = client.unwrap_or_else(|| "claude".to_string());
+        let adapter = crate::clients::find_client(&client)?;
+        let (position, entry) = take_entry(adapter, dir.as_deref(), &name)?;
+
+        // 定义保存在 store 中，任一步失败时事务会一起回滚
+        crate::store::park_disabled_server(
+            &app,
+            DisabledServer {
+                name: name.clone(),
+                client: client.clone(),
+                project: project.clone(),
+                entry,
+                position,
+                disabled_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or_default(),
+            },
+        )?;
+
+        // 停止运行中的 server
+        if client == "claude" {
+            crate::mcp_runner::stop_mcp_server(&name)?;
+        }
+        Ok(())
+    })
+    .await?;
+    crate::tray::update_tray_status(app).map_err(|e| e.to_string())
+}
+
+// 启用：把保存的定义放回客户端配置中原来的位置
+#[tauri::command]
+pub async fn enable_mcp_server(
+    app: AppHandle,
+    name: String,
+    client: Option<String>,
+    project: Option<String>,
+) -> Result<(), String> {
+    let dir = project.as_deref().map(PathBuf::from);
+    let label = format!("Enable {}", name);
+
+    crate::transaction::run(&app, label, dir.as_deref(), async {
+        let client = client.unwrap_or_else(|| "claude".to_string());
+        let adapter = crate::clients::find_client(&client)?;
+
+        let disabled = crate::store::get_disabled_servers(&app)?
+            .into_iter()
+            .find(|s| s.client == client && s.project == project && s.name == name)
+            .ok_or_else(|| format!("MCP server '{}' is not disabled", name))?;
+
+        put_back_entry(
+            adapter,
+            dir.as_deref(),
+            &name,
+            disabled.position,
+            disabled.entry,
+        )?;
+
+        crate::store::take_disabled_server(&app, &client, project.as_deref(), &name)?;
+        Ok(())
+    })
+    .await?;
+    crate::tray::update_tray_status(app).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::testing::test_client;
+    use crate::clients::ClientAdapter;
+    use std::fs;
+
+    #[test]
+    fn disabling_and_enabling_restores_the_entry() {
+        let client = test_client("disable-enable");
+        let path = client.config_path(None).unwrap();
+        let original = r#"{
+  // Servers of this workspace
+  "servers": {
+    "first": {
+      "command": "uvx",
+      "args": [
+        "mcp-server-git"
+      ]
+    },
+    "db": {
+      "command": "npx",
+      "args": [
+        "-y",
+        "@modelcontextprotocol/server-postgres"
+      ],
+      "env": {
+        "PGHOST": "localhost"
+      },
+      "x-notes": {
+        "owner": "data"
+      }
+    },
+    "last": {
+      "url": "https://example.com/mcp"
+    }
+  }
+}
+"#;
+        fs::write(&path, original).unwrap();
+
+        let (position, entry) = take_entry(client, None, "db").unwrap();
+        assert_eq!(position, 1);
+        let servers = client.read_servers(None).unwrap().servers;
+        assert_eq!(servers.keys().collect::<Vec<_>>(), ["first", "last"]);
+
+        put_back_entry(client, None, "db", position, entry.clone()).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+        assert!(put_back_entry(client, None, "db", position, entry).is_err());
+        let _ = fs::remove_dir_all(&client.0);
+    }
+}
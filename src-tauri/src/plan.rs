@@ -0,0 +1,306 @@
+use crate::claude_config::ServerConfig;
+use crate::clients::{ClientAdapter, ClientServers};
+use crate::config_diff::{self, ChangeKind, FieldChange, ServerChange};
+use crate::store::InstalledMcpServer;
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::path::Path;
+use tauri::AppHandle;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ConfigPlan {
+    pub client: String,
+    pub config_path: String,
+    pub changes: Vec<ServerChange>,
+    // Set when the config cannot be read or edited; the operation would fail
+    pub error: Option<String>,
+}
+
+// A change to the store's record of an installed server
+#[derive(Debug, Serialize, Clone)]
+pub struct StorePlan {
+    pub name: String,
+    pub kind: ChangeKind,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SideEffect {
+    // A running server that is stopped
+    StopServer { name: String },
+    // A package the client downloads the next time it starts the server
+    FetchPackage { runner: String, package: String },
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct OperationPlan {
+    pub operation: String,
+    pub configs: Vec<ConfigPlan>,
+    pub store: Vec<StorePlan>,
+    pub side_effects: Vec<SideEffect>,
+}
+
+enum StoreWrite {
+    Save(InstalledMcpServer),
+    Remove(String),
+}
+
+// The writes behind a plan; applying them produces exactly the planned changes
+pub struct PreparedOperation {
+    pub plan: OperationPlan,
+    writes: Vec<(&'static dyn ClientAdapter, ClientServers)>,
+    store: Option<StoreWrite>,
+}
+
+impl PreparedOperation {
+    // Edit the targets' servers in memory and diff the result against what is on disk
+    pub fn edit<F>(
+        operation: String,
+        targets: &[&'static dyn ClientAdapter],
+        project: Option<&Path>,
+        mut edit: F,
+    ) -> Self
+    where
+        F: FnMut(&mut IndexMap<String, ServerConfig>) -> Result<(), String>,
+    {
+        let mut configs = Vec::new();
+        let mut writes = Vec::new();
+        for client in targets {
+            let mut plan = ConfigPlan {
+                client: client.id().to_string(),
+                config_path: client
+                    .config_path(project)
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                changes: Vec::new(),
+                error: None,
+            };
+            let result = client.read_servers(project).and_then(|mut current| {
+                let before = current.servers.clone();
+                edit(&mut current.servers)?;
+                // Diff what is written, with changed entries in the client's shape
+                current.servers = client.written_servers(&before, &current.servers);
+                Ok((before, current))
+            });
+            match result {
+                Ok((before, current)) => {
+                    plan.changes = config_diff::diff_server_maps(&before, &current.servers);
+                    if !plan.changes.is_empty() {
+                        writes.push((*client, current));
+                    }
+                }
+                Err(e) => plan.error = Some(e),
+            }
+            configs.push(plan);
+        }
+
+        PreparedOperation {
+            plan: OperationPlan {
+                operation,
+                configs,
+                store: Vec::new(),
+                side_effects: Vec::new(),
+            },
+            writes,
+            store: None,
+        }
+    }
+
+    pub fn save_server(&mut self, before: Option<&InstalledMcpServer>, after: InstalledMcpServer) {
+        self.plan
+            .store
+            .push(store_plan(&after.name, before, Some(&after)));
+        self.store = Some(StoreWrite::Save(after));
+    }
+
+    pub fn remove_server(&mut self, before: Option<&InstalledMcpServer>, name: &str) {
+        if before.is_some() {
+            self.plan.store.push(store_plan(name, before, None));
+            self.store = Some(StoreWrite::Remove(name.to_string()));
+        }
+    }
+
+    pub fn side_effect(&mut self, effect: SideEffect) {
+        if !self.plan.side_effects.contains(&effect) {
+            self.plan.side_effects.push(effect);
+        }
+    }
+
+    // Carry out the plan. Configs changed on disk since planning fail with a conflict
+    // instead of being overwritten.
+    pub async fn apply(self, app: &AppHandle) -> Result<(), String> {
+        let errors: Vec<String> = self
+            .plan
+            .configs
+            .iter()
+            .filter_map(|c| c.error.as_ref().map(|e| format!("{}: {}", c.client, e)))
+            .collect();
+        if !errors.is_empty() {
+            return Err(errors.join("; "));
+        }
+
+        for effect in &self.plan.side_effects {
+            if let SideEffect::StopServer { name } = effect {
+                crate::mcp_runner::stop_mcp_server(name)?;
+            }
+        }
+        for (client, servers) in &self.writes {
+            client
+                .write_servers(servers)
+                .map_err(|e| format!("{}: {}", client.name(), e))?;
+        }
+        match self.store {
+            Some(StoreWrite::Save(server)) => {
+                crate::store::save_installed_server(app.clone(), server).await
+            }
+            Some(StoreWrite::Remove(name)) => {
+                crate::store::remove_installed_server(app.clone(), name).await
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+fn store_plan(
+    name: &str,
+    before: Option<&InstalledMcpServer>,
+    after: Option<&InstalledMcpServer>,
+) -> StorePlan {
+    let to_value =
+        |server: Option<&InstalledMcpServer>| server.and_then(|s| serde_json::to_value(s).ok());
+    let (before, after) = (to_value(before), to_value(after));
+    let kind = match (&before, &after) {
+        (None, _) => ChangeKind::Added,
+        (_, None) => ChangeKind::Removed,
+        _ => ChangeKind::Modified,
+    };
+
+    let mut changes = Vec::new();
+    config_diff::diff_values(
+        "",
+        before.as_ref().unwrap_or(&JsonValue::Null),
+        after.as_ref().unwrap_or(&JsonValue::Null),
+        &mut changes,
+    );
+    StorePlan {
+        name: name.to_string(),
+        kind,
+        changes,
+    }
+}
+
+// Docker flags whose value is the next argument
+const DOCKER_VALUE_FLAGS: &[&str] = &[
+    "-e",
+    "--env",
+    "-v",
+    "--volume",
+    "-p",
+    "--publish",
+    "--name",
+    "--network",
+    "-w",
+    "--workdir",
+    "--env-file",
+    "--mount",
+    "-u",
+    "--user",
+    "--entrypoint",
+];
+
+// The package a launcher command downloads on first start, if any
+pub fn fetched_package(command: &str, args: &[String]) -> Option<SideEffect> {
+    let runner = Path::new(command)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())?;
+    let package = match runner.as_str() {
+        "npx" | "bunx" | "uvx" => args.iter().find(|a| !a.starts_with('-')),
+        "pnpm" | "yarn" => match args.first().map(String::as_str) {
+            Some("dlx") => args[1..].iter().find(|a| !a.starts_with('-')),
+            _ => None,
+        },
+        "docker" | "podman" => {
+            let start = args.iter().position(|a| a == "run")? + 1;
+            let mut rest = args[start..].iter();
+            let mut image = None;
+            while let Some(arg) = rest.next() {
+                if DOCKER_VALUE_FLAGS.contains(&arg.as_str()) {
+                    rest.next();
+                } else if !arg.starts_with('-') {
+                    image = Some(arg);
+                    break;
+                }
+            }
+            image
+        }
+        _ => None,
+    }?;
+    Some(SideEffect::FetchPackage {
+        runner,
+        package: package.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fetched(command: &str, args: &[&str]) -> Option<(String, String)> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        match fetched_package(command, &args)? {
+            SideEffect::FetchPackage { runner, package } => Some((runner, package)),
+            SideEffect::StopServer { .. } => None,
+        }
+    }
+
+    #[test]
+    fn package_runners_name_the_first_positional_argument() {
+        assert_eq!(
+            fetched(
+                "/usr/local/bin/npx",
+                &["-y", "@scope/server@1.2.0", "--port", "1"]
+            ),
+            Some(("npx".to_string(), "@scope/server@1.2.0".to_string()))
+        );
+        assert_eq!(
+            fetched("uvx.exe", &["mcp-server-git"]),
+            Some(("uvx".to_string(), "mcp-server-git".to_string()))
+        );
+        assert_eq!(fetched("node", &["server.js"]), None);
+    }
+
+    #[test]
+    fn pnpm_and_yarn_fetch_only_with_dlx() {
+        assert_eq!(
+            fetched("pnpm", &["dlx", "--silent", "server-pkg"]),
+            Some(("pnpm".to_string(), "server-pkg".to_string()))
+        );
+        assert_eq!(fetched("pnpm", &["exec", "server-pkg"]), None);
+        assert_eq!(fetched("yarn", &[]), None);
+    }
+
+    #[test]
+    fn docker_flags_and_their_values_are_skipped() {
+        assert_eq!(
+            fetched(
+                "docker",
+                &[
+                    "run",
+                    "-i",
+                    "--rm",
+                    "-e",
+                    "TOKEN",
+                    "-v",
+                    "/a:/b",
+                    "--init",
+                    "ghcr.io/org/server:1",
+                    "serve"
+                ]
+            ),
+            Some(("docker".to_string(), "ghcr.io/org/server:1".to_string()))
+        );
+        assert_eq!(fetched("podman", &["pull", "image"]), None);
+    }
+}
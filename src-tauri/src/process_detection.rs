@@ -192,7 +192,7 @@ fn parse_command_line(command_line: &str) -> (String, Vec<String>) {
     (command, args)
 }
 
-fn get_vscode_settings_paths() -> Vec<PathBuf> {
+pub(crate) fn get_vscode_settings_paths() -> Vec<PathBuf> {
     let mut paths = Vec::new();
     
     if let Ok(home) = std::env::var("HOME") {
@@ -201,28 +201,13 @@ fn get_vscode_settings_paths() -> Vec<PathBuf> {
             "{}/Library/Application Support/Code/User/settings.json", 
             home
         )));
-        
-        // Workspace settings (this is harder to find automatically)
-        // We'll check common workspace locations
-        let common_workspace_dirs = [
-            format!("{}/Desktop", home),
-            format!("{}/Documents", home),
-            format!("{}/Projects", home),
-            format!("{}/workspace", home),
-            format!("{}/dev", home),
-        ];
-        
-        for workspace_dir in common_workspace_dirs {
-            if let Ok(entries) = fs::read_dir(&workspace_dir) {
-                for entry in entries.flatten() {
-                    if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
-                        let vscode_settings = entry.path().join(".vscode").join("settings.json");
-                        if vscode_settings.exists() {
-                            paths.push(vscode_settings);
-                        }
-                    }
-                }
-            }
+    }
+
+    // Workspace settings of the projects registered in MCP Manager
+    for project in crate::projects::registered_paths() {
+        let vscode_settings = project.join(".vscode").join("settings.json");
+        if vscode_settings.exists() {
+            paths.push(vscode_settings);
         }
     }
     
@@ -342,7 +327,7 @@ async fn get_claude_mcp_servers() -> Result<Vec<RunningMcpServer>, String> {
 }
 
 // Function to strip comments and fix common JSON issues (for VS Code settings files)
-fn strip_json_comments(content: &str) -> String {
+pub(crate) fn strip_json_comments(content: &str) -> String {
     let mut cleaned = String::new();
     let mut in_string = false;
     let mut escape_next = false;
@@ -0,0 +1,300 @@
+use crate::app_control::RestartResult;
+use crate::claude_config::ServerConfig;
+use crate::clients;
+use crate::server_entry::ServerEntry;
+use crate::store::{DisabledServer, InstalledMcpServer, Profile};
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProfileList {
+    pub profiles: Vec<Profile>,
+    // Active profile name by client id
+    pub active: HashMap<String, String>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn is_parked(server: &DisabledServer, client: &str, name: &str) -> bool {
+    server.client == client && server.project.is_none() && server.name == name
+}
+
+// Current definition of a server: the client's entry, its parked copy, or the store's template
+fn find_definition(
+    client: &str,
+    current: &IndexMap<String, ServerConfig>,
+    disabled: &[DisabledServer],
+    installed: &HashMap<String, InstalledMcpServer>,
+    name: &str,
+) -> Result<ServerConfig, String> {
+    if let Some(entry) = current.get(name) {
+        return Ok(entry.clone());
+    }
+    if let Some(parked) = disabled.iter().find(|s| is_parked(s, client, name)) {
+        return Ok(parked.entry.clone());
+    }
+    if let Some(server) = installed.get(name) {
+        return Ok(ServerEntry::Stdio {
+            command: server.command.clone(),
+            args: server.args.clone(),
+            env: server.env.clone().unwrap_or_default().into_iter().collect(),
+            cwd: None,
+        }
+        .to_config());
+    }
+    Err(format!("MCP server '{}' not found", name))
+}
+
+// Update the parked servers for switching `current` to `profile`: servers left out are
+// parked at their position, the profile's own are taken back. Returns the servers left out.
+fn park_left_out(
+    client: &str,
+    current: &IndexMap<String, ServerConfig>,
+    profile: &Profile,
+    disabled: &mut Vec<DisabledServer>,
+    disabled_at: u64,
+) -> Vec<String> {
+    // Servers parked again replace their older copy
+    disabled.retain(|s| {
+        let switched = profile.servers.contains_key(&s.name) || current.contains_key(&s.name);
+        !(switched && is_parked(s, client, &s.name))
+    });
+    let mut removed = Vec::new();
+    for (position, (server, entry)) in current.iter().enumerate() {
+        if profile.servers.contains_key(server) {
+            continue;
+        }
+        disabled.push(DisabledServer {
+            name: server.clone(),
+            client: client.to_string(),
+            project: None,
+            entry: entry.clone(),
+            position,
+            disabled_at,
+        });
+        removed.push(server.clone());
+    }
+    removed
+}
+
+#[tauri::command]
+pub async fn list_profiles(app: AppHandle) -> Result<ProfileList, String> {
+    Ok(ProfileList {
+        profiles: crate::store::get_profiles(&app)?.into_values().collect(),
+        active: crate::store::get_active_profiles(&app)?,
+    })
+}
+
+// Save a profile from the current definitions of `servers`, replacing one with the same name
+#[tauri::command]
+pub async fn save_profile(
+    app: AppHandle,
+    name: String,
+    servers: Vec<String>,
+    client: Option<String>,
+) -> Result<Profile, String> {
+    if name.trim().is_empty() {
+        return Err("Profile name is empty".to_string());
+    }
+    let client = client.unwrap_or_else(|| "claude".to_string());
+    let current = clients::find_client(&client)?.read_servers(None)?.servers;
+    let disabled = crate::store::get_disabled_servers(&app)?;
+    let installed = crate::store::get_installed_servers(&app)?;
+
+    let mut profile = Profile {
+        name: name.clone(),
+        servers: Default::default(),
+    };
+    for server in servers {
+        let entry = find_definition(&client, &current, &disabled, &installed, &server)?;
+        profile.servers.insert(server, entry);
+    }
+
+    let label = format!("Save profile {}", name);
+    crate::transaction::run(&app, label, None, async {
+        let mut profiles = crate::store::get_profiles(&app)?;
+        profiles.insert(name, profile.clone());
+        crate::store::save_profiles(&app, &profiles)
+    })
+    .await?;
+    Ok(profile)
+}
+
+#[tauri::command]
+pub async fn delete_profile(app: AppHandle, name: String) -> Result<(), String> {
+    let label = format!("Delete profile {}", name);
+    crate::transaction::run(&app, label, None, async {
+        let mut profiles = crate::store::get_profiles(&app)?;
+        if profiles.shift_remove(&name).is_none() {
+            return Err(format!("Profile '{}' not found", name));
+        }
+        crate::store::save_profiles(&app, &profiles)?;
+
+        for (client, active) in crate::store::get_active_profiles(&app)? {
+            if active == name {
+                crate::store::set_active_profile(&app, &client, None)?;
+            }
+        }
+        Ok(())
+    })
+    .await?;
+    crate::tray::update_tray_status(app).map_err(|e| e.to_string())
+}
+
+// Replace the client's servers with the profile's; servers not in the profile are parked as
+// disabled servers
+#[tauri::command]
+pub async fn activate_profile(
+    app: AppHandle,
+    name: String,
+    client: Option<String>,
+    restart: Option<bool>,
+) -> Result<Option<RestartResult>, String> {
+    let profile = crate::store::get_profiles(&app)?
+        .shift_remove(&name)
+        .ok_or_else(|| format!("Profile '{}' not found", name))?;
+    let client = client.unwrap_or_else(|| "claude".to_string());
+    let adapter = clients::find_client(&client)?;
+
+    let label = format!("Activate profile {}", name);
+    let removed = crate::transaction::run(&app, label, None, async {
+        let mut current = adapter.read_servers(None)?;
+
+        // Servers left out are parked as disabled, so switching back restores them
+        let mut disabled = crate::store::get_disabled_servers(&app)?;
+        let removed = park_left_out(&client, &current.servers, &profile, &mut disabled, now_ms());
+        crate::store::save_disabled_servers(&app, &disabled)?;
+
+        current.servers = profile.servers;
+        adapter.write_servers(&current)?;
+        crate::store::set_active_profile(&app, &client, Some(&name))?;
+        Ok(removed)
+    })
+    .await?;
+
+    // 停止不在 profile 中的运行中 server
+    if client == "claude" {
+        for server in removed {
+            crate::mcp_runner::stop_mcp_server(&server)?;
+        }
+    }
+    crate::tray::update_tray_status(app).map_err(|e| e.to_string())?;
+
+    if restart.unwrap_or(false) {
+        adapter.restart()
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::testing::test_client;
+    use crate::clients::ClientAdapter;
+    use serde_json::json;
+    use std::fs;
+
+    fn entry(command: &str) -> ServerConfig {
+        json!({ "command": command, "args": [] })
+            .as_object()
+            .unwrap()
+            .clone()
+    }
+
+    fn parked(name: &str, client: &str, command: &str, position: usize) -> DisabledServer {
+        DisabledServer {
+            name: name.to_string(),
+            client: client.to_string(),
+            project: None,
+            entry: entry(command),
+            position,
+            disabled_at: 0,
+        }
+    }
+
+    #[test]
+    fn definitions_fall_back_to_parked_then_store() {
+        let current = IndexMap::from([("live".to_string(), entry("live"))]);
+        let disabled = vec![
+            parked("live", "claude", "stale", 0),
+            parked("parked", "claude", "parked", 1),
+            parked("other", "cursor", "other", 0),
+        ];
+        let installed = HashMap::from([(
+            "stored".to_string(),
+            InstalledMcpServer {
+                id: "stored".to_string(),
+                name: "stored".to_string(),
+                command: "uvx".to_string(),
+                args: vec!["mcp-server-stored".to_string()],
+                env: None,
+                require_file_path: false,
+                repo_url: String::new(),
+            },
+        )]);
+        let find = |name| find_definition("claude", &current, &disabled, &installed, name);
+
+        assert_eq!(find("live").unwrap(), entry("live"));
+        assert_eq!(find("parked").unwrap(), entry("parked"));
+        assert_eq!(
+            find("stored").unwrap(),
+            json!({ "command": "uvx", "args": ["mcp-server-stored"] })
+                .as_object()
+                .unwrap()
+                .clone()
+        );
+        // Another client's parked server is not this client's definition
+        assert!(find("other").is_err());
+    }
+
+    #[test]
+    fn activating_parks_left_out_servers_and_takes_back_the_profiles() {
+        let client = test_client("profiles-activate");
+        let path = client.config_path(None).unwrap();
+        fs::write(
+            &path,
+            r#"{ "servers": { "a": { "command": "a" }, "b": { "command": "b" } } }"#,
+        )
+        .unwrap();
+
+        let profile = Profile {
+            name: "work".to_string(),
+            servers: IndexMap::from([("b".to_string(), entry("b")), ("c".to_string(), entry("c"))]),
+        };
+        let mut disabled = vec![
+            parked("c", "test", "c", 2),
+            parked("a", "test", "older", 0),
+            parked("c", "cursor", "c", 0),
+        ];
+
+        let mut current = client.read_servers(None).unwrap();
+        let removed = park_left_out("test", &current.servers, &profile, &mut disabled, 7);
+        current.servers = profile.servers.clone();
+        client.write_servers(&current).unwrap();
+
+        assert_eq!(removed, ["a"]);
+        let kept: Vec<_> = disabled
+            .iter()
+            .map(|s| (s.name.as_str(), s.client.as_str(), s.position))
+            .collect();
+        assert_eq!(kept, [("c", "cursor", 0), ("a", "test", 0)]);
+        assert_eq!(disabled[1].disabled_at, 7);
+        assert_eq!(
+            disabled[1].entry,
+            json!({ "command": "a" }).as_object().unwrap().clone()
+        );
+
+        let servers = client.read_servers(None).unwrap().servers;
+        assert_eq!(servers.keys().collect::<Vec<_>>(), ["b", "c"]);
+        let _ = fs::remove_dir_all(&client.0);
+    }
+}
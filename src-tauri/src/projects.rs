@@ -0,0 +1,340 @@
+use crate::claude_config::ServerConfig;
+use crate::clients;
+use crate::server_entry::{self, Diagnostic};
+use crate::store::{ProjectEntry, ProjectScanSettings};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Runtime};
+
+// Registered project directories, for code without access to the store
+static REGISTERED: Lazy<Mutex<Vec<PathBuf>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectServer {
+    pub name: String,
+    // Id the server runs under when started from this project
+    pub run_id: String,
+    pub entry: ServerConfig,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectConfig {
+    pub client: String,
+    pub config_path: String,
+    pub servers: Vec<ProjectServer>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectInfo {
+    pub path: String,
+    pub name: String,
+    pub exists: bool,
+    pub configs: Vec<ProjectConfig>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    match crate::store::get_projects(app) {
+        Ok(projects) => set_registered(&projects),
+        Err(e) => eprintln!("Failed to load projects: {}", e),
+    }
+}
+
+fn set_registered(projects: &[ProjectEntry]) {
+    if let Ok(mut registered) = REGISTERED.lock() {
+        *registered = projects.iter().map(|p| PathBuf::from(&p.path)).collect();
+    }
+    crate::config_watcher::reload();
+}
+
+pub fn registered_paths() -> Vec<PathBuf> {
+    REGISTERED.lock().map(|r| r.clone()).unwrap_or_default()
+}
+
+pub fn project_server_id(project: &str, name: &str) -> String {
+    format!("{}#{}", project, name)
+}
+
+fn project_configs(project: &Path) -> Vec<ProjectConfig> {
+    clients::CLIENTS
+        .iter()
+        .filter_map(|client| {
+            let path = client.config_path(Some(project)).ok()?;
+            if !path.is_file() {
+                return None;
+            }
+
+            let mut config = ProjectConfig {
+                client: client.id().to_string(),
+                config_path: path.to_string_lossy().to_string(),
+                servers: Vec::new(),
+                error: None,
+            };
+            match client.read_servers(Some(project)) {
+                Ok(current) => {
+                    config.servers = current
+                        .servers
+                        .into_iter()
+                        .map(|(name, entry)| ProjectServer {
+                            run_id: project_server_id(&project.to_string_lossy(), &name),
+                            diagnostics: server_entry::validate_entry(
+                                &server_entry::entry_path(client.servers_pointer(), &name),
+                                &entry,
+                            ),
+                            name,
+                            entry,
+                        })
+                        .collect();
+                }
+                Err(e) => config.error = Some(e),
+            }
+            Some(config)
+        })
+        .collect()
+}
+
+fn describe(project: &ProjectEntry) -> ProjectInfo {
+    let path = Path::new(&project.path);
+    ProjectInfo {
+        path: project.path.clone(),
+        name: project.name.clone(),
+        exists: path.is_dir(),
+        configs: project_configs(path),
+    }
+}
+
+fn has_servers(dir: &Path) -> bool {
+    project_configs(dir)
+        .iter()
+        .any(|config| !config.servers.is_empty())
+}
+
+// Match a directory name against an ignore rule where `*` matches any run of characters
+fn matches_rule(rule: &str, name: &str) -> bool {
+    let parts: Vec<&str> = rule.split('*').collect();
+    if parts.len() == 1 {
+        return rule == name;
+    }
+
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !name.starts_with(first) || !name[first.len()..].ends_with(last) {
+        return false;
+    }
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+fn scan_dir(dir: &Path, depth: usize, settings: &ProjectScanSettings, found: &mut Vec<PathBuf>) {
+    if has_servers(dir) {
+        found.push(dir.to_path_buf());
+    }
+    if depth >= settings.max_depth {
+        return;
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        // Symlinks are not followed, so loops cannot occur
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if settings.ignore.iter().any(|rule| matches_rule(rule, &name)) {
+            continue;
+        }
+        scan_dir(&entry.path(), depth + 1, settings, found);
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+// Add directories to the registry, returning the entries that were new
+fn register(app: &AppHandle, dirs: Vec<PathBuf>) -> Result<Vec<ProjectEntry>, String> {
+    let mut projects = crate::store::get_projects(app)?;
+    let mut added = Vec::new();
+    for dir in dirs {
+        let path = dir.to_string_lossy().to_string();
+        if projects.iter().any(|p| p.path == path) {
+            continue;
+        }
+        let entry = ProjectEntry {
+            name: dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.clone()),
+            path,
+            added_at: now_ms(),
+        };
+        projects.push(entry.clone());
+        added.push(entry);
+    }
+
+    if !added.is_empty() {
+        crate::store::save_projects(app, &projects)?;
+        set_registered(&projects);
+    }
+    Ok(added)
+}
+
+#[tauri::command]
+pub async fn list_projects(app: AppHandle) -> Result<Vec<ProjectInfo>, String> {
+    Ok(crate::store::get_projects(&app)?
+        .iter()
+        .map(describe)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn add_project(app: AppHandle, path: String) -> Result<ProjectInfo, String> {
+    let dir = fs::canonicalize(&path).map_err(|e| format!("Invalid project path: {}", e))?;
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    register(&app, vec![dir.clone()])?;
+
+    crate::store::get_projects(&app)?
+        .iter()
+        .find(|p| Path::new(&p.path) == dir)
+        .map(describe)
+        .ok_or_else(|| "Failed to register project".to_string())
+}
+
+#[tauri::command]
+pub async fn remove_project(app: AppHandle, path: String) -> Result<(), String> {
+    let mut projects = crate::store::get_projects(&app)?;
+    projects.retain(|p| p.path != path);
+    crate::store::save_projects(&app, &projects)?;
+    set_registered(&projects);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_project_scan_settings(app: AppHandle) -> Result<ProjectScanSettings, String> {
+    crate::store::get_project_scan_settings(&app)
+}
+
+#[tauri::command]
+pub async fn set_project_scan_settings(
+    app: AppHandle,
+    settings: ProjectScanSettings,
+) -> Result<(), String> {
+    crate::store::save_project_scan_settings(&app, &settings)
+}
+
+// Scan the configured roots and register every directory that has project servers
+#[tauri::command]
+pub async fn scan_projects(app: AppHandle) -> Result<Vec<ProjectInfo>, String> {
+    let settings = crate::store::get_project_scan_settings(&app)?;
+    let mut found = Vec::new();
+    for root in &settings.roots {
+        scan_dir(Path::new(root), 0, &settings, &mut found);
+    }
+
+    Ok(register(&app, found)?.iter().map(describe).collect())
+}
+
+// Start a project server with the project directory as its working directory
+#[tauri::command]
+pub async fn start_project_server(
+    app: AppHandle,
+    project: String,
+    client: String,
+    name: String,
+) -> Result<String, String> {
+    // Only directories in the registry, added by hand or by a scan, are run from
+    let dir = PathBuf::from(&project);
+    if !crate::store::get_projects(&app)?
+        .iter()
+        .any(|p| Path::new(&p.path) == dir)
+    {
+        return Err(format!("{} is not a registered project", project));
+    }
+    let current = clients::find_client(&client)?.read_servers(Some(&dir))?;
+    let entry = current
+        .servers
+        .get(&name)
+        .ok_or_else(|| format!("Server {} not found", name))?;
+
+    let run_id = project_server_id(&project, &name);
+    crate::mcp_runner::start_entry(&app, &run_id, entry, Some(&dir))?;
+    Ok(run_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignore_rules_match_globs() {
+        assert!(matches_rule("node_modules", "node_modules"));
+        assert!(!matches_rule("node_modules", "node_modules2"));
+        assert!(matches_rule(".*", ".venv"));
+        assert!(!matches_rule(".*", "venv"));
+        assert!(matches_rule("*-cache", "build-cache"));
+        assert!(!matches_rule("*-cache", "build-cached"));
+        assert!(matches_rule("cmake-*-debug", "cmake-build-debug"));
+        assert!(matches_rule("a*b*c", "a-b-c"));
+        assert!(!matches_rule("a*b*c", "a-c-b"));
+        // The start and end of a rule cannot share characters of the name
+        assert!(!matches_rule("ab*ba", "aba"));
+        assert!(matches_rule("*", "anything"));
+    }
+
+    #[test]
+    fn scanning_skips_ignored_and_deep_directories() {
+        let root = std::env::temp_dir().join(format!("mcp-projects-scan-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let project = |dir: &str| {
+            let dir = root.join(dir);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join(".mcp.json"),
+                r#"{ "mcpServers": { "git": { "command": "uvx" } } }"#,
+            )
+            .unwrap();
+        };
+        project("app");
+        project("app/packages/web");
+        project("a/b/c/too-deep");
+        project("node_modules/pkg");
+        project("build-cache");
+        fs::create_dir_all(root.join("empty")).unwrap();
+
+        let settings = ProjectScanSettings {
+            roots: Vec::new(),
+            max_depth: 3,
+            ignore: vec!["node_modules".to_string(), "*-cache".to_string()],
+        };
+        let mut found = Vec::new();
+        scan_dir(&root, 0, &settings, &mut found);
+        found.sort();
+        assert_eq!(found, [root.join("app"), root.join("app/packages/web")]);
+
+        let shallow = ProjectScanSettings {
+            max_depth: 1,
+            ..settings
+        };
+        let mut found = Vec::new();
+        scan_dir(&root, 0, &shallow, &mut found);
+        assert_eq!(found, [root.join("app")]);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,625 @@
+use crate::mcp_servers::{ApiData, ApiResponse, McpServerTemplate, PaginationParams};
+use crate::server_json;
+use crate::store::TemplateRegistry;
+use crate::template_cache::{self, CachedResponse};
+use template_filter::TemplateFilter;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::time::Duration;
+use tauri::AppHandle;
+
+// Merged listings take at most this many templates from each registry. Every page is cut
+// from the same window, so paging reuses one cached response per registry.
+const MERGE_WINDOW: u32 = 500;
+
+// Registries in the official format page with a cursor; a listing walks at most this many
+const CURSOR_PAGE_LIMIT: usize = 20;
+// The largest page the official registry serves
+const CURSOR_LIMIT_MAX: u32 = 100;
+
+// Sent to the UI in place of stored auth values; saving it back keeps the stored value
+const MASKED_VALUE: &str = "********";
+
+// A registry that could not be queried; the others' results are still returned
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RegistryError {
+    pub registry: String,
+    pub error: String,
+}
+
+// The query sent to each registry
+#[derive(Debug, Clone)]
+pub struct TemplateQuery {
+    pub page: u32,
+    pub page_size: u32,
+    pub keyword: Option<String>,
+    pub require_file_path: Option<bool>,
+}
+
+impl TemplateQuery {
+    // Parameters of both this app's format and the official registry's cursor API; each
+    // registry ignores the ones it does not know
+    fn pairs(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![
+            ("page", self.page.to_string()),
+            ("page_size", self.page_size.to_string()),
+            ("limit", self.page_size.min(CURSOR_LIMIT_MAX).to_string()),
+        ];
+        if let Some(keyword) = &self.keyword {
+            query.push(("keyword", keyword.clone()));
+            query.push(("search", keyword.clone()));
+        }
+        if let Some(require_file_path) = self.require_file_path {
+            query.push(("require_file_path", require_file_path.to_string()));
+        }
+        query
+    }
+
+    pub fn cache_key(&self) -> String {
+        self.pairs()
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    pub fn filter(&self) -> TemplateFilter {
+        TemplateFilter::new(self.keyword.as_deref(), self.require_file_path)
+    }
+}
+
+// Templates from one registry; `stale_since` is set when they came from the cache
+struct Fetched {
+    data: ApiData,
+    stale_since: Option<u64>,
+}
+
+// A response body with its validators; `None` when the registry answered 304
+async fn get(
+    client: &reqwest::Client,
+    registry: &TemplateRegistry,
+    pairs: &[(&'static str, String)],
+    cached: Option<&CachedResponse>,
+) -> Result<Option<(JsonValue, Option<String>, Option<String>)>, String> {
+    let mut request = client
+        .get(&registry.url)
+        .query(pairs)
+        .timeout(Duration::from_millis(registry.timeout_ms));
+    if let Some(auth) = &registry.auth {
+        request = request.header(auth.name.as_str(), auth.value.as_str());
+    }
+    // 缓存的响应带上校验信息，未修改时 registry 只返回 304
+    if let Some(cached) = cached {
+        if let Some(etag) = &cached.etag {
+            request = request.header(IF_NONE_MATCH, etag.as_str());
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified.as_str());
+        }
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch templates: {}", e))?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(format!("Registry returned {}", response.status()));
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+    let body: JsonValue = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    Ok(Some((body, etag, last_modified)))
+}
+
+async fn fetch_remote(
+    client: &reqwest::Client,
+    registry: &TemplateRegistry,
+    query: &TemplateQuery,
+    cached: Option<CachedResponse>,
+) -> Result<CachedResponse, String> {
+    let pairs = query.pairs();
+    let Some((body, etag, last_modified)) = get(client, registry, &pairs, cached.as_ref()).await?
+    else {
+        return cached.ok_or_else(|| "Registry returned 304 for an uncached query".to_string());
+    };
+
+    let (total, list) = if is_document_list(&body) {
+        // Walk the cursor until the requested page is filled, plus one template to tell
+        // whether there is a next page
+        let filter = query.filter();
+        let wanted = (query.page.max(1) * query.page_size) as usize + 1;
+        let mut cursor = next_cursor(&body);
+        let mut templates = document_templates(body);
+        let mut pages = 1;
+        while let Some(next) = cursor.take() {
+            let matching = templates.iter().filter(|t| filter.matches(t)).count();
+            if matching >= wanted || pages >= CURSOR_PAGE_LIMIT {
+                cursor = Some(next);
+                break;
+            }
+            let mut pairs = pairs.clone();
+            pairs.push(("cursor", next));
+            let Some((body, _, _)) = get(client, registry, &pairs, None).await? else {
+                break;
+            };
+            cursor = next_cursor(&body);
+            templates.extend(document_templates(body));
+            pages += 1;
+        }
+        page_documents(templates, query, cursor.is_some())
+    } else {
+        parse_listing(body)?
+    };
+    Ok(CachedResponse {
+        etag,
+        last_modified,
+        validated_at: 0,
+        total,
+        list,
+    })
+}
+
+// Registries answer in this app's format, or list documents in the official server.json
+// format, which are mapped to templates runnable here and filtered locally
+fn is_document_list(body: &JsonValue) -> bool {
+    body.get("servers").is_some()
+}
+
+fn parse_listing(body: JsonValue) -> Result<(i32, Vec<McpServerTemplate>), String> {
+    let api_response: ApiResponse =
+        serde_json::from_value(body).map_err(|e| format!("Failed to parse response: {}", e))?;
+    if api_response.code != 0 {
+        return Err(api_response.msg);
+    }
+    Ok((api_response.data.total, api_response.data.list))
+}
+
+fn next_cursor(body: &JsonValue) -> Option<String> {
+    body.pointer("/metadata/nextCursor")
+        .and_then(|cursor| cursor.as_str())
+        .filter(|cursor| !cursor.is_empty())
+        .map(str::to_string)
+}
+
+// Templates of the listed documents; one that cannot be read or run here is skipped
+fn document_templates(body: JsonValue) -> Vec<McpServerTemplate> {
+    let items = match body {
+        JsonValue::Object(mut map) => match map.remove("servers") {
+            Some(JsonValue::Array(items)) => items,
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
+    let mut templates = Vec::new();
+    for item in items {
+        let documents = match server_json::parse_documents(item) {
+            Ok(documents) => documents,
+            Err(e) => {
+                println!("Skipping registry entry: {}", e);
+                continue;
+            }
+        };
+        for document in documents {
+            match document.to_template() {
+                Ok(template) => templates.push(template),
+                Err(e) => println!("Skipping {}: {}", document.name, e),
+            }
+        }
+    }
+    templates
+}
+
+// The requested page of the documents fetched so far. While the cursor has more, the
+// total reaches past this page so the next one can be asked for.
+fn page_documents(
+    templates: Vec<McpServerTemplate>,
+    query: &TemplateQuery,
+    more: bool,
+) -> (i32, Vec<McpServerTemplate>) {
+    let (total, list) =
+        query
+            .filter()
+            .select(templates, query.page as usize, query.page_size as usize);
+    let page_end = (query.page.max(1) * query.page_size) as usize;
+    let total = if more { total.max(page_end + 1) } else { total };
+    (total as i32, list)
+}
+
+// Query a registry, revalidating the cached response; when the registry cannot be
+// reached, answer from the cache instead
+async fn fetch(
+    client: reqwest::Client,
+    registry: TemplateRegistry,
+    query: TemplateQuery,
+) -> Result<Fetched, String> {
+    let cached = template_cache::lookup(&registry.url, &query);
+    match fetch_remote(&client, &registry, &query, cached).await {
+        Ok(response) => {
+            template_cache::store(&registry.url, &query, response.clone());
+            Ok(Fetched {
+                data: ApiData {
+                    total: response.total,
+                    list: response.list,
+                    errors: Vec::new(),
+                    stale_since: None,
+                },
+                stale_since: None,
+            })
+        }
+        Err(e) => match template_cache::offline(&registry.url, &query) {
+            Some((data, validated_at)) => {
+                println!("Registry {} failed, using cache: {}", registry.id, e);
+                Ok(Fetched {
+                    data,
+                    stale_since: Some(validated_at),
+                })
+            }
+            None => Err(e),
+        },
+    }
+}
+
+// Templates are the same when they point at the same repository, else share a name
+fn identity(template: &McpServerTemplate) -> String {
+    let repo = template
+        .repo_url
+        .trim()
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_lowercase();
+    if repo.is_empty() {
+        format!("name:{}", template.name.to_lowercase())
+    } else {
+        format!("repo:{}", repo)
+    }
+}
+
+// Merge registry results in priority order; a template already listed by a registry of
+// higher priority is dropped
+fn merge(results: Vec<(String, Result<Fetched, String>)>) -> ApiData {
+    let mut merged = ApiData {
+        total: 0,
+        list: Vec::new(),
+        errors: Vec::new(),
+        stale_since: None,
+    };
+    let mut seen = HashSet::new();
+    for (registry, result) in results {
+        match result {
+            Ok(Fetched { data, stale_since }) => {
+                // 取最早的缓存时间作为整体的过期标记
+                if let Some(since) = stale_since {
+                    merged.stale_since = Some(merged.stale_since.map_or(since, |s| s.min(since)));
+                }
+                for mut template in data.list {
+                    if seen.insert(identity(&template)) {
+                        template.source = Some(registry.clone());
+                        merged.list.push(template);
+                    }
+                }
+            }
+            Err(error) => {
+                println!("Registry {} failed: {}", registry, error);
+                merged.errors.push(RegistryError { registry, error });
+            }
+        }
+    }
+    merged.total = merged.list.len() as i32;
+    merged
+}
+
+// Query the enabled registries concurrently and merge their results by priority
+pub async fn query_registries(
+    registries: Vec<TemplateRegistry>,
+    params: PaginationParams,
+) -> Result<ApiData, String> {
+    let mut enabled: Vec<TemplateRegistry> = registries.into_iter().filter(|r| r.enabled).collect();
+    if enabled.is_empty() {
+        return Err("No template registry is enabled".to_string());
+    }
+    enabled.sort_by_key(|r| Reverse(r.priority));
+
+    // A single registry pages itself. Several are each asked for the same window and the
+    // page is cut from the merged list; templates past the window are not listed or counted.
+    let page = params.page.max(1);
+    let query = TemplateQuery {
+        page,
+        page_size: params.page_size,
+        keyword: params.keyword.clone(),
+        require_file_path: params.require_file_path,
+    };
+    let query = if enabled.len() == 1 {
+        query
+    } else {
+        TemplateQuery {
+            page: 1,
+            page_size: MERGE_WINDOW,
+            ..query
+        }
+    };
+
+    let client = reqwest::Client::new();
+    let handles: Vec<_> = enabled
+        .iter()
+        .map(|registry| {
+            tauri::async_runtime::spawn(fetch(client.clone(), registry.clone(), query.clone()))
+        })
+        .collect();
+    let mut results = Vec::new();
+    for (registry, handle) in enabled.iter().zip(handles) {
+        let result = match handle.await {
+            Ok(result) => result,
+            Err(e) => Err(e.to_string()),
+        };
+        results.push((registry.id.clone(), result));
+    }
+
+    if enabled.len() == 1 {
+        let (registry, result) = results.remove(0);
+        let Fetched {
+            mut data,
+            stale_since,
+        } = result.map_err(|e| format!("{}: {}", registry, e))?;
+        for template in &mut data.list {
+            template.source = Some(registry.clone());
+        }
+        data.stale_since = stale_since;
+        return Ok(data);
+    }
+
+    let mut merged = merge(results);
+    if merged.errors.len() == enabled.len() {
+        return Err(merged
+            .errors
+            .iter()
+            .map(|e| format!("{}: {}", e.registry, e.error))
+            .collect::<Vec<_>>()
+            .join("; "));
+    }
+    let start = ((page - 1) * params.page_size) as usize;
+    merged.list = merged
+        .list
+        .into_iter()
+        .skip(start)
+        .take(params.page_size as usize)
+        .collect();
+    Ok(merged)
+}
+
+#[tauri::command]
+pub async fn list_template_registries(app: AppHandle) -> Result<Vec<TemplateRegistry>, String> {
+    let mut registries = crate::store::get_template_registries(&app)?;
+    for auth in registries.iter_mut().filter_map(|r| r.auth.as_mut()) {
+        if !auth.value.is_empty() {
+            auth.value = MASKED_VALUE.to_string();
+        }
+    }
+    Ok(registries)
+}
+
+// Put the stored auth values back in place of masked ones
+fn unmask(registries: &mut [TemplateRegistry], stored: &[TemplateRegistry]) -> Result<(), String> {
+    for registry in registries {
+        let Some(auth) = registry.auth.as_mut().filter(|a| a.value == MASKED_VALUE) else {
+            continue;
+        };
+        auth.value = stored
+            .iter()
+            .find(|s| s.id == registry.id)
+            .and_then(|s| s.auth.as_ref())
+            .map(|a| a.value.clone())
+            .ok_or_else(|| format!("Registry '{}' has no stored credential", registry.id))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn save_template_registries(
+    app: AppHandle,
+    mut registries: Vec<TemplateRegistry>,
+) -> Result<(), String> {
+    let mut ids = HashSet::new();
+    for registry in &registries {
+        if registry.id.trim().is_empty() {
+            return Err("Registry id is empty".to_string());
+        }
+        if !ids.insert(registry.id.as_str()) {
+            return Err(format!("Duplicate registry id '{}'", registry.id));
+        }
+        if !registry.url.starts_with("http://") && !registry.url.starts_with("https://") {
+            return Err(format!(
+                "Registry '{}' needs an http or https URL",
+                registry.id
+            ));
+        }
+        if registry.timeout_ms == 0 {
+            return Err(format!("Registry '{}' has no timeout", registry.id));
+        }
+    }
+    unmask(
+        &mut registries,
+        &crate::store::get_template_registries(&app)?,
+    )?;
+    crate::transaction::run(&app, "Save template registries", None, async {
+        crate::store::save_template_registries(&app, &registries)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::RegistryAuth;
+    use serde_json::json;
+
+    fn template(name: &str, repo_url: &str) -> McpServerTemplate {
+        serde_json::from_value(json!({
+            "id": name,
+            "name": name,
+            "description": "",
+            "command": "npx",
+            "args": [],
+            "env": null,
+            "repo_url": repo_url,
+            "created_at": "",
+            "updated_at": "",
+            "downloads": 0,
+            "total_usage_time": 0,
+        }))
+        .unwrap()
+    }
+
+    fn fetched(list: Vec<McpServerTemplate>, stale_since: Option<u64>) -> Result<Fetched, String> {
+        Ok(Fetched {
+            data: ApiData {
+                total: list.len() as i32,
+                list,
+                errors: Vec::new(),
+                stale_since: None,
+            },
+            stale_since,
+        })
+    }
+
+    #[test]
+    fn identity_prefers_the_repository() {
+        assert_eq!(
+            identity(&template("a", "https://github.com/Org/Server.git/")),
+            identity(&template("b", "https://github.com/org/server"))
+        );
+        assert_eq!(identity(&template("Git", " ")), "name:git");
+        assert_ne!(
+            identity(&template("git", "")),
+            identity(&template("git", "https://github.com/org/git"))
+        );
+    }
+
+    #[test]
+    fn merge_keeps_the_higher_priority_duplicate() {
+        let merged = merge(vec![
+            (
+                "team".to_string(),
+                fetched(
+                    vec![template("git", "https://github.com/org/git")],
+                    Some(20),
+                ),
+            ),
+            ("broken".to_string(), Err("timed out".to_string())),
+            (
+                "public".to_string(),
+                fetched(
+                    vec![
+                        template("git-server", "https://github.com/org/git.git"),
+                        template("fetch", ""),
+                    ],
+                    Some(10),
+                ),
+            ),
+        ]);
+
+        let listed: Vec<(&str, Option<&str>)> = merged
+            .list
+            .iter()
+            .map(|t| (t.name.as_str(), t.source.as_deref()))
+            .collect();
+        assert_eq!(listed, [("git", Some("team")), ("fetch", Some("public"))]);
+        assert_eq!(merged.total, 2);
+        assert_eq!(merged.stale_since, Some(10));
+        assert_eq!(merged.errors.len(), 1);
+        assert_eq!(merged.errors[0].registry, "broken");
+    }
+
+    #[test]
+    fn bad_documents_are_skipped() {
+        let weather: JsonValue =
+            serde_json::from_str(include_str!("../tests/fixtures/server_json/weather.json"))
+                .unwrap();
+        let runnable = server_json::parse_documents(weather.clone()).unwrap()[0]
+            .to_template()
+            .is_ok();
+        let body = json!({
+            "servers": [
+                { "server": { "name": 5 } },
+                { "server": weather, "_meta": {} },
+                "not a document",
+            ],
+            "metadata": { "nextCursor": "abc", "count": 3 },
+        });
+
+        assert_eq!(next_cursor(&body).as_deref(), Some("abc"));
+        let templates = document_templates(body);
+        assert_eq!(templates.len(), runnable as usize);
+        assert!(templates
+            .iter()
+            .all(|t| t.description == "Weather forecasts and alerts"));
+        assert_eq!(
+            next_cursor(&json!({ "metadata": { "nextCursor": "" } })),
+            None
+        );
+    }
+
+    #[test]
+    fn cursor_listings_reach_past_the_fetched_page() {
+        let query = |page| TemplateQuery {
+            page,
+            page_size: 2,
+            keyword: Some("git".to_string()),
+            require_file_path: None,
+        };
+        let fetched = || {
+            vec![
+                template("git", ""),
+                template("fetch", ""),
+                template("gitlab", ""),
+                template("github", ""),
+            ]
+        };
+
+        let (total, list) = page_documents(fetched(), &query(1), false);
+        assert_eq!(total, 3);
+        assert_eq!(list.len(), 2);
+        let (total, list) = page_documents(fetched(), &query(2), true);
+        assert_eq!(total, 5);
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn masked_auth_keeps_the_stored_value() {
+        let registry = |id: &str, value: &str| TemplateRegistry {
+            auth: Some(RegistryAuth {
+                name: "Authorization".to_string(),
+                value: value.to_string(),
+            }),
+            id: id.to_string(),
+            ..TemplateRegistry::local()
+        };
+        let stored = [registry("team", "Bearer secret")];
+
+        let mut saved = vec![registry("team", MASKED_VALUE)];
+        unmask(&mut saved, &stored).unwrap();
+        assert_eq!(saved[0].auth.as_ref().unwrap().value, "Bearer secret");
+
+        let mut changed = vec![registry("team", "Bearer new")];
+        unmask(&mut changed, &stored).unwrap();
+        assert_eq!(changed[0].auth.as_ref().unwrap().value, "Bearer new");
+
+        assert!(unmask(&mut [registry("other", MASKED_VALUE)], &stored).is_err());
+    }
+}
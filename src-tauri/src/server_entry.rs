@@ -0,0 +1,405 @@
+use crate::claude_config::ServerConfig;
+use crate::config_diff::escape_pointer;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+// Keys clients understand besides the ones modelled below
+const PASSTHROUGH_KEYS: &[&str] = &[
+    "type",
+    "disabled",
+    "autoApprove",
+    "alwaysAllow",
+    "timeout",
+    "envFile",
+    "description",
+    "source",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Diagnostic {
+    // JSON pointer into the config document
+    pub path: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RemoteTransport {
+    StreamableHttp,
+    Sse,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ServerEntry {
+    Stdio {
+        command: String,
+        args: Vec<String>,
+        env: IndexMap<String, String>,
+        cwd: Option<String>,
+    },
+    Remote {
+        url: String,
+        headers: IndexMap<String, String>,
+        transport: RemoteTransport,
+    },
+}
+
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.severity == Severity::Error)
+}
+
+pub fn format_diagnostics(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|d| format!("{}: {}", d.path, d.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+// Pointer to a server entry below the servers object at `servers_pointer`
+pub fn entry_path(servers_pointer: &str, name: &str) -> String {
+    format!("{}/{}", servers_pointer, escape_pointer(name))
+}
+
+struct Validator<'a> {
+    base: &'a str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator<'_> {
+    fn push(&mut self, key: &str, severity: Severity, message: String) {
+        let path = if key.is_empty() {
+            self.base.to_string()
+        } else {
+            format!("{}/{}", self.base, key)
+        };
+        self.diagnostics.push(Diagnostic {
+            path,
+            severity,
+            message,
+        });
+    }
+
+    fn string(&mut self, entry: &ServerConfig, key: &str, required: bool) -> Option<String> {
+        match entry.get(key) {
+            Some(JsonValue::String(value)) => Some(value.clone()),
+            Some(other) => {
+                self.push(
+                    key,
+                    Severity::Error,
+                    format!("`{}` must be a string, found {}", key, type_name(other)),
+                );
+                None
+            }
+            None if required => {
+                self.push("", Severity::Error, format!("Missing `{}`", key));
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn string_array(&mut self, entry: &ServerConfig, key: &str) -> Vec<String> {
+        match entry.get(key) {
+            None => Vec::new(),
+            Some(JsonValue::Array(items)) => items
+                .iter()
+                .enumerate()
+                .filter_map(|(i, item)| match item {
+                    JsonValue::String(value) => Some(value.clone()),
+                    other => {
+                        self.push(
+                            &format!("{}/{}", key, i),
+                            Severity::Error,
+                            format!("Argument must be a string, found {}", type_name(other)),
+                        );
+                        None
+                    }
+                })
+                .collect(),
+            Some(other) => {
+                self.push(
+                    key,
+                    Severity::Error,
+                    format!("`{}` must be an array, found {}", key, type_name(other)),
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    fn string_map(&mut self, entry: &ServerConfig, key: &str) -> IndexMap<String, String> {
+        match entry.get(key) {
+            None | Some(JsonValue::Null) => IndexMap::new(),
+            Some(JsonValue::Object(values)) => values
+                .iter()
+                .filter_map(|(name, value)| match value {
+                    JsonValue::String(value) => Some((name.clone(), value.clone())),
+                    other => {
+                        self.push(
+                            &format!("{}/{}", key, escape_pointer(name)),
+                            Severity::Error,
+                            format!("`{}` must be a string, found {}", name, type_name(other)),
+                        );
+                        None
+                    }
+                })
+                .collect(),
+            Some(other) => {
+                self.push(
+                    key,
+                    Severity::Error,
+                    format!("`{}` must be an object, found {}", key, type_name(other)),
+                );
+                IndexMap::new()
+            }
+        }
+    }
+}
+
+fn type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "a boolean",
+        JsonValue::Number(_) => "a number",
+        JsonValue::String(_) => "a string",
+        JsonValue::Array(_) => "an array",
+        JsonValue::Object(_) => "an object",
+    }
+}
+
+// Check an entry and build the typed model from whatever is valid
+fn check(base: &str, entry: &ServerConfig) -> (Option<ServerEntry>, Vec<Diagnostic>) {
+    let mut v = Validator {
+        base,
+        diagnostics: Vec::new(),
+    };
+
+    let declared_type = entry.get("type").and_then(|t| t.as_str());
+    let is_remote = match declared_type {
+        Some("stdio") => false,
+        Some("http") | Some("streamable-http") | Some("sse") => true,
+        Some(other) => {
+            v.push(
+                "type",
+                Severity::Error,
+                format!("Unknown server type `{}`", other),
+            );
+            entry.contains_key("url")
+        }
+        None => !entry.contains_key("command") && entry.contains_key("url"),
+    };
+
+    let model_keys: &[&str] = if is_remote {
+        &["url", "headers", "transport"]
+    } else {
+        &["command", "args", "env", "cwd"]
+    };
+    for key in entry.keys() {
+        if !model_keys.contains(&key.as_str()) && !PASSTHROUGH_KEYS.contains(&key.as_str()) {
+            v.push(
+                &escape_pointer(key),
+                Severity::Warning,
+                format!("Unknown key `{}`", key),
+            );
+        }
+    }
+
+    let model = if is_remote {
+        let url = v.string(entry, "url", true);
+        if let Some(url) = &url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                v.push(
+                    "url",
+                    Severity::Error,
+                    "`url` must start with http:// or https://".to_string(),
+                );
+            }
+        }
+        let headers = v.string_map(entry, "headers");
+        let transport = match (
+            declared_type,
+            entry.get("transport").and_then(|t| t.as_str()),
+        ) {
+            (Some("sse"), _) | (_, Some("sse")) => RemoteTransport::Sse,
+            _ => RemoteTransport::StreamableHttp,
+        };
+        url.map(|url| ServerEntry::Remote {
+            url,
+            headers,
+            transport,
+        })
+    } else {
+        if !entry.contains_key("command") && !entry.contains_key("url") {
+            v.push(
+                "",
+                Severity::Error,
+                "Missing `command` (or `url` for remote servers)".to_string(),
+            );
+        }
+        let command = v.string(entry, "command", false);
+        if command.as_deref().is_some_and(|c| c.trim().is_empty()) {
+            v.push("command", Severity::Error, "`command` is empty".to_string());
+        }
+        let args = v.string_array(entry, "args");
+        let env = v.string_map(entry, "env");
+        let cwd = v.string(entry, "cwd", false);
+        command.map(|command| ServerEntry::Stdio {
+            command,
+            args,
+            env,
+            cwd,
+        })
+    };
+
+    let model = if has_errors(&v.diagnostics) {
+        None
+    } else {
+        model
+    };
+    (model, v.diagnostics)
+}
+
+pub fn validate_entry(base: &str, entry: &ServerConfig) -> Vec<Diagnostic> {
+    check(base, entry).1
+}
+
+impl ServerEntry {
+    pub fn parse(entry: &ServerConfig) -> Result<Self, Vec<Diagnostic>> {
+        match check("", entry) {
+            (Some(model), _) => Ok(model),
+            (None, diagnostics) => Err(diagnostics),
+        }
+    }
+
+    pub fn to_config(&self) -> ServerConfig {
+        let value = match self {
+            ServerEntry::Stdio {
+                command,
+                args,
+                env,
+                cwd,
+            } => {
+                let mut value = json!({ "command": command, "args": args });
+                if !env.is_empty() {
+                    value["env"] = json!(env);
+                }
+                if let Some(cwd) = cwd {
+                    value["cwd"] = json!(cwd);
+                }
+                value
+            }
+            ServerEntry::Remote {
+                url,
+                headers,
+                transport,
+            } => {
+                // Without a type some clients take a bare `url` for SSE
+                let kind = match transport {
+                    RemoteTransport::StreamableHttp => "http",
+                    RemoteTransport::Sse => "sse",
+                };
+                let mut value = json!({ "type": kind, "url": url });
+                if !headers.is_empty() {
+                    value["headers"] = json!(headers);
+                }
+                value
+            }
+        };
+        match value {
+            JsonValue::Object(map) => map,
+            _ => ServerConfig::new(),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn validate_mcp_server_config(name: String, config: ServerConfig) -> Vec<Diagnostic> {
+    validate_entry(&entry_path("/mcpServers", &name), &config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(value: JsonValue) -> ServerConfig {
+        value.as_object().unwrap().clone()
+    }
+
+    fn found(diagnostics: &[Diagnostic]) -> Vec<(&str, Severity)> {
+        diagnostics
+            .iter()
+            .map(|d| (d.path.as_str(), d.severity))
+            .collect()
+    }
+
+    #[test]
+    fn diagnostics_point_into_the_entry() {
+        let base = entry_path("/mcpServers", "a/b");
+        assert_eq!(base, "/mcpServers/a~1b");
+
+        let diagnostics = validate_entry(
+            &base,
+            &entry(json!({
+                "command": "npx",
+                "args": ["-y", 3],
+                "env": { "TOKEN": true },
+                "autoApprove": [],
+                "colour": "red"
+            })),
+        );
+        assert_eq!(
+            found(&diagnostics),
+            [
+                ("/mcpServers/a~1b/colour", Severity::Warning),
+                ("/mcpServers/a~1b/args/1", Severity::Error),
+                ("/mcpServers/a~1b/env/TOKEN", Severity::Error),
+            ]
+        );
+
+        let diagnostics = validate_entry(&base, &entry(json!({ "args": [] })));
+        assert_eq!(found(&diagnostics), [("/mcpServers/a~1b", Severity::Error)]);
+
+        let diagnostics = validate_entry(
+            &base,
+            &entry(json!({ "type": "sse", "url": "ftp://example.com" })),
+        );
+        assert_eq!(
+            found(&diagnostics),
+            [("/mcpServers/a~1b/url", Severity::Error)]
+        );
+
+        // Warnings alone still produce a model
+        let parsed = ServerEntry::parse(&entry(json!({ "command": "uvx", "colour": "red" })));
+        assert!(parsed.is_ok());
+    }
+
+    #[test]
+    fn remote_entries_declare_their_transport() {
+        let http = ServerEntry::parse(&entry(json!({ "url": "https://example.com/mcp" }))).unwrap();
+        assert_eq!(
+            JsonValue::Object(http.to_config()),
+            json!({ "type": "http", "url": "https://example.com/mcp" })
+        );
+
+        let sse = ServerEntry::parse(&entry(json!({
+            "type": "sse",
+            "url": "https://example.com/sse",
+            "headers": { "Authorization": "Bearer x" }
+        })))
+        .unwrap();
+        let config = sse.to_config();
+        assert_eq!(config["type"], "sse");
+        assert_eq!(ServerEntry::parse(&config).unwrap(), sse);
+    }
+}
@@ -0,0 +1,772 @@
+use crate::mcp_servers::McpServerTemplate;
+use crate::plan::{fetched_package, PreparedOperation};
+use crate::server_entry::{RemoteTransport, ServerEntry};
+use crate::store::InstalledMcpServer;
+use crate::template_params::{ParameterTarget, ParameterType, TemplateParameter};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+// Field names follow the published schema; older snake_case documents are read too
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerJson {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub repository: Option<Repository>,
+    #[serde(default)]
+    pub packages: Vec<Package>,
+    #[serde(default)]
+    pub remotes: Vec<Remote>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Repository {
+    #[serde(default)]
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Package {
+    #[serde(alias = "registry_type", alias = "registry_name")]
+    pub registry_type: String,
+    #[serde(alias = "name")]
+    pub identifier: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default, alias = "runtime_hint")]
+    pub runtime_hint: Option<String>,
+    #[serde(default)]
+    pub transport: Option<Transport>,
+    #[serde(default, alias = "runtime_arguments")]
+    pub runtime_arguments: Vec<Argument>,
+    #[serde(default, alias = "package_arguments")]
+    pub package_arguments: Vec<Argument>,
+    #[serde(default, alias = "environment_variables")]
+    pub environment_variables: Vec<KeyValueInput>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Transport {
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Remote {
+    #[serde(rename = "type", alias = "transport_type")]
+    pub kind: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: Vec<KeyValueInput>,
+}
+
+// What every user-supplied value shares: a fixed value, or a description to ask with
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Input {
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, alias = "is_required")]
+    pub is_required: bool,
+    #[serde(default, alias = "is_secret")]
+    pub is_secret: bool,
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub choices: Vec<String>,
+    // Inputs referenced as `{name}` inside `value`
+    #[serde(default)]
+    pub variables: IndexMap<String, Input>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Argument {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default, alias = "value_hint")]
+    pub value_hint: Option<String>,
+    #[serde(flatten)]
+    pub input: Input,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct KeyValueInput {
+    pub name: String,
+    #[serde(flatten)]
+    pub input: Input,
+}
+
+// A value the user is asked for before installing
+#[derive(Debug, Serialize, Clone)]
+pub struct InputSpec {
+    pub key: String,
+    pub description: String,
+    pub required: bool,
+    pub secret: bool,
+    pub format: Option<String>,
+    pub default: Option<String>,
+    pub choices: Vec<String>,
+}
+
+// One way to run the server: a package through its runtime, or a hosted endpoint
+#[derive(Debug, Serialize, Clone)]
+pub struct Variant {
+    pub id: String,
+    pub registry_type: String,
+    pub identifier: String,
+    pub runtime: Option<String>,
+    pub available: bool,
+    // Why the variant cannot be used on this machine
+    pub reason: Option<String>,
+    pub inputs: Vec<InputSpec>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ServerJsonCheck {
+    pub name: String,
+    pub install_name: String,
+    pub description: String,
+    pub variants: Vec<Variant>,
+    // The variant installed when none is chosen
+    pub selected: Option<String>,
+}
+
+// A single document, an array of them, or a registry listing `{"servers": [...]}` whose
+// items may be wrapped in `{"server": ...}`
+pub fn parse_documents(value: JsonValue) -> Result<Vec<ServerJson>, String> {
+    let items = match value {
+        JsonValue::Array(items) => items,
+        JsonValue::Object(mut map) => match map.remove("servers") {
+            Some(JsonValue::Array(items)) => items,
+            Some(_) => return Err("`servers` must be an array".to_string()),
+            None => vec![JsonValue::Object(map)],
+        },
+        _ => return Err("Expected a server.json object".to_string()),
+    };
+    items
+        .into_iter()
+        .map(|mut item| {
+            if let Some(server) = item.get_mut("server") {
+                item = server.take();
+            }
+            serde_json::from_value::<ServerJson>(item)
+                .map_err(|e| format!("Invalid server.json: {}", e))
+        })
+        .collect()
+}
+
+fn read_documents(path: &Path) -> Result<Vec<ServerJson>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let value: JsonValue = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    parse_documents(value)
+}
+
+// Key the user's value for an input is looked up by
+fn argument_key(argument: &Argument, index: usize) -> String {
+    match (&argument.name, &argument.value_hint) {
+        (Some(name), _) if argument.kind == "named" => name.trim_start_matches('-').to_string(),
+        (_, Some(hint)) => hint.clone(),
+        _ => format!("arg{}", index),
+    }
+}
+
+fn input_spec(key: &str, input: &Input) -> InputSpec {
+    InputSpec {
+        key: key.to_string(),
+        description: input.description.clone(),
+        required: input.is_required,
+        secret: input.is_secret,
+        format: input.format.clone(),
+        default: input.default.clone(),
+        choices: input.choices.clone(),
+    }
+}
+
+// Inputs with a fixed value need nothing from the user except their variables
+fn collect_inputs(key: &str, input: &Input, out: &mut Vec<InputSpec>) {
+    if input.value.is_none() {
+        out.push(input_spec(key, input));
+    }
+    for (name, variable) in &input.variables {
+        collect_inputs(name, variable, out);
+    }
+}
+
+// The value for an input, or None when an optional input is left empty
+fn resolve(
+    key: &str,
+    input: &Input,
+    values: &HashMap<String, String>,
+) -> Result<Option<String>, String> {
+    let value = match &input.value {
+        Some(value) => {
+            let mut value = value.clone();
+            for (name, variable) in &input.variables {
+                let resolved = resolve(name, variable, values)?.unwrap_or_default();
+                value = value.replace(&format!("{{{}}}", name), &resolved);
+            }
+            Some(value)
+        }
+        None => values
+            .get(key)
+            .filter(|v| !v.is_empty())
+            .or(input.default.as_ref())
+            .cloned(),
+    };
+    match value {
+        None if input.is_required => Err(format!("Missing value for `{}`", key)),
+        // Template placeholders are checked when the template is installed
+        Some(value) if value == placeholder(key) => Ok(Some(value)),
+        Some(value) if !input.choices.is_empty() && !input.choices.contains(&value) => Err(
+            format!("`{}` must be one of {}", key, input.choices.join(", ")),
+        ),
+        value => Ok(value),
+    }
+}
+
+// `start` is the position of the first argument among all of the package's arguments
+fn render_arguments(
+    arguments: &[Argument],
+    start: usize,
+    values: &HashMap<String, String>,
+    out: &mut Vec<String>,
+) -> Result<(), String> {
+    for (index, argument) in arguments.iter().enumerate() {
+        let key = argument_key(argument, start + index);
+        let value = resolve(&key, &argument.input, values)?;
+        match (argument.kind.as_str(), &argument.name) {
+            ("named", Some(name)) => {
+                if let Some(value) = value {
+                    out.push(name.clone());
+                    if !value.is_empty() {
+                        out.push(value);
+                    }
+                }
+            }
+            _ => out.extend(value),
+        }
+    }
+    Ok(())
+}
+
+impl Package {
+    fn runtime(&self) -> Option<String> {
+        if let Some(hint) = &self.runtime_hint {
+            return Some(hint.clone());
+        }
+        let runtime = match self.registry_type.as_str() {
+            "npm" => "npx",
+            "pypi" => "uvx",
+            "oci" | "docker" => "docker",
+            "nuget" => "dnx",
+            _ => return None,
+        };
+        Some(runtime.to_string())
+    }
+
+    // Why this package cannot be run here, if it cannot
+    fn unavailable(&self) -> Option<String> {
+        if let Some(transport) = &self.transport {
+            if transport.kind != "stdio" {
+                return Some(format!("Runs a local {} server", transport.kind));
+            }
+        }
+        match self.runtime() {
+            None => Some(format!(
+                "Packages from {} cannot be installed",
+                self.registry_type
+            )),
+            Some(runtime) if crate::env_check::resolve_command(&runtime).is_none() => {
+                Some(format!("`{}` was not found on this machine", runtime))
+            }
+            Some(_) => None,
+        }
+    }
+
+    fn inputs(&self) -> Vec<InputSpec> {
+        let mut inputs = Vec::new();
+        for (index, argument) in self
+            .runtime_arguments
+            .iter()
+            .chain(&self.package_arguments)
+            .enumerate()
+        {
+            collect_inputs(&argument_key(argument, index), &argument.input, &mut inputs);
+        }
+        for variable in &self.environment_variables {
+            collect_inputs(&variable.name, &variable.input, &mut inputs);
+        }
+        inputs
+    }
+
+    // The package reference in the form its runtime expects
+    fn spec(&self) -> String {
+        let Some(version) = self.version.as_ref().filter(|v| !v.is_empty()) else {
+            return self.identifier.clone();
+        };
+        match self.registry_type.as_str() {
+            "pypi" => format!("{}=={}", self.identifier, version),
+            "oci" | "docker" => {
+                // Images that already carry a tag or digest are used as is
+                let last = self.identifier.rsplit('/').next().unwrap_or_default();
+                if last.contains(':') || last.contains('@') {
+                    self.identifier.clone()
+                } else {
+                    format!("{}:{}", self.identifier, version)
+                }
+            }
+            _ => format!("{}@{}", self.identifier, version),
+        }
+    }
+
+    fn entry(&self, values: &HashMap<String, String>) -> Result<ServerEntry, String> {
+        let runtime = self
+            .runtime()
+            .ok_or_else(|| format!("Packages from {} cannot be installed", self.registry_type))?;
+
+        let mut env = IndexMap::new();
+        for variable in &self.environment_variables {
+            if let Some(value) = resolve(&variable.name, &variable.input, values)? {
+                env.insert(variable.name.clone(), value);
+            }
+        }
+
+        let mut args = Vec::new();
+        match runtime.as_str() {
+            "npx" => args.push("-y".to_string()),
+            "pipx" => args.push("run".to_string()),
+            "docker" | "podman" => args.extend(["run", "-i", "--rm"].map(String::from)),
+            _ => {}
+        }
+        render_arguments(&self.runtime_arguments, 0, values, &mut args)?;
+        if matches!(runtime.as_str(), "docker" | "podman") {
+            // Containers only see the variables passed through explicitly
+            for name in env.keys() {
+                args.extend(["-e".to_string(), name.clone()]);
+            }
+        }
+        args.push(self.spec());
+        render_arguments(
+            &self.package_arguments,
+            self.runtime_arguments.len(),
+            values,
+            &mut args,
+        )?;
+        if runtime == "dnx" {
+            args.push("--yes".to_string());
+        }
+
+        Ok(ServerEntry::Stdio {
+            command: runtime,
+            args,
+            env,
+            cwd: None,
+        })
+    }
+}
+
+impl Remote {
+    fn transport(&self) -> Option<RemoteTransport> {
+        match self.kind.as_str() {
+            "streamable-http" | "streamable" | "http" => Some(RemoteTransport::StreamableHttp),
+            "sse" => Some(RemoteTransport::Sse),
+            _ => None,
+        }
+    }
+
+    fn entry(&self, values: &HashMap<String, String>) -> Result<ServerEntry, String> {
+        let transport = self
+            .transport()
+            .ok_or_else(|| format!("Unsupported remote transport `{}`", self.kind))?;
+        let mut headers = IndexMap::new();
+        for header in &self.headers {
+            if let Some(value) = resolve(&header.name, &header.input, values)? {
+                headers.insert(header.name.clone(), value);
+            }
+        }
+        Ok(ServerEntry::Remote {
+            url: self.url.clone(),
+            headers,
+            transport,
+        })
+    }
+}
+
+impl ServerJson {
+    // Config key the server is installed under: the last segment of its registry name
+    pub fn install_name(&self) -> String {
+        self.name
+            .rsplit('/')
+            .next()
+            .unwrap_or(&self.name)
+            .to_string()
+    }
+
+    pub fn variants(&self) -> Vec<Variant> {
+        let packages = self.packages.iter().enumerate().map(|(i, package)| {
+            let reason = package.unavailable();
+            Variant {
+                id: format!("package-{}", i),
+                registry_type: package.registry_type.clone(),
+                identifier: package.identifier.clone(),
+                runtime: package.runtime(),
+                available: reason.is_none(),
+                reason,
+                inputs: package.inputs(),
+            }
+        });
+        let remotes = self.remotes.iter().enumerate().map(|(i, remote)| {
+            let mut inputs = Vec::new();
+            for header in &remote.headers {
+                collect_inputs(&header.name, &header.input, &mut inputs);
+            }
+            let reason = remote
+                .transport()
+                .is_none()
+                .then(|| format!("Unsupported remote transport `{}`", remote.kind));
+            Variant {
+                id: format!("remote-{}", i),
+                registry_type: "remote".to_string(),
+                identifier: remote.url.clone(),
+                runtime: None,
+                available: reason.is_none(),
+                reason,
+                inputs,
+            }
+        });
+        packages.chain(remotes).collect()
+    }
+
+    // Packages in the publisher's order come first; a hosted endpoint needs no runtime and
+    // is the fallback
+    pub fn choose_variant(&self) -> Option<String> {
+        self.variants()
+            .into_iter()
+            .find(|v| v.available)
+            .map(|v| v.id)
+    }
+
+    pub fn entry(
+        &self,
+        variant: Option<&str>,
+        values: &HashMap<String, String>,
+    ) -> Result<ServerEntry, String> {
+        let id = match variant {
+            Some(id) => id.to_string(),
+            None => self.choose_variant().ok_or_else(|| {
+                let reasons: Vec<String> = self
+                    .variants()
+                    .into_iter()
+                    .filter_map(|v| v.reason.map(|r| format!("{}: {}", v.identifier, r)))
+                    .collect();
+                format!(
+                    "No way to run {} on this machine: {}",
+                    self.name,
+                    reasons.join("; ")
+                )
+            })?,
+        };
+        let (kind, index) = id
+            .rsplit_once('-')
+            .and_then(|(kind, index)| Some((kind, index.parse::<usize>().ok()?)))
+            .ok_or_else(|| format!("Unknown variant '{}'", id))?;
+        match kind {
+            "package" => self.packages.get(index).map(|p| p.entry(values)),
+            "remote" => self.remotes.get(index).map(|r| r.entry(values)),
+            _ => None,
+        }
+        .ok_or_else(|| format!("Unknown variant '{}'", id))?
+    }
+
+    pub fn check(&self) -> ServerJsonCheck {
+        ServerJsonCheck {
+            name: self.name.clone(),
+            install_name: self.install_name(),
+            description: self.description.clone(),
+            variants: self.variants(),
+            selected: self.choose_variant(),
+        }
+    }
+
+    // A template for registry listings. The variant's inputs become template parameters:
+    // environment variables map to env, the rest to `{{key}}` placeholders in args.
+    // Optional inputs without a default are left out.
+    pub fn to_template(&self) -> Result<McpServerTemplate, String> {
+        let variant = self
+            .variants()
+            .into_iter()
+            .find(|v| v.available && v.registry_type != "remote")
+            .ok_or_else(|| format!("No package of {} can run on this machine", self.name))?;
+        let inputs: Vec<&InputSpec> = variant
+            .inputs
+            .iter()
+            .filter(|i| i.required || i.default.is_some())
+            .collect();
+        let values: HashMap<String, String> = inputs
+            .iter()
+            .map(|i| (i.key.clone(), placeholder(&i.key)))
+            .collect();
+        let ServerEntry::Stdio {
+            command,
+            args,
+            mut env,
+            ..
+        } = self.entry(Some(&variant.id), &values)?
+        else {
+            return Err(format!("{} is not a local server", self.name));
+        };
+
+        let parameters = inputs
+            .iter()
+            .map(|input| {
+                let target = if env.get(&input.key) == Some(&placeholder(&input.key)) {
+                    env.shift_remove(&input.key);
+                    ParameterTarget::Env {
+                        name: input.key.clone(),
+                    }
+                } else {
+                    ParameterTarget::Placeholder
+                };
+                let kind = match input.format.as_deref() {
+                    _ if !input.choices.is_empty() => ParameterType::Enum,
+                    Some("number") => ParameterType::Number,
+                    Some("boolean") => ParameterType::Boolean,
+                    Some("filepath") => ParameterType::Path,
+                    _ => ParameterType::String,
+                };
+                TemplateParameter {
+                    name: input.key.clone(),
+                    kind,
+                    description: input.description.clone(),
+                    required: input.required,
+                    default: input.default.clone().map(JsonValue::String),
+                    secret: input.secret,
+                    multiple: false,
+                    choices: input.choices.clone(),
+                    target,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Ok(McpServerTemplate {
+            id: self.name.clone(),
+            name: self.install_name(),
+            description: self.description.clone(),
+            command,
+            args,
+            env: (!env.is_empty()).then(|| env.into_iter().collect()),
+            require_file_path: parameters.iter().any(|p| p.kind == ParameterType::Path),
+            parameters,
+            repo_url: self
+                .repository
+                .as_ref()
+                .map(|r| r.url.clone())
+                .unwrap_or_default(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            downloads: 0,
+            total_usage_time: 0,
+            source: None,
+        })
+    }
+}
+
+fn placeholder(key: &str) -> String {
+    format!("{{{{{}}}}}", key)
+}
+
+fn find_server(path: &str, server: Option<&str>) -> Result<ServerJson, String> {
+    let mut documents = read_documents(Path::new(path))?;
+    match server {
+        Some(name) => documents
+            .into_iter()
+            .find(|d| d.name == name || d.install_name() == name)
+            .ok_or_else(|| format!("'{}' is not in {}", name, path)),
+        None if documents.len() == 1 => Ok(documents.remove(0)),
+        None => Err(format!("{} describes several servers; pick one", path)),
+    }
+}
+
+fn prepare(
+    app: &AppHandle,
+    document: &ServerJson,
+    entry: ServerEntry,
+    clients: Option<Vec<String>>,
+    project: Option<&Path>,
+) -> Result<PreparedOperation, String> {
+    let name = document.install_name();
+    let targets = crate::clients::resolve_targets(clients)?;
+
+    let config = entry.to_config();
+    let mut prepared =
+        PreparedOperation::edit(format!("Install {}", name), &targets, project, |servers| {
+            servers.insert(name.clone(), config.clone());
+            Ok(())
+        });
+    // Like imported bundles, only local servers are tracked in the store
+    if let ServerEntry::Stdio {
+        command, args, env, ..
+    } = entry
+    {
+        if let Some(fetch) = fetched_package(&command, &args) {
+            prepared.side_effect(fetch);
+        }
+        prepared.save_server(
+            crate::store::get_installed_servers(app)?.get(&name),
+            InstalledMcpServer {
+                id: document.name.clone(),
+                name: name.clone(),
+                command,
+                args,
+                env: (!env.is_empty()).then(|| env.into_iter().collect()),
+                require_file_path: false,
+                repo_url: document
+                    .repository
+                    .as_ref()
+                    .map(|r| r.url.clone())
+                    .unwrap_or_default(),
+            },
+        );
+    }
+    Ok(prepared)
+}
+
+// The servers in a server.json file and how each could be run here
+#[tauri::command]
+pub fn read_server_json(path: String) -> Result<Vec<ServerJsonCheck>, String> {
+    Ok(read_documents(Path::new(&path))?
+        .iter()
+        .map(ServerJson::check)
+        .collect())
+}
+
+// `server` picks a document from a file with several, `variant` a package or remote;
+// `values` fills in the inputs the variant asks for
+#[tauri::command]
+pub async fn install_server_json(
+    app: AppHandle,
+    path: String,
+    server: Option<String>,
+    variant: Option<String>,
+    values: HashMap<String, String>,
+    clients: Option<Vec<String>>,
+    project: Option<String>,
+) -> Result<(), String> {
+    let project = project.map(PathBuf::from);
+    let document = find_server(&path, server.as_deref())?;
+    let entry = document.entry(variant.as_deref(), &values)?;
+    let name = document.install_name();
+    // Remote servers fetch no package; installing one over a local server drops its lock
+    let lock = match &entry {
+        ServerEntry::Stdio { command, args, .. } => {
+            crate::lockfile::resolve_command(command, args).await
+        }
+        ServerEntry::Remote { .. } => None,
+    };
+
+    let label = format!("Install from {}", path);
+    crate::transaction::run(&app, label, project.as_deref(), async {
+        prepare(&app, &document, entry, clients, project.as_deref())?
+            .apply(&app)
+            .await?;
+        crate::store::set_lock_entry(&app, &name, lock)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weather() -> ServerJson {
+        let sample = include_str!("../tests/fixtures/server_json/weather.json");
+        parse_documents(serde_json::from_str(sample).unwrap())
+            .unwrap()
+            .remove(0)
+    }
+
+    #[test]
+    fn maps_packages_and_remotes() {
+        let server = weather();
+        let values = HashMap::from([
+            ("WEATHER_API_KEY".to_string(), "secret".to_string()),
+            ("cache_dir".to_string(), "/tmp/weather".to_string()),
+            ("token".to_string(), "abc".to_string()),
+        ]);
+
+        let ServerEntry::Stdio {
+            command, args, env, ..
+        } = server.entry(Some("package-0"), &values).unwrap()
+        else {
+            panic!("expected a local server");
+        };
+        assert_eq!(command, "npx");
+        assert_eq!(
+            args,
+            [
+                "-y",
+                "@example/weather-mcp@1.2.0",
+                "--units",
+                "metric",
+                "/tmp/weather"
+            ]
+        );
+        assert_eq!(env["WEATHER_API_KEY"], "secret");
+
+        let ServerEntry::Stdio { args, .. } = server.entry(Some("package-1"), &values).unwrap()
+        else {
+            panic!("expected a local server");
+        };
+        assert_eq!(
+            args,
+            [
+                "run",
+                "-i",
+                "--rm",
+                "-e",
+                "WEATHER_API_KEY",
+                "ghcr.io/example/weather-mcp:1.2.0"
+            ]
+        );
+
+        let ServerEntry::Remote { headers, .. } = server.entry(Some("remote-0"), &values).unwrap()
+        else {
+            panic!("expected a remote server");
+        };
+        assert_eq!(headers["Authorization"], "Bearer abc");
+    }
+
+    #[test]
+    fn reports_missing_and_invalid_inputs() {
+        let server = weather();
+        let err = server
+            .entry(Some("package-1"), &HashMap::new())
+            .unwrap_err();
+        assert_eq!(err, "Missing value for `WEATHER_API_KEY`");
+
+        let values = HashMap::from([
+            ("WEATHER_API_KEY".to_string(), "secret".to_string()),
+            ("units".to_string(), "kelvin".to_string()),
+        ]);
+        let err = server.entry(Some("package-0"), &values).unwrap_err();
+        assert_eq!(err, "`units` must be one of metric, imperial");
+    }
+}
@@ -1,11 +1,106 @@
+use crate::claude_config::ServerConfig;
+use crate::client_requests::CallbackMode;
+use crate::config_history::RetentionPolicy;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use serde_json;
+use serde_json::{self, Value as JsonValue};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tauri::{AppHandle, Wry};
+use tauri::{AppHandle, Runtime, Wry};
 use tauri_plugin_store::{Store, StoreBuilder};
 
+// A server taken out of a client config, kept exactly as it was written there
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DisabledServer {
+    pub name: String,
+    pub client: String,
+    pub project: Option<String>,
+    pub entry: ServerConfig,
+    // Index among the client's servers, so enabling puts it back in place
+    pub position: usize,
+    pub disabled_at: u64,
+}
+
+// A named set of server definitions that can replace a client's servers at once
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Profile {
+    pub name: String,
+    pub servers: IndexMap<String, ServerConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectEntry {
+    pub path: String,
+    pub name: String,
+    pub added_at: u64,
+}
+
+// Where and how deep `scan_projects` looks for project configs
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectScanSettings {
+    pub roots: Vec<String>,
+    pub max_depth: usize,
+    // Directory names to skip; `*` matches any run of characters
+    pub ignore: Vec<String>,
+}
+
+impl Default for ProjectScanSettings {
+    fn default() -> Self {
+        ProjectScanSettings {
+            roots: Vec::new(),
+            max_depth: 3,
+            ignore: [
+                ".git",
+                "node_modules",
+                "target",
+                "dist",
+                "build",
+                ".venv",
+                "vendor",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        }
+    }
+}
+
+// A header sent with every request to a registry, e.g. `Authorization: Bearer ...`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RegistryAuth {
+    pub name: String,
+    pub value: String,
+}
+
+// A template API queried by `get_mcp_server_templates`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TemplateRegistry {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub auth: Option<RegistryAuth>,
+    // Higher priority wins when registries return the same template
+    pub priority: i32,
+    pub enabled: bool,
+    pub timeout_ms: u64,
+}
+
+impl TemplateRegistry {
+    // The registry the app has always used, kept as the default
+    pub fn local() -> Self {
+        TemplateRegistry {
+            id: "local".to_string(),
+            name: "Local".to_string(),
+            url: "http://127.0.0.1:3988/api/v1/templates".to_string(),
+            auth: None,
+            priority: 0,
+            enabled: true,
+            timeout_ms: 5000,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct InstalledMcpServer {
     pub id: String,
@@ -17,22 +112,67 @@ pub struct InstalledMcpServer {
     pub repo_url: String,
 }
 
+// The package a server's launcher fetched, as resolved when it was installed or pinned
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LockEntry {
+    pub runner: String,
+    // The package argument as written in the server's args, e.g. `@scope/pkg` or `pkg==1.0`
+    pub requested: String,
+    pub package: String,
+    pub version: String,
+    // npm's subresource integrity, the PyPI sha256 or the image digest, when known
+    pub integrity: Option<String>,
+    pub resolved_at: u64,
+}
+
 const STORE_PATH: &str = ".mcp.servers.dat";
 const SERVERS_KEY: &str = "installed_servers";
+const ROOT_GRANTS_KEY: &str = "root_grants";
+const CALLBACK_MODES_KEY: &str = "callback_modes";
+const HISTORY_RETENTION_KEY: &str = "history_retention";
+const DISABLED_SERVERS_KEY: &str = "disabled_servers";
+const PROFILES_KEY: &str = "profiles";
+const ACTIVE_PROFILES_KEY: &str = "active_profiles";
+const PROJECTS_KEY: &str = "projects";
+const PROJECT_SCAN_KEY: &str = "project_scan";
+const TEMPLATE_REGISTRIES_KEY: &str = "template_registries";
+const PACKAGE_LOCK_KEY: &str = "package_lock";
 
-fn get_store(app: &AppHandle) -> Result<Arc<Store<Wry>>, String> {
+fn get_store<R: Runtime>(app: &AppHandle<R>) -> Result<Arc<Store<R>>, String> {
     let path = PathBuf::from(STORE_PATH);
     StoreBuilder::new(app, path)
         .build()
         .map_err(|e| format!("Failed to create store: {}", e))
 }
 
-fn save_store(store: &Store<Wry>) -> Result<(), String> {
+fn save_store<R: Runtime>(store: &Store<R>) -> Result<(), String> {
     store
         .save()
         .map_err(|e| format!("Failed to save store: {}", e))
 }
 
+// Every value in the store, so transactions can tell what an operation changed
+pub fn store_entries(app: &AppHandle) -> Result<HashMap<String, JsonValue>, String> {
+    Ok(get_store(app)?.entries().into_iter().collect())
+}
+
+// Set or delete several keys with one save; `None` deletes the key
+pub fn restore_store_entries(
+    app: &AppHandle,
+    entries: &[(String, Option<JsonValue>)],
+) -> Result<(), String> {
+    let store = get_store(app)?;
+    for (key, value) in entries {
+        match value {
+            Some(value) => store.set(key.clone(), value.clone()),
+            None => {
+                store.delete(key);
+            }
+        }
+    }
+    save_store(&store)
+}
+
 #[tauri::command]
 pub async fn save_installed_server(
     app: AppHandle,
@@ -68,6 +208,17 @@ pub async fn get_installed_server(
     Ok(servers.get(&name).cloned())
 }
 
+pub fn get_installed_servers(
+    app: &AppHandle,
+) -> Result<HashMap<String, InstalledMcpServer>, String> {
+    let store = get_store(app)?;
+
+    Ok(store
+        .get(SERVERS_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default())
+}
+
 #[tauri::command]
 pub async fn remove_installed_server(app: AppHandle, name: String) -> Result<(), String> {
     let store = get_store(&app)?;
@@ -84,3 +235,309 @@ pub async fn remove_installed_server(app: AppHandle, name: String) -> Result<(),
 
     Ok(())
 }
+
+fn load_root_grants(store: &Store<Wry>) -> HashMap<String, Vec<String>> {
+    store
+        .get(ROOT_GRANTS_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+// Folders the user granted to a server, answered to `roots/list`
+pub fn get_root_grants(app: &AppHandle, name: &str) -> Result<Vec<String>, String> {
+    let store = get_store(app)?;
+    Ok(load_root_grants(&store).remove(name).unwrap_or_default())
+}
+
+pub fn add_root_grants(app: &AppHandle, name: &str, folders: &[String]) -> Result<(), String> {
+    let store = get_store(app)?;
+    let mut grants = load_root_grants(&store);
+
+    let entry = grants.entry(name.to_string()).or_default();
+    let before = entry.len();
+    for folder in folders {
+        if !entry.contains(folder) {
+            entry.push(folder.clone());
+        }
+    }
+    if entry.len() == before {
+        return Ok(());
+    }
+
+    store.set(ROOT_GRANTS_KEY.to_string(), serde_json::json!(grants));
+    save_store(&store)?;
+
+    crate::mcp_client::notify_roots_changed(name);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_server_roots(app: AppHandle, name: String) -> Result<Vec<String>, String> {
+    get_root_grants(&app, &name)
+}
+
+#[tauri::command]
+pub async fn remove_server_root(app: AppHandle, name: String, path: String) -> Result<(), String> {
+    let label = format!("Revoke folder from {}", name);
+    crate::transaction::run(&app, label, None, async {
+        remove_root_grant(&app, &name, &path)
+    })
+    .await
+}
+
+fn remove_root_grant(app: &AppHandle, name: &str, path: &str) -> Result<(), String> {
+    let store = get_store(app)?;
+    let mut grants = load_root_grants(&store);
+
+    let Some(entry) = grants.get_mut(name) else {
+        return Ok(());
+    };
+    let before = entry.len();
+    entry.retain(|folder| folder != path);
+    if entry.len() == before {
+        return Ok(());
+    }
+    if entry.is_empty() {
+        grants.remove(name);
+    }
+
+    store.set(ROOT_GRANTS_KEY.to_string(), serde_json::json!(grants));
+    save_store(&store)?;
+
+    crate::mcp_client::notify_roots_changed(name);
+    Ok(())
+}
+
+fn load_callback_modes(store: &Store<Wry>) -> HashMap<String, CallbackMode> {
+    store
+        .get(CALLBACK_MODES_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+// How sampling and elicitation requests from a server are answered
+pub fn get_callback_mode(app: &AppHandle, name: &str) -> Result<CallbackMode, String> {
+    let store = get_store(app)?;
+    Ok(load_callback_modes(&store).remove(name).unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn get_server_callback_mode(
+    app: AppHandle,
+    name: String,
+) -> Result<CallbackMode, String> {
+    get_callback_mode(&app, &name)
+}
+
+#[tauri::command]
+pub async fn set_server_callback_mode(
+    app: AppHandle,
+    name: String,
+    mode: CallbackMode,
+) -> Result<(), String> {
+    let store = get_store(&app)?;
+    let mut modes = load_callback_modes(&store);
+
+    if mode == CallbackMode::default() {
+        modes.remove(&name);
+    } else {
+        modes.insert(name, mode);
+    }
+
+    store.set(CALLBACK_MODES_KEY.to_string(), serde_json::json!(modes));
+    save_store(&store)?;
+
+    Ok(())
+}
+
+pub fn get_history_retention(app: &AppHandle) -> Result<RetentionPolicy, String> {
+    let store = get_store(app)?;
+    Ok(store
+        .get(HISTORY_RETENTION_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default())
+}
+
+pub fn save_history_retention(app: &AppHandle, retention: &RetentionPolicy) -> Result<(), String> {
+    let store = get_store(app)?;
+    store.set(
+        HISTORY_RETENTION_KEY.to_string(),
+        serde_json::json!(retention),
+    );
+    save_store(&store)
+}
+
+pub fn get_disabled_servers<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<DisabledServer>, String> {
+    let store = get_store(app)?;
+    Ok(store
+        .get(DISABLED_SERVERS_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default())
+}
+
+pub fn save_disabled_servers(app: &AppHandle, servers: &[DisabledServer]) -> Result<(), String> {
+    let store = get_store(app)?;
+    store.set(DISABLED_SERVERS_KEY.to_string(), serde_json::json!(servers));
+    save_store(&store)
+}
+
+fn is_same(server: &DisabledServer, client: &str, project: Option<&str>, name: &str) -> bool {
+    server.client == client && server.project.as_deref() == project && server.name == name
+}
+
+pub fn park_disabled_server(app: &AppHandle, server: DisabledServer) -> Result<(), String> {
+    let mut servers = get_disabled_servers(app)?;
+    servers.retain(|s| !is_same(s, &server.client, server.project.as_deref(), &server.name));
+    servers.push(server);
+    save_disabled_servers(app, &servers)
+}
+
+pub fn take_disabled_server(
+    app: &AppHandle,
+    client: &str,
+    project: Option<&str>,
+    name: &str,
+) -> Result<Option<DisabledServer>, String> {
+    let mut servers = get_disabled_servers(app)?;
+    let Some(index) = servers
+        .iter()
+        .position(|s| is_same(s, client, project, name))
+    else {
+        return Ok(None);
+    };
+    let server = servers.remove(index);
+    save_disabled_servers(app, &servers)?;
+    Ok(Some(server))
+}
+
+#[tauri::command]
+pub async fn list_disabled_servers(app: AppHandle) -> Result<Vec<DisabledServer>, String> {
+    get_disabled_servers(&app)
+}
+
+pub fn get_profiles(app: &AppHandle) -> Result<IndexMap<String, Profile>, String> {
+    let store = get_store(app)?;
+    Ok(store
+        .get(PROFILES_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default())
+}
+
+pub fn save_profiles(app: &AppHandle, profiles: &IndexMap<String, Profile>) -> Result<(), String> {
+    let store = get_store(app)?;
+    store.set(PROFILES_KEY.to_string(), serde_json::json!(profiles));
+    save_store(&store)
+}
+
+// Profile last activated for each client id
+pub fn get_active_profiles<R: Runtime>(
+    app: &AppHandle<R>,
+) -> Result<HashMap<String, String>, String> {
+    let store = get_store(app)?;
+    Ok(store
+        .get(ACTIVE_PROFILES_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default())
+}
+
+pub fn set_active_profile(
+    app: &AppHandle,
+    client: &str,
+    profile: Option<&str>,
+) -> Result<(), String> {
+    let store = get_store(app)?;
+    let mut active = get_active_profiles(app)?;
+    match profile {
+        Some(profile) => active.insert(client.to_string(), profile.to_string()),
+        None => active.remove(client),
+    };
+    store.set(ACTIVE_PROFILES_KEY.to_string(), serde_json::json!(active));
+    save_store(&store)
+}
+
+pub fn get_projects<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<ProjectEntry>, String> {
+    let store = get_store(app)?;
+    Ok(store
+        .get(PROJECTS_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default())
+}
+
+pub fn save_projects(app: &AppHandle, projects: &[ProjectEntry]) -> Result<(), String> {
+    let store = get_store(app)?;
+    store.set(PROJECTS_KEY.to_string(), serde_json::json!(projects));
+    save_store(&store)
+}
+
+pub fn get_project_scan_settings(app: &AppHandle) -> Result<ProjectScanSettings, String> {
+    let store = get_store(app)?;
+    Ok(store
+        .get(PROJECT_SCAN_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default())
+}
+
+pub fn save_project_scan_settings(
+    app: &AppHandle,
+    settings: &ProjectScanSettings,
+) -> Result<(), String> {
+    let store = get_store(app)?;
+    store.set(PROJECT_SCAN_KEY.to_string(), serde_json::json!(settings));
+    save_store(&store)
+}
+
+pub fn get_template_registries<R: Runtime>(
+    app: &AppHandle<R>,
+) -> Result<Vec<TemplateRegistry>, String> {
+    let store = get_store(app)?;
+    Ok(store
+        .get(TEMPLATE_REGISTRIES_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_else(|| vec![TemplateRegistry::local()]))
+}
+
+pub fn save_template_registries(
+    app: &AppHandle,
+    registries: &[TemplateRegistry],
+) -> Result<(), String> {
+    let store = get_store(app)?;
+    store.set(
+        TEMPLATE_REGISTRIES_KEY.to_string(),
+        serde_json::json!(registries),
+    );
+    save_store(&store)
+}
+
+// Lock entries by server name
+pub fn get_package_lock(app: &AppHandle) -> Result<IndexMap<String, LockEntry>, String> {
+    let store = get_store(app)?;
+    Ok(store
+        .get(PACKAGE_LOCK_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default())
+}
+
+pub fn save_package_lock(
+    app: &AppHandle,
+    lock: &IndexMap<String, LockEntry>,
+) -> Result<(), String> {
+    let store = get_store(app)?;
+    store.set(PACKAGE_LOCK_KEY.to_string(), serde_json::json!(lock));
+    save_store(&store)
+}
+
+// Record or, with None, drop a server's lock entry
+pub fn set_lock_entry(app: &AppHandle, name: &str, entry: Option<LockEntry>) -> Result<(), String> {
+    let mut lock = get_package_lock(app)?;
+    match entry {
+        Some(entry) => {
+            lock.insert(name.to_string(), entry);
+        }
+        None => {
+            if lock.shift_remove(name).is_none() {
+                return Ok(());
+            }
+        }
+    }
+    save_package_lock(app, &lock)
+}
@@ -0,0 +1,434 @@
+use crate::claude_config::ServerConfig;
+use crate::clients;
+use crate::server_entry::{self, ServerEntry};
+use crate::store::InstalledMcpServer;
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftStatus {
+    InSync,
+    Differs,
+    // In the store but not in the client
+    Missing,
+    // In the client but not in the store
+    Extra,
+    // The client's entry does not parse as a server definition
+    Invalid,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ValueDrift<T> {
+    pub store: T,
+    pub client: T,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ServerDrift {
+    pub server: String,
+    pub client: String,
+    pub status: DriftStatus,
+    pub command: Option<ValueDrift<String>>,
+    pub args: Option<ValueDrift<Vec<String>>>,
+    // Env values may hold secrets, so only key names are reported
+    pub env_missing: Vec<String>,
+    pub env_extra: Vec<String>,
+    pub env_changed: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct SyncReport {
+    pub servers: Vec<ServerDrift>,
+    // Clients whose config could not be read
+    pub errors: Vec<String>,
+}
+
+impl ServerDrift {
+    fn new(server: &str, client: &str, status: DriftStatus) -> Self {
+        ServerDrift {
+            server: server.to_string(),
+            client: client.to_string(),
+            status,
+            command: None,
+            args: None,
+            env_missing: Vec::new(),
+            env_extra: Vec::new(),
+            env_changed: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+fn compare(
+    canonical: &InstalledMcpServer,
+    client: &str,
+    entry: Option<&ServerConfig>,
+) -> ServerDrift {
+    let Some(entry) = entry else {
+        return ServerDrift::new(&canonical.name, client, DriftStatus::Missing);
+    };
+
+    let (command, args, env) = match ServerEntry::parse(entry) {
+        Ok(ServerEntry::Stdio {
+            command, args, env, ..
+        }) => (command, args, env),
+        Ok(ServerEntry::Remote { .. }) => {
+            let mut drift = ServerDrift::new(&canonical.name, client, DriftStatus::Differs);
+            drift.error = Some("The client has a remote server under this name".to_string());
+            return drift;
+        }
+        Err(diagnostics) => {
+            let mut drift = ServerDrift::new(&canonical.name, client, DriftStatus::Invalid);
+            drift.error = Some(server_entry::format_diagnostics(&diagnostics));
+            return drift;
+        }
+    };
+
+    let mut drift = ServerDrift::new(&canonical.name, client, DriftStatus::InSync);
+    if command != canonical.command {
+        drift.command = Some(ValueDrift {
+            store: canonical.command.clone(),
+            client: command,
+        });
+    }
+    if args != canonical.args {
+        drift.args = Some(ValueDrift {
+            store: canonical.args.clone(),
+            client: args,
+        });
+    }
+
+    let store_env = canonical.env.clone().unwrap_or_default();
+    let keys: BTreeSet<&String> = store_env.keys().chain(env.keys()).collect();
+    for key in keys {
+        match (store_env.get(key), env.get(key)) {
+            (Some(_), None) => drift.env_missing.push(key.clone()),
+            (None, Some(_)) => drift.env_extra.push(key.clone()),
+            (Some(a), Some(b)) if a != b => drift.env_changed.push(key.clone()),
+            _ => {}
+        }
+    }
+
+    let differs = drift.command.is_some()
+        || drift.args.is_some()
+        || !drift.env_missing.is_empty()
+        || !drift.env_extra.is_empty()
+        || !drift.env_changed.is_empty();
+    if differs {
+        drift.status = DriftStatus::Differs;
+    }
+    drift
+}
+
+// Drift of the `names` servers in one client's entries; with `report_extra` servers only
+// the client knows are reported too
+fn client_drift(
+    installed: &HashMap<String, InstalledMcpServer>,
+    names: &[&String],
+    client: &str,
+    entries: &IndexMap<String, ServerConfig>,
+    report_extra: bool,
+) -> Vec<ServerDrift> {
+    let mut drifts: Vec<ServerDrift> = names
+        .iter()
+        .map(|name| compare(&installed[*name], client, entries.get(*name)))
+        .collect();
+    if report_extra {
+        for name in entries.keys() {
+            if !installed.contains_key(name) {
+                drifts.push(ServerDrift::new(name, client, DriftStatus::Extra));
+            }
+        }
+    }
+    drifts
+}
+
+// Overwrite the definition in a client entry, keeping client-specific keys like `disabled`
+fn apply_canonical(entry: &mut ServerConfig, canonical: &InstalledMcpServer) {
+    for key in ["url", "headers", "transport"] {
+        entry.shift_remove(key);
+    }
+    if entry.get("type").is_some_and(|t| t != "stdio") {
+        entry.shift_remove("type");
+    }
+
+    entry.insert("command".to_string(), json!(canonical.command));
+    entry.insert("args".to_string(), json!(canonical.args));
+    match &canonical.env {
+        Some(env) if !env.is_empty() => {
+            entry.insert("env".to_string(), json!(env));
+        }
+        _ => {
+            entry.shift_remove("env");
+        }
+    }
+}
+
+// Store definitions, checking that every selected server is among them
+fn load_selected(
+    app: &AppHandle,
+    servers: Option<&[String]>,
+) -> Result<HashMap<String, InstalledMcpServer>, String> {
+    let installed = crate::store::get_installed_servers(app)?;
+    if let Some(name) = servers
+        .unwrap_or_default()
+        .iter()
+        .find(|name| !installed.contains_key(*name))
+    {
+        return Err(format!("MCP server '{}' is not in the store", name));
+    }
+    Ok(installed)
+}
+
+// Compare each client's copy against the store; without a selection every server is compared
+// and servers only the client knows are reported as extra
+#[tauri::command]
+pub fn get_sync_status(
+    app: AppHandle,
+    servers: Option<Vec<String>>,
+    clients: Option<Vec<String>>,
+    project: Option<String>,
+) -> Result<SyncReport, String> {
+    let installed = load_selected(&app, servers.as_deref())?;
+    let targets =
+        clients::resolve_targets(Some(clients.unwrap_or_else(|| vec!["all".to_string()])))?;
+    let project = project.map(PathBuf::from);
+
+    let mut names_sorted: Vec<&String> = match &servers {
+        Some(names) => names.iter().collect(),
+        None => installed.keys().collect(),
+    };
+    names_sorted.sort();
+
+    let mut report = SyncReport::default();
+    for client in targets {
+        let current = match client.read_servers(project.as_deref()) {
+            Ok(current) => current,
+            Err(e) => {
+                report.errors.push(format!("{}: {}", client.name(), e));
+                continue;
+            }
+        };
+
+        report.servers.extend(client_drift(
+            &installed,
+            &names_sorted,
+            client.id(),
+            &current.servers,
+            servers.is_none(),
+        ));
+    }
+
+    Ok(report)
+}
+
+// Write the store's definition of `servers` to the chosen clients
+#[tauri::command]
+pub async fn push_server_definitions(
+    app: AppHandle,
+    servers: Vec<String>,
+    clients: Vec<String>,
+    project: Option<String>,
+) -> Result<(), String> {
+    let installed = load_selected(&app, Some(&servers))?;
+    let targets = clients::resolve_targets(Some(clients))?;
+    let project = project.map(PathBuf::from);
+
+    let label = format!("Push {}", servers.join(", "));
+    crate::transaction::run(&app, label, project.as_deref(), async {
+        clients::edit_servers(&targets, project.as_deref(), |entries| {
+            for name in &servers {
+                let entry = entries.entry(name.clone()).or_default();
+                apply_canonical(entry, &installed[name]);
+            }
+            Ok(())
+        })
+    })
+    .await
+}
+
+// Adopt a client's copy of a server as the store's definition
+#[tauri::command]
+pub async fn pull_server_definition(
+    app: AppHandle,
+    server: String,
+    client: String,
+    project: Option<String>,
+) -> Result<InstalledMcpServer, String> {
+    let project = project.map(PathBuf::from);
+    let adapter = clients::find_client(&client)?;
+    let current = adapter.read_servers(project.as_deref())?;
+    let entry = current
+        .servers
+        .get(&server)
+        .ok_or_else(|| format!("MCP server '{}' not found in {}", server, adapter.name()))?;
+
+    let ServerEntry::Stdio {
+        command, args, env, ..
+    } = ServerEntry::parse(entry)
+        .map_err(|diagnostics| server_entry::format_diagnostics(&diagnostics))?
+    else {
+        return Err("Remote servers cannot be stored as a definition yet".to_string());
+    };
+
+    let mut definition = crate::store::get_installed_servers(&app)?
+        .remove(&server)
+        .unwrap_or_else(|| InstalledMcpServer {
+            id: server.clone(),
+            name: server.clone(),
+            command: String::new(),
+            args: Vec::new(),
+            env: None,
+            require_file_path: false,
+            repo_url: String::new(),
+        });
+    definition.command = command;
+    definition.args = args;
+    definition.env = (!env.is_empty()).then(|| env.into_iter().collect());
+
+    let lock = crate::lockfile::resolve_command(&definition.command, &definition.args).await;
+
+    let label = format!("Pull {} from {}", server, adapter.name());
+    crate::transaction::run(&app, label, None, async {
+        crate::store::save_installed_server(app.clone(), definition.clone()).await?;
+        crate::store::set_lock_entry(&app, &server, lock)
+    })
+    .await?;
+    Ok(definition)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(
+        name: &str,
+        command: &str,
+        args: &[&str],
+        env: &[(&str, &str)],
+    ) -> InstalledMcpServer {
+        InstalledMcpServer {
+            id: name.to_string(),
+            name: name.to_string(),
+            command: command.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            env: (!env.is_empty()).then(|| {
+                env.iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect()
+            }),
+            require_file_path: false,
+            repo_url: String::new(),
+        }
+    }
+
+    fn entry(value: serde_json::Value) -> ServerConfig {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn reports_drift_per_server() {
+        let installed = HashMap::from([
+            (
+                "git".to_string(),
+                server(
+                    "git",
+                    "uvx",
+                    &["mcp-server-git"],
+                    &[("A", "1"), ("B", "2"), ("C", "3")],
+                ),
+            ),
+            (
+                "fetch".to_string(),
+                server("fetch", "uvx", &["mcp-server-fetch"], &[]),
+            ),
+            ("broken".to_string(), server("broken", "npx", &[], &[])),
+            ("same".to_string(), server("same", "npx", &["pkg"], &[])),
+        ]);
+        let entries = IndexMap::from([
+            (
+                "git".to_string(),
+                entry(json!({
+                    "command": "uvx",
+                    "args": ["mcp-server-git", "--verbose"],
+                    "env": { "B": "2", "C": "changed", "D": "4" }
+                })),
+            ),
+            ("broken".to_string(), entry(json!({ "args": ["x"] }))),
+            (
+                "same".to_string(),
+                entry(json!({ "command": "npx", "args": ["pkg"], "disabled": true })),
+            ),
+            ("local".to_string(), entry(json!({ "command": "node" }))),
+        ]);
+        let names: Vec<String> = ["broken", "fetch", "git", "same"].map(String::from).into();
+        let names: Vec<&String> = names.iter().collect();
+
+        let drifts = client_drift(&installed, &names, "cursor", &entries, true);
+        let statuses: Vec<_> = drifts
+            .iter()
+            .map(|d| (d.server.as_str(), d.status.clone()))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("broken", DriftStatus::Invalid),
+                ("fetch", DriftStatus::Missing),
+                ("git", DriftStatus::Differs),
+                ("same", DriftStatus::InSync),
+                ("local", DriftStatus::Extra),
+            ]
+        );
+        assert!(drifts[0].error.is_some());
+
+        let git = &drifts[2];
+        assert!(git.command.is_none());
+        assert_eq!(
+            git.args.as_ref().unwrap().client,
+            ["mcp-server-git", "--verbose"]
+        );
+        assert_eq!(git.env_missing, ["A"]);
+        assert_eq!(git.env_extra, ["D"]);
+        assert_eq!(git.env_changed, ["C"]);
+
+        // A selection leaves the client's other servers alone
+        let selected = client_drift(&installed, &names[..1], "cursor", &entries, false);
+        assert_eq!(selected.len(), 1);
+    }
+
+    #[test]
+    fn canonical_definition_keeps_client_keys() {
+        let mut remote = entry(json!({
+            "type": "http",
+            "url": "https://example.com/mcp",
+            "headers": { "Authorization": "Bearer x" },
+            "disabled": true,
+            "env": { "OLD": "1" }
+        }));
+        apply_canonical(&mut remote, &server("git", "uvx", &["mcp-server-git"], &[]));
+        assert_eq!(
+            serde_json::Value::Object(remote),
+            json!({ "disabled": true, "command": "uvx", "args": ["mcp-server-git"] })
+        );
+
+        let mut stdio =
+            entry(json!({ "type": "stdio", "command": "npx", "alwaysAllow": ["read"] }));
+        apply_canonical(&mut stdio, &server("git", "uvx", &[], &[("TOKEN", "t")]));
+        assert_eq!(
+            serde_json::Value::Object(stdio),
+            json!({
+                "type": "stdio",
+                "command": "uvx",
+                "alwaysAllow": ["read"],
+                "args": [],
+                "env": { "TOKEN": "t" }
+            })
+        );
+    }
+}
@@ -0,0 +1,299 @@
+use crate::config_io;
+use crate::mcp_servers::{ApiData, McpServerTemplate};
+use crate::registries::TemplateQuery;
+use indexmap::IndexMap;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Responses kept per registry; the least recently confirmed are dropped first
+const RESPONSE_LIMIT: usize = 64;
+// Responses the registry has not confirmed for this long are dropped
+const RESPONSE_MAX_AGE_MS: u64 = 30 * 24 * 60 * 60 * 1000;
+
+static CACHE_ROOT: OnceCell<PathBuf> = OnceCell::new();
+static CACHE_LOCK: Mutex<()> = Mutex::new(());
+
+// A registry response and the validators to revalidate it with
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CachedResponse {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    // When the registry last confirmed this response, in ms since the epoch
+    pub validated_at: u64,
+    pub total: i32,
+    pub list: Vec<McpServerTemplate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct RegistryCache {
+    url: String,
+    responses: HashMap<String, CachedResponse>,
+    // When any response from the registry was last confirmed
+    validated_at: u64,
+}
+
+pub fn init(root: PathBuf) {
+    let _ = CACHE_ROOT.set(root);
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+// One file per registry URL
+fn cache_file(url: &str) -> Option<PathBuf> {
+    let root = CACHE_ROOT.get()?;
+    Some(root.join(format!("{}.json", &config_io::content_hash(url)[..16])))
+}
+
+fn load(url: &str) -> RegistryCache {
+    cache_file(url)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<RegistryCache>(&content).ok())
+        .filter(|cache| cache.url == url)
+        .unwrap_or_else(|| RegistryCache {
+            url: url.to_string(),
+            ..Default::default()
+        })
+}
+
+fn save(cache: &RegistryCache) -> Result<(), String> {
+    let Some(path) = cache_file(&cache.url) else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create cache directory: {}", e))?;
+    }
+    let content = serde_json::to_string(cache)
+        .map_err(|e| format!("Failed to serialize template cache: {}", e))?;
+    config_io::replace_file(&path, &content)
+}
+
+fn template_id(template: &McpServerTemplate) -> String {
+    if template.id.is_empty() {
+        template.name.clone()
+    } else {
+        template.id.clone()
+    }
+}
+
+// Templates of the cached responses by id, the most recently confirmed first
+fn templates(cache: &RegistryCache) -> IndexMap<String, &McpServerTemplate> {
+    let mut responses: Vec<&CachedResponse> = cache.responses.values().collect();
+    responses.sort_by_key(|r| std::cmp::Reverse(r.validated_at));
+    let mut templates = IndexMap::new();
+    for template in responses.into_iter().flat_map(|r| &r.list) {
+        templates.entry(template_id(template)).or_insert(template);
+    }
+    templates
+}
+
+// The cached response to revalidate a query with, if any
+pub fn lookup(url: &str, query: &TemplateQuery) -> Option<CachedResponse> {
+    let _lock = CACHE_LOCK.lock().ok()?;
+    load(url).responses.get(&query.cache_key()).cloned()
+}
+
+// Record a fresh or revalidated response as confirmed now
+pub fn store(url: &str, query: &TemplateQuery, mut response: CachedResponse) {
+    let Ok(_lock) = CACHE_LOCK.lock() else {
+        return;
+    };
+    let now = now_ms();
+    response.validated_at = now;
+    let mut cache = load(url);
+
+    // A complete, unfiltered listing is authoritative: responses that still list a
+    // template missing from it are outdated, the template was withdrawn
+    let complete = query.page <= 1
+        && query.keyword.is_none()
+        && query.require_file_path.is_none()
+        && response.list.len() as i32 >= response.total;
+    if complete {
+        let listed: HashSet<String> = response.list.iter().map(template_id).collect();
+        cache
+            .responses
+            .retain(|_, cached| cached.list.iter().all(|t| listed.contains(&template_id(t))));
+    }
+
+    cache.validated_at = cache.validated_at.max(now);
+    cache.responses.insert(query.cache_key(), response);
+    cache
+        .responses
+        .retain(|_, cached| now.saturating_sub(cached.validated_at) <= RESPONSE_MAX_AGE_MS);
+    while cache.responses.len() > RESPONSE_LIMIT {
+        let oldest = cache
+            .responses
+            .iter()
+            .min_by_key(|(_, cached)| cached.validated_at)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = oldest {
+            cache.responses.remove(&key);
+        }
+    }
+    if let Err(e) = save(&cache) {
+        eprintln!("{}", e);
+    }
+}
+
+// Answer a query without the registry: the same query's cached response, or a search
+// over every template seen so far. Returns the data and when it was last confirmed.
+pub fn offline(url: &str, query: &TemplateQuery) -> Option<(ApiData, u64)> {
+    let _lock = CACHE_LOCK.lock().ok()?;
+    let cache = load(url);
+    if let Some(response) = cache.responses.get(&query.cache_key()) {
+        let data = ApiData {
+            total: response.total,
+            list: response.list.clone(),
+            errors: Vec::new(),
+            stale_since: None,
+        };
+        return Some((data, response.validated_at));
+    }
+    let templates = templates(&cache);
+    if templates.is_empty() {
+        return None;
+    }
+
+    let (total, list) = query.filter().select(
+        templates.into_values(),
+        query.page as usize,
+        query.page_size as usize,
+    );
+    let data = ApiData {
+        total: total as i32,
+        list: list.into_iter().cloned().collect(),
+        errors: Vec::new(),
+        stale_since: None,
+    };
+    Some((data, cache.validated_at))
+}
+
+// A template as last seen from the registry at `url`
+pub fn find_template(url: &str, id: &str) -> Option<McpServerTemplate> {
+    let _lock = CACHE_LOCK.lock().ok()?;
+    templates(&load(url)).get(id).map(|t| (*t).clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn template(name: &str, description: &str, require_file_path: bool) -> McpServerTemplate {
+        serde_json::from_value(json!({
+            "id": name,
+            "name": name,
+            "description": description,
+            "command": "npx",
+            "args": [],
+            "env": null,
+            "require_file_path": require_file_path,
+            "repo_url": "",
+            "created_at": "",
+            "updated_at": "",
+            "downloads": 0,
+            "total_usage_time": 0,
+        }))
+        .unwrap()
+    }
+
+    fn query(page: u32, keyword: Option<&str>) -> TemplateQuery {
+        TemplateQuery {
+            page,
+            page_size: 2,
+            keyword: keyword.map(str::to_string),
+            require_file_path: None,
+        }
+    }
+
+    fn response(list: Vec<McpServerTemplate>, total: i32) -> CachedResponse {
+        CachedResponse {
+            etag: None,
+            last_modified: None,
+            validated_at: 0,
+            total,
+            list,
+        }
+    }
+
+    fn setup(name: &str) -> String {
+        init(std::env::temp_dir().join(format!("mcp-template-cache-{}", std::process::id())));
+        format!("https://registry.test/{}", name)
+    }
+
+    fn names(data: &ApiData) -> Vec<&str> {
+        data.list.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    #[test]
+    fn offline_searches_every_cached_template() {
+        let url = setup("offline");
+        store(
+            &url,
+            &query(1, None),
+            response(
+                vec![
+                    template("git", "Repository tools", false),
+                    template("files", "Read local files", true),
+                ],
+                3,
+            ),
+        );
+        store(
+            &url,
+            &query(2, None),
+            response(vec![template("fetch", "Fetch web pages", false)], 3),
+        );
+
+        // The same query answers with its own response
+        let (cached, validated_at) = offline(&url, &query(2, None)).unwrap();
+        assert_eq!(names(&cached), ["fetch"]);
+        assert!(validated_at > 0);
+
+        // Others search the templates of all responses
+        let (found, _) = offline(&url, &query(1, Some("FILES"))).unwrap();
+        assert_eq!(names(&found), ["files"]);
+        let (found, _) = offline(&url, &query(1, Some("e"))).unwrap();
+        assert_eq!(found.total, 3);
+        assert_eq!(found.list.len(), 2);
+        let only_paths = TemplateQuery {
+            require_file_path: Some(true),
+            ..query(1, Some("e"))
+        };
+        assert_eq!(names(&offline(&url, &only_paths).unwrap().0), ["files"]);
+
+        assert!(offline("https://registry.test/unknown", &query(1, None)).is_none());
+    }
+
+    #[test]
+    fn complete_listings_drop_withdrawn_templates() {
+        let url = setup("withdrawn");
+        store(
+            &url,
+            &query(1, Some("git")),
+            response(
+                vec![template("git", "", false), template("gitlab", "", false)],
+                2,
+            ),
+        );
+        assert!(find_template(&url, "gitlab").is_some());
+
+        store(
+            &url,
+            &query(1, None),
+            response(vec![template("git", "", false)], 1),
+        );
+        assert!(find_template(&url, "git").is_some());
+        assert!(find_template(&url, "gitlab").is_none());
+        assert!(lookup(&url, &query(1, Some("git"))).is_none());
+    }
+}
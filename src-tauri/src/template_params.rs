@@ -0,0 +1,322 @@
+use crate::mcp_servers::McpServerTemplate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParameterType {
+    String,
+    Number,
+    Boolean,
+    // An existing file or directory
+    Path,
+    Directory,
+    Url,
+    Port,
+    // One of `choices`
+    Enum,
+}
+
+// Where a parameter's value goes in the rendered entry
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ParameterTarget {
+    // Replaces `{{name}}` in args and env values; an arg that is only the placeholder
+    // expands to one arg per value
+    #[default]
+    Placeholder,
+    Env {
+        name: String,
+    },
+    // Appended to args
+    Args,
+    // Appended to args as `flag value`, once per value; booleans add just the flag when true
+    Flag {
+        flag: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TemplateParameter {
+    pub name: String,
+    #[serde(rename = "type", default = "default_type")]
+    pub kind: ParameterType,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub default: Option<JsonValue>,
+    // Entered masked, like a password
+    #[serde(default)]
+    pub secret: bool,
+    // Accepts a list of values, e.g. several directories
+    #[serde(default)]
+    pub multiple: bool,
+    #[serde(default)]
+    pub choices: Vec<String>,
+    #[serde(default)]
+    pub target: ParameterTarget,
+}
+
+fn default_type() -> ParameterType {
+    ParameterType::String
+}
+
+fn placeholder(name: &str) -> String {
+    format!("{{{{{}}}}}", name)
+}
+
+// Strings, numbers and booleans, or a list of them for `multiple` parameters
+fn to_strings(parameter: &TemplateParameter, value: &JsonValue) -> Result<Vec<String>, String> {
+    let scalar = |value: &JsonValue| match value {
+        JsonValue::String(s) => Ok(s.trim().to_string()),
+        JsonValue::Number(n) => Ok(n.to_string()),
+        JsonValue::Bool(b) => Ok(b.to_string()),
+        _ => Err(format!("`{}` must be a single value", parameter.name)),
+    };
+    let values = match value {
+        JsonValue::Null => Vec::new(),
+        JsonValue::Array(items) if parameter.multiple => {
+            items.iter().map(scalar).collect::<Result<_, _>>()?
+        }
+        JsonValue::Array(_) => {
+            return Err(format!("`{}` takes a single value", parameter.name));
+        }
+        value => vec![scalar(value)?],
+    };
+    Ok(values.into_iter().filter(|v| !v.is_empty()).collect())
+}
+
+fn check(parameter: &TemplateParameter, value: &str) -> Result<(), String> {
+    let name = &parameter.name;
+    let ok = match parameter.kind {
+        ParameterType::String => true,
+        ParameterType::Number => value.parse::<f64>().is_ok_and(f64::is_finite),
+        ParameterType::Boolean => matches!(value, "true" | "false"),
+        ParameterType::Port => value.parse::<u16>().is_ok_and(|port| port > 0),
+        ParameterType::Url => reqwest::Url::parse(value).is_ok_and(|url| url.has_host()),
+        ParameterType::Enum => parameter.choices.iter().any(|c| c == value),
+        ParameterType::Path => {
+            let path = Path::new(value);
+            if !path.is_absolute() {
+                return Err(format!("`{}` must be an absolute path", name));
+            }
+            path.exists()
+        }
+        ParameterType::Directory => {
+            let path = Path::new(value);
+            if !path.is_absolute() {
+                return Err(format!("`{}` must be an absolute path", name));
+            }
+            path.is_dir()
+        }
+    };
+    if ok {
+        return Ok(());
+    }
+    Err(match parameter.kind {
+        ParameterType::Enum => {
+            format!("`{}` must be one of {}", name, parameter.choices.join(", "))
+        }
+        ParameterType::Path => format!("`{}`: {} does not exist", name, value),
+        ParameterType::Directory => format!("`{}`: {} is not a directory", name, value),
+        kind => format!(
+            "`{}` must be a {}, got '{}'",
+            name,
+            serde_json::to_value(kind)
+                .ok()
+                .and_then(|k| k.as_str().map(str::to_string))
+                .unwrap_or_default(),
+            value
+        ),
+    })
+}
+
+// Validate the supplied values against the template's parameters; missing values fall back
+// to defaults. Returns the values of every parameter that has one.
+pub fn validate(
+    parameters: &[TemplateParameter],
+    values: &HashMap<String, JsonValue>,
+) -> Result<HashMap<String, Vec<String>>, String> {
+    if let Some(unknown) = values
+        .keys()
+        .find(|key| !parameters.iter().any(|p| &p.name == *key))
+    {
+        return Err(format!("Unknown parameter `{}`", unknown));
+    }
+
+    let mut resolved = HashMap::new();
+    for parameter in parameters {
+        let mut found = match values.get(&parameter.name) {
+            Some(value) => to_strings(parameter, value)?,
+            None => Vec::new(),
+        };
+        if found.is_empty() {
+            if let Some(default) = &parameter.default {
+                found = to_strings(parameter, default)?;
+            }
+        }
+        if found.is_empty() {
+            if parameter.required {
+                return Err(format!("Missing value for `{}`", parameter.name));
+            }
+            continue;
+        }
+        for value in &found {
+            check(parameter, value)?;
+        }
+        resolved.insert(parameter.name.clone(), found);
+    }
+    Ok(resolved)
+}
+
+// The template with its parameters validated and rendered into args and env
+pub fn render(
+    template: &McpServerTemplate,
+    values: &HashMap<String, JsonValue>,
+) -> Result<McpServerTemplate, String> {
+    let resolved = validate(&template.parameters, values)?;
+    let mut rendered = template.clone();
+    let mut env = rendered.env.take().unwrap_or_default();
+    // Env entries that only held a placeholder for an optional parameter are dropped
+    let mut emptied = Vec::new();
+
+    for parameter in &template.parameters {
+        let found = resolved.get(&parameter.name);
+        match &parameter.target {
+            ParameterTarget::Placeholder => {
+                let token = placeholder(&parameter.name);
+                let joined = found.map(|v| v.join(",")).unwrap_or_default();
+                rendered.args = rendered
+                    .args
+                    .iter()
+                    .flat_map(|arg| {
+                        if *arg == token {
+                            found.cloned().unwrap_or_default()
+                        } else {
+                            vec![arg.replace(&token, &joined)]
+                        }
+                    })
+                    .collect();
+                for (key, value) in env.iter_mut() {
+                    if value.contains(&token) {
+                        *value = value.replace(&token, &joined);
+                        if value.is_empty() {
+                            emptied.push(key.clone());
+                        }
+                    }
+                }
+            }
+            ParameterTarget::Env { name } => {
+                if let Some(found) = found {
+                    env.insert(name.clone(), found.join(","));
+                }
+            }
+            ParameterTarget::Args => rendered.args.extend(found.cloned().unwrap_or_default()),
+            ParameterTarget::Flag { flag } => {
+                for value in found.into_iter().flatten() {
+                    match (parameter.kind, value.as_str()) {
+                        (ParameterType::Boolean, "true") => rendered.args.push(flag.clone()),
+                        (ParameterType::Boolean, _) => {}
+                        _ => rendered.args.extend([flag.clone(), value.clone()]),
+                    }
+                }
+            }
+        }
+    }
+
+    env.retain(|key, _| !emptied.contains(key));
+    if let Some(arg) = rendered
+        .args
+        .iter()
+        .chain(env.values())
+        .find(|value| value.contains("{{"))
+    {
+        return Err(format!("'{}' uses an undeclared parameter", arg));
+    }
+    rendered.env = (!env.is_empty() || template.env.is_some()).then_some(env);
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn template(args: &[&str], parameters: JsonValue) -> McpServerTemplate {
+        serde_json::from_value(json!({
+            "id": "demo",
+            "name": "demo",
+            "description": "",
+            "command": "npx",
+            "args": args,
+            "env": null,
+            "parameters": parameters,
+            "repo_url": "",
+            "created_at": "",
+            "updated_at": "",
+            "downloads": 0,
+            "total_usage_time": 0
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn renders_values_into_args_and_env() {
+        let dir = std::env::temp_dir().to_string_lossy().to_string();
+        let template = template(
+            &["-y", "demo-server", "{{dirs}}"],
+            json!([
+                { "name": "dirs", "type": "directory", "required": true, "multiple": true },
+                { "name": "token", "secret": true, "target": { "kind": "env", "name": "API_TOKEN" } },
+                { "name": "port", "type": "port", "default": 8080, "target": { "kind": "flag", "flag": "--port" } },
+                { "name": "verbose", "type": "boolean", "target": { "kind": "flag", "flag": "-v" } }
+            ]),
+        );
+        let values = HashMap::from([
+            ("dirs".to_string(), json!([dir.clone(), dir.clone()])),
+            ("token".to_string(), json!("abc")),
+            ("verbose".to_string(), json!(true)),
+        ]);
+
+        let rendered = render(&template, &values).unwrap();
+        assert_eq!(
+            rendered.args,
+            ["-y", "demo-server", &dir, &dir, "--port", "8080", "-v"]
+        );
+        assert_eq!(rendered.env.unwrap()["API_TOKEN"], "abc");
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let template = template(
+            &["{{mode}}"],
+            json!([
+                { "name": "mode", "type": "enum", "required": true, "choices": ["read", "write"] },
+                { "name": "port", "type": "port", "target": { "kind": "args" } }
+            ]),
+        );
+        let values = |pairs: &[(&str, JsonValue)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        let err = render(&template, &values(&[])).unwrap_err();
+        assert_eq!(err, "Missing value for `mode`");
+        let err = render(&template, &values(&[("mode", json!("delete"))])).unwrap_err();
+        assert_eq!(err, "`mode` must be one of read, write");
+        let err = render(
+            &template,
+            &values(&[("mode", json!("read")), ("port", json!(70000))]),
+        )
+        .unwrap_err();
+        assert_eq!(err, "`port` must be a port, got '70000'");
+        let err = render(&template, &values(&[("other", json!("x"))])).unwrap_err();
+        assert_eq!(err, "Unknown parameter `other`");
+    }
+}
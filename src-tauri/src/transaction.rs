@@ -0,0 +1,459 @@
+use crate::clients;
+use crate::config_io;
+use crate::json_edit::{self, JsonStyle};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+// Transactions kept for undo; older ones are dropped
+const JOURNAL_LIMIT: usize = 50;
+
+// One transaction at a time, so each sees the state the previous one left
+static ACTIVE: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+static JOURNAL: Lazy<Mutex<Journal>> = Lazy::new(|| Mutex::new(Journal::default()));
+
+#[derive(Debug, Clone)]
+struct Change<K, V> {
+    key: K,
+    before: V,
+    after: V,
+}
+
+// A config file and the pointers of the values the app manages in it
+#[derive(Debug, Clone, PartialEq)]
+struct Tracked {
+    path: PathBuf,
+    pointers: Vec<&'static str>,
+}
+
+// Values at a tracked file's pointers, `None` where absent
+type Values = Vec<Option<JsonValue>>;
+
+#[derive(Debug, Clone)]
+struct JournalEntry {
+    id: u64,
+    label: String,
+    time: u64,
+    files: Vec<Change<Tracked, Values>>,
+    store: Vec<Change<String, Option<JsonValue>>>,
+}
+
+#[derive(Default)]
+struct Journal {
+    next_id: u64,
+    undo: Vec<JournalEntry>,
+    redo: Vec<JournalEntry>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TransactionSummary {
+    pub id: u64,
+    pub label: String,
+    pub time: u64,
+    pub files: Vec<String>,
+    pub store_keys: Vec<String>,
+}
+
+// Most recent first in both lists
+#[derive(Debug, Serialize, Clone)]
+pub struct JournalState {
+    pub undo: Vec<TransactionSummary>,
+    pub redo: Vec<TransactionSummary>,
+}
+
+impl JournalEntry {
+    fn summary(&self) -> TransactionSummary {
+        TransactionSummary {
+            id: self.id,
+            label: self.label.clone(),
+            time: self.time,
+            files: self
+                .files
+                .iter()
+                .map(|f| f.key.path.to_string_lossy().to_string())
+                .collect(),
+            store_keys: self.store.iter().map(|s| s.key.clone()).collect(),
+        }
+    }
+}
+
+struct Snapshot {
+    files: Vec<(Tracked, Values)>,
+    store: HashMap<String, JsonValue>,
+}
+
+// Configs a mutation can write: each client's user config and, for project
+// operations, its project configs. Only the managed values are journaled, so undo
+// and rollback leave whatever else the client keeps in the file alone.
+fn tracked_files(project: Option<&Path>) -> Vec<Tracked> {
+    let mut files: Vec<Tracked> = Vec::new();
+    let scopes = match project {
+        Some(project) => vec![None, Some(project)],
+        None => vec![None],
+    };
+    for client in clients::CLIENTS {
+        for scope in &scopes {
+            let Ok(path) = client.config_path(*scope) else {
+                continue;
+            };
+            let pointers = client.managed_pointers();
+            match files.iter_mut().find(|t| t.path == path) {
+                Some(tracked) => {
+                    for pointer in pointers {
+                        if !tracked.pointers.contains(&pointer) {
+                            tracked.pointers.push(pointer);
+                        }
+                    }
+                }
+                None => files.push(Tracked { path, pointers }),
+            }
+        }
+    }
+    files
+}
+
+fn read_values(tracked: &Tracked) -> Result<Values, String> {
+    let content = config_io::read_config(&tracked.path)?.content;
+    let document = match content.as_deref() {
+        Some(content) => clients::parse_document(content)?,
+        None => json!({}),
+    };
+    Ok(tracked
+        .pointers
+        .iter()
+        .map(|pointer| document.pointer(pointer).cloned())
+        .collect())
+}
+
+// Configs that cannot be parsed are left out; no operation can edit them either
+fn snapshot(app: &AppHandle, files: &[Tracked]) -> Result<Snapshot, String> {
+    Ok(Snapshot {
+        files: files
+            .iter()
+            .filter_map(|tracked| {
+                read_values(tracked)
+                    .ok()
+                    .map(|values| (tracked.clone(), values))
+            })
+            .collect(),
+        store: crate::store::store_entries(app)?,
+    })
+}
+
+fn diff(label: String, before: Snapshot, after: Snapshot) -> JournalEntry {
+    let files = before
+        .files
+        .into_iter()
+        .filter_map(|(tracked, before)| {
+            let (_, after) = after.files.iter().find(|(t, _)| t.path == tracked.path)?;
+            (&before != after).then(|| Change {
+                key: tracked,
+                before,
+                after: after.clone(),
+            })
+        })
+        .collect();
+
+    let mut keys: Vec<&String> = before.store.keys().chain(after.store.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    let store = keys
+        .into_iter()
+        .filter(|key| before.store.get(*key) != after.store.get(*key))
+        .map(|key| Change {
+            key: key.clone(),
+            before: before.store.get(key).cloned(),
+            after: after.store.get(key).cloned(),
+        })
+        .collect();
+
+    JournalEntry {
+        id: 0,
+        label,
+        time: now_ms(),
+        files,
+        store,
+    }
+}
+
+// Put the managed values of a config back, editing the file in place. Values that
+// should not exist are removed.
+fn write_values(tracked: &Tracked, values: &Values) -> Result<(), String> {
+    let current = config_io::read_config(&tracked.path)?;
+    let original = current
+        .content
+        .as_deref()
+        .filter(|content| !content.trim().is_empty());
+    let mut document = match original {
+        Some(content) => clients::parse_document(content)?,
+        None => json!({}),
+    };
+    let unchanged = document.clone();
+    for (pointer, value) in tracked.pointers.iter().zip(values) {
+        match value {
+            Some(value) => clients::set_pointer(&mut document, pointer, value.clone())?,
+            None => clients::remove_pointer(&mut document, pointer),
+        }
+    }
+    if document == unchanged {
+        return Ok(());
+    }
+
+    let content = match original {
+        Some(original) => json_edit::edit_over(original, &document)?,
+        None => json_edit::to_string_styled(&document, &JsonStyle::default())?,
+    };
+    config_io::write_config(&tracked.path, &content, &current.version).map(|_| ())
+}
+
+// Move files and store keys to the given states. When a write fails, the ones
+// already written are put back.
+fn apply_states(
+    app: &AppHandle,
+    files: Vec<(Tracked, Values)>,
+    store: Vec<(String, Option<JsonValue>)>,
+) -> Result<(), String> {
+    let tracked: Vec<Tracked> = files.iter().map(|(tracked, _)| tracked.clone()).collect();
+    let previous = snapshot(app, &tracked)?;
+
+    let result = files
+        .iter()
+        .try_for_each(|(tracked, values)| write_values(tracked, values))
+        .and_then(|()| crate::store::restore_store_entries(app, &store));
+    if let Err(e) = result {
+        for (tracked, values) in &previous.files {
+            let _ = write_values(tracked, values);
+        }
+        let keys: Vec<(String, Option<JsonValue>)> = store
+            .into_iter()
+            .map(|(key, _)| {
+                let value = previous.store.get(&key).cloned();
+                (key, value)
+            })
+            .collect();
+        let _ = crate::store::restore_store_entries(app, &keys);
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+// Run a mutation of config files and the store as one transaction: if it fails,
+// everything it changed is put back; if it succeeds, it is journaled for undo.
+// In config files only the managed values are put back, and side effects such as
+// stopped servers are not reverted.
+pub async fn run<T>(
+    app: &AppHandle,
+    label: impl Into<String>,
+    project: Option<&Path>,
+    op: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    let _active = ACTIVE.lock().await;
+    let files = tracked_files(project);
+    let before = snapshot(app, &files)?;
+
+    let result = op.await;
+    let after = snapshot(app, &files);
+
+    let value = match result {
+        Ok(value) => value,
+        Err(e) => {
+            // Keys the failed operation created are deleted again
+            let mut store: HashMap<String, Option<JsonValue>> = after
+                .map(|after| after.store.into_keys().map(|key| (key, None)).collect())
+                .unwrap_or_default();
+            store.extend(
+                before
+                    .store
+                    .into_iter()
+                    .map(|(key, value)| (key, Some(value))),
+            );
+            return match apply_states(app, before.files, store.into_iter().collect()) {
+                Ok(()) => Err(e),
+                Err(rollback) => Err(format!("{}; rollback failed: {}", e, rollback)),
+            };
+        }
+    };
+
+    let mut entry = diff(label.into(), before, after?);
+    if entry.files.is_empty() && entry.store.is_empty() {
+        return Ok(value);
+    }
+    if let Ok(mut journal) = JOURNAL.lock() {
+        journal.next_id += 1;
+        entry.id = journal.next_id;
+        journal.undo.push(entry);
+        if journal.undo.len() > JOURNAL_LIMIT {
+            journal.undo.remove(0);
+        }
+        journal.redo.clear();
+    }
+    Ok(value)
+}
+
+// Move a journaled transaction's files and keys from one side to the other, refusing
+// when something was changed since
+fn replay(app: &AppHandle, entry: &JournalEntry, undo: bool) -> Result<(), String> {
+    let mut files = Vec::new();
+    for change in &entry.files {
+        let (expected, target) = if undo {
+            (&change.after, &change.before)
+        } else {
+            (&change.before, &change.after)
+        };
+        if &read_values(&change.key)? != expected {
+            return Err(format!(
+                "Servers in {} were changed since \"{}\"",
+                change.key.path.display(),
+                entry.label
+            ));
+        }
+        files.push((change.key.clone(), target.clone()));
+    }
+
+    let current = crate::store::store_entries(app)?;
+    let mut store = Vec::new();
+    for change in &entry.store {
+        let (expected, target) = if undo {
+            (&change.after, &change.before)
+        } else {
+            (&change.before, &change.after)
+        };
+        if current.get(&change.key) != expected.as_ref() {
+            return Err(format!(
+                "Stored {} was changed since \"{}\"",
+                change.key, entry.label
+            ));
+        }
+        store.push((change.key.clone(), target.clone()));
+    }
+
+    apply_states(app, files, store)
+}
+
+fn journal_state() -> JournalState {
+    let journal = JOURNAL.lock();
+    let (undo, redo) = match &journal {
+        Ok(journal) => (&journal.undo[..], &journal.redo[..]),
+        Err(_) => (&[][..], &[][..]),
+    };
+    JournalState {
+        undo: undo.iter().rev().map(JournalEntry::summary).collect(),
+        redo: redo.iter().rev().map(JournalEntry::summary).collect(),
+    }
+}
+
+async fn step(app: AppHandle, undo: bool) -> Result<JournalState, String> {
+    let _active = ACTIVE.lock().await;
+    let entry = {
+        let journal = JOURNAL
+            .lock()
+            .map_err(|e| format!("Failed to lock journal: {}", e))?;
+        let stack = if undo { &journal.undo } else { &journal.redo };
+        stack
+            .last()
+            .cloned()
+            .ok_or_else(|| format!("Nothing to {}", if undo { "undo" } else { "redo" }))?
+    };
+
+    replay(&app, &entry, undo)?;
+
+    if let Ok(mut journal) = JOURNAL.lock() {
+        let journal = &mut *journal;
+        let (from, to) = if undo {
+            (&mut journal.undo, &mut journal.redo)
+        } else {
+            (&mut journal.redo, &mut journal.undo)
+        };
+        if let Some(entry) = from.pop() {
+            to.push(entry);
+        }
+    }
+    if let Err(e) = crate::tray::update_tray_status(app) {
+        eprintln!("Failed to update tray status: {}", e);
+    }
+    Ok(journal_state())
+}
+
+#[tauri::command]
+pub fn get_transaction_journal() -> JournalState {
+    journal_state()
+}
+
+#[tauri::command]
+pub async fn undo_transaction(app: AppHandle) -> Result<JournalState, String> {
+    step(app, true).await
+}
+
+#[tauri::command]
+pub async fn redo_transaction(app: AppHandle) -> Result<JournalState, String> {
+    step(app, false).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_file(name: &str, content: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("mcp-transaction-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json");
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn restoring_values_keeps_the_rest_of_the_file() {
+        let path = temp_file(
+            "restore",
+            "{\n  // other settings\n  \"theme\": \"dark\",\n  \"mcpServers\": {\n    \"a\": { \"command\": \"a\" }\n  }\n}\n",
+        );
+        let tracked = Tracked {
+            path: path.clone(),
+            pointers: vec!["/mcpServers", "/globalShortcut"],
+        };
+        let before = read_values(&tracked).unwrap();
+        assert_eq!(before[1], None);
+
+        // Another program changes an unmanaged setting while the servers change
+        let changed = fs::read_to_string(&path)
+            .unwrap()
+            .replace("dark", "light")
+            .replace("\"a\"", "\"b\"");
+        fs::write(&path, changed).unwrap();
+        write_values(&tracked, &before).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("// other settings"));
+        assert!(content.contains("\"theme\": \"light\""));
+        assert!(!content.contains("globalShortcut"));
+        assert_eq!(read_values(&tracked).unwrap(), before);
+    }
+
+    #[test]
+    fn absent_values_are_not_written() {
+        let path = temp_file("absent", "{}");
+        fs::remove_file(&path).unwrap();
+        let tracked = Tracked {
+            path: path.clone(),
+            pointers: vec!["/mcpServers"],
+        };
+        write_values(&tracked, &vec![None]).unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -10,26 +10,75 @@ use tauri::{
 fn create_status_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
     let mut menu_items = Vec::new();
 
+    // 显示当前激活的 profile
+    if let Some(profile) = crate::store::get_active_profiles(app)
+        .ok()
+        .and_then(|mut active| active.remove("claude"))
+    {
+        let profile_item = MenuItem::with_id(
+            app,
+            "active_profile",
+            format!("Profile: {}", profile),
+            false,
+            None::<&str>,
+        )?;
+        menu_items.push(profile_item);
+    }
+
     // 添加服务状态
-    if let Ok(config) = claude_config::get_claude_config() {
-        let mut services: Vec<_> = config.mcp_servers.keys().collect();
-        services.sort();
+    match claude_config::get_claude_config() {
+        Ok(config) => {
+            let mut services: Vec<_> = config.mcp_servers.keys().collect();
+            services.sort();
+
+            for name in services {
+                let status_icon = if mcp_runner::get_server_status(name) {
+                    "运行中"
+                } else {
+                    "已停止"
+                };
+
+                let service_item = MenuItem::with_id(
+                    app,
+                    format!("service_{}", name),
+                    format!("{} {}", name, status_icon),
+                    false, // 设为不可点击
+                    None::<&str>,
+                )?;
+                menu_items.push(service_item);
+            }
+        }
+        Err(_) => {
+            // 配置文件无法解析时提示用户
+            let error_item = MenuItem::with_id(
+                app,
+                "config_error",
+                "配置文件解析失败",
+                false,
+                None::<&str>,
+            )?;
+            menu_items.push(error_item);
+        }
+    }
 
-        for name in services {
-            let status_icon = if mcp_runner::get_server_status(name) {
-                "运行中"
-            } else {
-                "已停止"
-            };
+    // 已禁用的 server 保存在 store 中
+    if let Ok(disabled) = crate::store::get_disabled_servers(app) {
+        let mut names: Vec<_> = disabled
+            .iter()
+            .filter(|server| server.client == "claude" && server.project.is_none())
+            .map(|server| &server.name)
+            .collect();
+        names.sort();
 
-            let service_item = MenuItem::with_id(
+        for name in names {
+            let disabled_item = MenuItem::with_id(
                 app,
-                format!("service_{}", name),
-                format!("{} {}", name, status_icon),
-                false, // 设为不可点击
+                format!("disabled_{}", name),
+                format!("{} 已禁用", name),
+                false,
                 None::<&str>,
             )?;
-            menu_items.push(service_item);
+            menu_items.push(disabled_item);
         }
     }
 
@@ -0,0 +1,116 @@
+// Keyword and file path filtering and paging of template listings, shared by the app's
+// registry client and offline template cache and by the reference registry server
+
+// A template as far as filtering is concerned
+pub trait Listed {
+    fn name(&self) -> &str;
+    fn description(&self) -> &str;
+    fn require_file_path(&self) -> bool;
+}
+
+impl<T: Listed + ?Sized> Listed for &T {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn description(&self) -> &str {
+        (**self).description()
+    }
+
+    fn require_file_path(&self) -> bool {
+        (**self).require_file_path()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TemplateFilter {
+    keyword: Option<String>,
+    require_file_path: Option<bool>,
+}
+
+impl TemplateFilter {
+    // A blank keyword matches everything
+    pub fn new(keyword: Option<&str>, require_file_path: Option<bool>) -> Self {
+        TemplateFilter {
+            keyword: keyword
+                .map(|k| k.trim().to_lowercase())
+                .filter(|k| !k.is_empty()),
+            require_file_path,
+        }
+    }
+
+    // The keyword is matched case-insensitively against the name and description
+    pub fn matches<T: Listed>(&self, template: &T) -> bool {
+        self.require_file_path
+            .is_none_or(|required| template.require_file_path() == required)
+            && self.keyword.as_ref().is_none_or(|k| {
+                template.name().to_lowercase().contains(k)
+                    || template.description().to_lowercase().contains(k)
+            })
+    }
+
+    // The number of matching templates and the 1-based `page` of them
+    pub fn select<T: Listed>(
+        &self,
+        templates: impl IntoIterator<Item = T>,
+        page: usize,
+        page_size: usize,
+    ) -> (usize, Vec<T>) {
+        let matching: Vec<T> = templates.into_iter().filter(|t| self.matches(t)).collect();
+        let total = matching.len();
+        let list = matching
+            .into_iter()
+            .skip((page.max(1) - 1) * page_size)
+            .take(page_size)
+            .collect();
+        (total, list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Item(&'static str, &'static str, bool);
+
+    impl Listed for Item {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            self.1
+        }
+
+        fn require_file_path(&self) -> bool {
+            self.2
+        }
+    }
+
+    const ITEMS: &[Item] = &[
+        Item("git", "Repository tools", false),
+        Item("files", "Read local files", true),
+        Item("fetch", "Fetch web pages", false),
+    ];
+
+    fn names(list: &[&Item]) -> Vec<&'static str> {
+        list.iter().map(|item| item.0).collect()
+    }
+
+    #[test]
+    fn keyword_matches_name_or_description() {
+        let (total, list) = TemplateFilter::new(Some(" REPO "), None).select(ITEMS, 1, 10);
+        assert_eq!((total, names(&list)), (1, vec!["git"]));
+        let (total, _) = TemplateFilter::new(Some("  "), None).select(ITEMS, 1, 10);
+        assert_eq!(total, 3);
+    }
+
+    #[test]
+    fn pages_are_cut_after_filtering() {
+        let filter = TemplateFilter::new(None, Some(false));
+        assert_eq!(names(&filter.select(ITEMS, 0, 1).1), ["git"]);
+        let (total, list) = filter.select(ITEMS, 2, 1);
+        assert_eq!((total, names(&list)), (2, vec!["fetch"]));
+        assert!(filter.select(ITEMS, 3, 1).1.is_empty());
+    }
+}
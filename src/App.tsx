@@ -2,6 +2,7 @@ import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { BrowserRouter, Routes, Route } from "react-router-dom";
 import { Sidebar } from "./components/Sidebar";
+import { ClientRequestDialog } from "./components/ClientRequestDialog";
 import { StatusPage } from "./pages/Status";
 import { ConfigPage } from "./pages/Config";
 import { AllServersPage } from "./pages/AllServers";
@@ -9,13 +10,16 @@ import { Button } from "./components/ui/button";
 import { Card, CardContent } from "./components/ui/card";
 import { RotateCw } from "lucide-react";
 import { useEnvironmentCheck } from "./hooks/useEnvironmentCheck";
-import { useClaudeConfig } from "./hooks/useClaudeConfig";
+import { useClaudeConfig, useConfigChanges } from "./hooks/useClaudeConfig";
 import { useServerControl } from "./hooks/useServerControl";
 import {
+  ApiData,
   ClaudeConfig,
+  ConfigChangeEvent,
   ServerStatus,
   McpServerArgs,
   McpServerTemplate,
+  ParameterValue,
 } from "./types";
 import "./App.css";
 import { cn } from "./lib/utils";
@@ -31,6 +35,10 @@ function App() {
   const [selectedPath, setSelectedPath] = useState<Record<string, string>>({});
   const [isRefreshing, setIsRefreshing] = useState(false);
   const [, setMcpServers] = useState<Record<string, McpServerArgs>>({});
+  // Parse errors of config files edited outside the app, by path
+  const [configErrors, setConfigErrors] = useState<Record<string, string>>({});
+  const [templates, setTemplates] = useState<ApiData | null>(null);
+  const [envInputs, setEnvInputs] = useState<Record<string, string>>({});
 
   const checkMcpServers = useCallback(async () => {
     try {
@@ -45,17 +53,18 @@ function App() {
 
   const getAvailableServers = useCallback(async () => {
     try {
-      const templates = await invoke<McpServerTemplate[]>(
-        "get_mcp_server_templates"
-      );
-      await Promise.all(
-        templates.map(async (template: McpServerTemplate) => {
+      const data = await invoke<ApiData>("get_mcp_server_templates", {
+        params: { page: 1, page_size: 100 },
+      });
+      const list = await Promise.all(
+        data.list.map(async (template: McpServerTemplate) => {
           const installed = await invoke<boolean>("is_mcp_server_installed", {
             name: template.name,
           });
           return { ...template, installed };
         })
       );
+      setTemplates({ ...data, list });
     } catch (error) {
       console.error("Error getting MCP server templates:", error);
     }
@@ -73,13 +82,24 @@ function App() {
       setSelectedPath((prev: Record<string, string>) => ({...prev, [templateName]: selectedPath}));
     }
   );
-  const { uninstallServer, controlServer } = useServerControl(
+  const { installServer, uninstallServer, controlServer } = useServerControl(
     () => checkClaudeConfig().then(setClaudeConfig),
     checkMcpServers,
     getAvailableServers,
-    {}
+    envInputs
+  );
+
+  // A listing served from the offline cache installs the cached templates
+  const handleInstallServer = useCallback(
+    (template: McpServerTemplate, values: Record<string, ParameterValue>) =>
+      installServer(template, values, Boolean(templates?.stale_since)),
+    [installServer, templates]
   );
 
+  const handleEnvInput = useCallback((serverName: string, key: string, value: string) => {
+    setEnvInputs((prev) => ({ ...prev, [`${serverName}_${key}`]: value }));
+  }, []);
+
 
 
   const initializeApp = useCallback(async () => {
@@ -110,6 +130,23 @@ function App() {
     getAvailableServers,
   ]);
 
+  // Another program edited a config: show the new state, or why it can't be read
+  const handleConfigChange = useCallback(
+    async (event: ConfigChangeEvent) => {
+      setConfigErrors((prev) => {
+        const next = { ...prev };
+        delete next[event.path];
+        return event.error ? { ...next, [event.path]: event.error } : next;
+      });
+      if (event.error) return;
+      setClaudeConfig(await checkClaudeConfig());
+      await Promise.all([checkMcpServers(), getAvailableServers()]);
+    },
+    [checkClaudeConfig, checkMcpServers, getAvailableServers]
+  );
+
+  useConfigChanges(handleConfigChange);
+
   const updateServerStatus = useCallback(async () => {
     if (!claudeConfig) return;
 
@@ -174,6 +211,14 @@ function App() {
       <div className="flex h-screen bg-background">
         <Sidebar />
         <main className="flex-1 overflow-y-auto p-8">
+          {Object.entries(configErrors).map(([path, error]) => (
+            <div
+              key={path}
+              className="mb-4 rounded-md border border-red-500 p-3 text-sm text-red-500"
+            >
+              {path}: {error}
+            </div>
+          ))}
           <Card>
             <CardContent>
               <Routes>
@@ -203,6 +248,10 @@ function App() {
                       onControlServer={controlServer}
                       onUninstallServer={uninstallServer}
                       onConfigChange={setClaudeConfig}
+                      templates={templates}
+                      envInputs={envInputs}
+                      onEnvInput={handleEnvInput}
+                      onInstallServer={handleInstallServer}
                     />
                   }
                 />
@@ -225,6 +274,7 @@ function App() {
               className={cn("h-5 w-5", isRefreshing && "animate-spin")}
             />
           </Button>
+          <ClientRequestDialog />
         </main>
       </div>
     </BrowserRouter>
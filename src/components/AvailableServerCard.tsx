@@ -1,7 +1,10 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "./ui/card";
 import { Button } from "./ui/button";
 import { Input } from "./ui/input";
-import { Download } from "lucide-react";
+import { Download, X } from "lucide-react";
+import type { ParameterValue, TemplateParameter } from "../types";
 
 interface AvailableServerCardProps {
   name: string;
@@ -12,9 +15,145 @@ interface AvailableServerCardProps {
   env?: Record<string, string>;
   selectedPath?: string;
   envInputs: Record<string, string>;
+  parameters?: TemplateParameter[];
   onSelectDirectory?: () => void;
   onEnvInput?: (key: string, value: string) => void;
-  onInstall: () => void;
+  onInstall: (values: Record<string, ParameterValue>) => void;
+}
+
+type Values = Record<string, ParameterValue | undefined>;
+
+const isFolder = (parameter: TemplateParameter) =>
+  parameter.type === "directory" || parameter.type === "path";
+
+const isEmpty = (value: ParameterValue | undefined) =>
+  value === undefined || value === "" || (Array.isArray(value) && value.length === 0);
+
+function initialValues(parameters: TemplateParameter[]): Values {
+  const values: Values = {};
+  for (const parameter of parameters) {
+    if (parameter.default !== undefined && parameter.default !== null) {
+      values[parameter.name] = parameter.default;
+    }
+  }
+  return values;
+}
+
+// Values as the install command takes them; typed lists are split on commas
+function toValues(parameters: TemplateParameter[], values: Values): Record<string, ParameterValue> {
+  const result: Record<string, ParameterValue> = {};
+  for (const parameter of parameters) {
+    let value = values[parameter.name];
+    if (parameter.multiple && typeof value === "string") {
+      value = value.split(",").map((v) => v.trim()).filter(Boolean);
+    }
+    if (!isEmpty(value)) {
+      result[parameter.name] = value as ParameterValue;
+    }
+  }
+  return result;
+}
+
+interface ParameterInputProps {
+  id: string;
+  parameter: TemplateParameter;
+  value: ParameterValue | undefined;
+  onChange: (value: ParameterValue | undefined) => void;
+  onPickFolder: () => void;
+}
+
+// One input per parameter, picked by its type
+function ParameterInput({ id, parameter, value, onChange, onPickFolder }: ParameterInputProps) {
+  if (isFolder(parameter)) {
+    if (parameter.multiple) {
+      const folders = Array.isArray(value) ? value : [];
+      return (
+        <div className="col-span-2 space-y-1">
+          {folders.map((folder) => (
+            <div key={folder} className="flex items-center gap-2">
+              <Input value={folder} readOnly />
+              <Button
+                variant="outline"
+                size="icon"
+                onClick={() => onChange(folders.filter((f) => f !== folder))}
+              >
+                <X className="h-4 w-4" />
+              </Button>
+            </div>
+          ))}
+          <Button variant="outline" onClick={onPickFolder}>
+            添加目录
+          </Button>
+        </div>
+      );
+    }
+    return (
+      <div className="col-span-2 flex space-x-2">
+        <Input id={id} value={String(value ?? "")} readOnly placeholder="点击选择目录" />
+        <Button variant="outline" onClick={onPickFolder}>
+          选择
+        </Button>
+      </div>
+    );
+  }
+
+  switch (parameter.type) {
+    case "boolean":
+      return (
+        <input
+          id={id}
+          type="checkbox"
+          className="justify-self-start"
+          checked={value === true || value === "true"}
+          onChange={(e) => onChange(e.target.checked)}
+        />
+      );
+    case "enum":
+      return (
+        <select
+          id={id}
+          className="col-span-2 h-9 rounded-md border border-input bg-transparent px-3 text-sm"
+          value={String(value ?? "")}
+          onChange={(e) => onChange(e.target.value || undefined)}
+        >
+          <option value="" />
+          {(parameter.choices ?? []).map((choice) => (
+            <option key={choice} value={choice}>
+              {choice}
+            </option>
+          ))}
+        </select>
+      );
+    case "number":
+    case "port":
+      return (
+        <Input
+          id={id}
+          className="col-span-2"
+          type="number"
+          min={parameter.type === "port" ? 1 : undefined}
+          max={parameter.type === "port" ? 65535 : undefined}
+          placeholder={parameter.description}
+          value={value === undefined ? "" : String(value)}
+          onChange={(e) => onChange(e.target.value === "" ? undefined : Number(e.target.value))}
+        />
+      );
+    default:
+      return (
+        <Input
+          id={id}
+          className="col-span-2"
+          type={parameter.secret ? "password" : parameter.type === "url" ? "url" : "text"}
+          placeholder={
+            parameter.multiple
+              ? `${parameter.description ?? ""} (逗号分隔)`.trim()
+              : parameter.description
+          }
+          value={Array.isArray(value) ? value.join(", ") : String(value ?? "")}
+          onChange={(e) => onChange(e.target.value)}
+        />
+      );
+  }
 }
 
 export function AvailableServerCard({
@@ -26,10 +165,42 @@ export function AvailableServerCard({
   env,
   selectedPath,
   envInputs,
+  parameters = [],
   onSelectDirectory,
   onEnvInput,
   onInstall,
 }: AvailableServerCardProps) {
+  const [values, setValues] = useState<Values>(() => initialValues(parameters));
+
+  const setValue = (key: string, value: ParameterValue | undefined) =>
+    setValues((prev) => ({ ...prev, [key]: value }));
+
+  // Folders picked for a `multiple` parameter are added to its list
+  const pickFolder = async (parameter: TemplateParameter) => {
+    try {
+      const folder = await invoke<string | null>("select_folder", { serverName: name });
+      if (!folder) {
+        return;
+      }
+      if (parameter.multiple) {
+        const current = values[parameter.name];
+        const folders = Array.isArray(current) ? current : [];
+        setValue(parameter.name, folders.includes(folder) ? folders : [...folders, folder]);
+      } else {
+        setValue(parameter.name, folder);
+      }
+    } catch (error) {
+      console.error("Failed to select folder:", error);
+    }
+  };
+
+  const missingParameter = parameters.some(
+    (parameter) =>
+      parameter.required &&
+      (parameter.default === undefined || parameter.default === null) &&
+      isEmpty(values[parameter.name])
+  );
+
   return (
     <Card className="h-full flex flex-col">
       <CardHeader className="flex-none">
@@ -57,7 +228,32 @@ export function AvailableServerCard({
             </div>
           </div>
 
-          {name === "filesystem" && (
+          {parameters.length > 0 && (
+            <div className="mt-4 space-y-2">
+              <p className="text-sm font-medium">参数:</p>
+              {parameters.map((parameter) => (
+                <div key={parameter.name} className="grid grid-cols-3 gap-2 items-center">
+                  <label
+                    htmlFor={`${name}_param_${parameter.name}`}
+                    className="text-sm truncate"
+                    title={parameter.description}
+                  >
+                    {parameter.name}
+                    {parameter.required && " *"}
+                  </label>
+                  <ParameterInput
+                    id={`${name}_param_${parameter.name}`}
+                    parameter={parameter}
+                    value={values[parameter.name]}
+                    onChange={(value) => setValue(parameter.name, value)}
+                    onPickFolder={() => pickFolder(parameter)}
+                  />
+                </div>
+              ))}
+            </div>
+          )}
+
+          {parameters.length === 0 && name === "filesystem" && (
             <div className="mt-4">
               <p className="text-sm font-medium mb-2">选择目录:</p>
               <div className="flex space-x-2">
@@ -96,8 +292,8 @@ export function AvailableServerCard({
 
         <Button
           className="w-full mt-6"
-          onClick={onInstall}
-          disabled={installed || (
+          onClick={() => onInstall(toValues(parameters, values))}
+          disabled={installed || missingParameter || (
             env &&
             Object.keys(env).some(
               (key) => !envInputs[`${name}_${key}`]
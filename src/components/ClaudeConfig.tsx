@@ -4,7 +4,7 @@ import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { InstalledMcpServer } from "../types";
+import { DisabledServer, InstalledMcpServer, JournalState } from "../types";
 
 interface ClaudeConfigProps {
   claudeConfig: ClaudeConfig;
@@ -13,6 +13,7 @@ interface ClaudeConfigProps {
   onSelectDirectory: (name: string) => void;
   onControlServer: (name: string, action: "start" | "stop") => void;
   onUninstallServer: (name: string) => void;
+  onServersChanged?: () => void;
 }
 
 export function ClaudeConfig({
@@ -22,10 +23,53 @@ export function ClaudeConfig({
   onSelectDirectory,
   onControlServer,
   onUninstallServer,
+  onServersChanged,
 }: ClaudeConfigProps) {
   const [serverConfigs, setServerConfigs] = useState<Record<string, InstalledMcpServer>>({});
   const [envInputs, setEnvInputs] = useState<Record<string, string>>({});
   const [localServerStatus, setLocalServerStatus] = useState<ServerStatus>(initialServerStatus);
+  const [disabledServers, setDisabledServers] = useState<DisabledServer[]>([]);
+  const [journal, setJournal] = useState<JournalState>({ undo: [], redo: [] });
+
+  const loadDisabledServers = async () => {
+    try {
+      const servers = await invoke<DisabledServer[]>("list_disabled_servers");
+      setDisabledServers(
+        servers
+          .filter((server) => server.client === "claude" && !server.project)
+          .sort((a, b) => a.name.localeCompare(b.name))
+      );
+    } catch (error) {
+      console.error("Failed to load disabled servers:", error);
+    }
+  };
+
+  useEffect(() => {
+    loadDisabledServers();
+    invoke<JournalState>("get_transaction_journal")
+      .then(setJournal)
+      .catch((error) => console.error("Failed to load journal:", error));
+  }, [claudeConfig]);
+
+  const handleUndoRedo = async (undo: boolean) => {
+    try {
+      setJournal(await invoke<JournalState>(undo ? "undo_transaction" : "redo_transaction"));
+      await loadDisabledServers();
+      onServersChanged?.();
+    } catch (error) {
+      console.error(`Failed to ${undo ? "undo" : "redo"}:`, error);
+    }
+  };
+
+  const handleToggleServer = async (name: string, enable: boolean) => {
+    try {
+      await invoke(enable ? "enable_mcp_server" : "disable_mcp_server", { name });
+      await loadDisabledServers();
+      onServersChanged?.();
+    } catch (error) {
+      console.error(`Failed to ${enable ? "enable" : "disable"} server:`, error);
+    }
+  };
 
   // 先声明 sortedServers
   const sortedServers = Object.entries(claudeConfig.mcpServers).sort(
@@ -141,7 +185,27 @@ export function ClaudeConfig({
 
   return (
     <div className="space-y-4">
-      <h2 className="text-lg font-semibold">MCP Servers</h2>
+      <div className="flex items-center justify-between gap-2">
+        <h2 className="text-lg font-semibold">MCP Servers</h2>
+        <div className="flex gap-2">
+          <Button
+            variant="outline"
+            disabled={journal.undo.length === 0}
+            title={journal.undo[0]?.label}
+            onClick={() => handleUndoRedo(true)}
+          >
+            Undo
+          </Button>
+          <Button
+            variant="outline"
+            disabled={journal.redo.length === 0}
+            title={journal.redo[0]?.label}
+            onClick={() => handleUndoRedo(false)}
+          >
+            Redo
+          </Button>
+        </div>
+      </div>
       <div className="space-y-4">
         {sortedServers.map(([name, config]) => {
           const serverConfig = serverConfigs[name];
@@ -167,6 +231,13 @@ export function ClaudeConfig({
                       >
                         {localServerStatus[name] ? "Stop" : "Start"}
                       </Button>
+                      <Button
+                        variant="outline"
+                        onClick={() => handleToggleServer(name, false)}
+                        className="w-full sm:w-24"
+                      >
+                        Disable
+                      </Button>
                       <Button
                         variant="outline"
                         onClick={() => onUninstallServer(name)}
@@ -246,6 +317,33 @@ export function ClaudeConfig({
           );
         })}
       </div>
+
+      {/* 已禁用的服务器 */}
+      {disabledServers.length > 0 && (
+        <div className="space-y-4">
+          <h2 className="text-lg font-semibold">Disabled Servers</h2>
+          {disabledServers.map((server) => (
+            <Card key={server.name}>
+              <CardContent className="p-6">
+                <div className="flex flex-col sm:flex-row sm:items-center justify-between gap-4">
+                  <div className="flex-1 min-w-0">
+                    <h3 className="text-lg font-semibold truncate text-muted-foreground">
+                      {server.name}
+                    </h3>
+                  </div>
+                  <Button
+                    variant="default"
+                    onClick={() => handleToggleServer(server.name, true)}
+                    className="w-full sm:w-24"
+                  >
+                    Enable
+                  </Button>
+                </div>
+              </CardContent>
+            </Card>
+          ))}
+        </div>
+      )}
     </div>
   );
 }
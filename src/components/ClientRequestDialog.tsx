@@ -0,0 +1,186 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "./ui/card";
+import { Button } from "./ui/button";
+import { Input } from "./ui/input";
+import type { ClientRequest, ElicitationProperty } from "../types";
+
+const PROMPT_EVENT = "mcp_client_request";
+const ELICITATION_METHOD = "elicitation/create";
+
+async function respond(request: ClientRequest, result: unknown, error?: string) {
+  try {
+    await invoke("respond_client_request", {
+      requestId: request.request_id,
+      result: error ? null : result,
+      error: error ?? null,
+    });
+  } catch (e) {
+    // The server gave up waiting
+    console.error("Error answering client request:", e);
+  }
+}
+
+// Text of the conversation a server asks the user to continue
+function samplingText(params: Record<string, unknown>): string {
+  const messages = (params.messages as { role?: string; content?: { text?: string } }[]) ?? [];
+  const lines = messages.map((m) => `${m.role ?? "user"}: ${m.content?.text ?? ""}`);
+  if (typeof params.systemPrompt === "string") {
+    lines.unshift(`system: ${params.systemPrompt}`);
+  }
+  return lines.join("\n\n");
+}
+
+function toValue(property: ElicitationProperty, raw: string): unknown {
+  switch (property.type) {
+    case "number":
+    case "integer":
+      return raw === "" ? undefined : Number(raw);
+    case "boolean":
+      return raw === "true";
+    default:
+      return raw === "" ? undefined : raw;
+  }
+}
+
+// Answers sampling and elicitation requests of servers whose callback mode is "prompt",
+// one at a time in the order they arrive
+export function ClientRequestDialog() {
+  const [queue, setQueue] = useState<ClientRequest[]>([]);
+  const [reply, setReply] = useState("");
+  const [fields, setFields] = useState<Record<string, string>>({});
+
+  useEffect(() => {
+    const unlisten = listen<ClientRequest>(PROMPT_EVENT, (event) => {
+      setQueue((prev) => [...prev, event.payload]);
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  const request = queue[0];
+  if (!request) {
+    return null;
+  }
+
+  const done = () => {
+    setQueue((prev) => prev.slice(1));
+    setReply("");
+    setFields({});
+  };
+
+  const isElicitation = request.method === ELICITATION_METHOD;
+  const properties: Record<string, ElicitationProperty> =
+    ((request.params.requestedSchema as { properties?: Record<string, ElicitationProperty> })
+      ?.properties) ?? {};
+  const required: string[] =
+    ((request.params.requestedSchema as { required?: string[] })?.required) ?? [];
+  const missing = required.some((key) => !fields[key]);
+
+  const accept = async () => {
+    if (isElicitation) {
+      const content: Record<string, unknown> = {};
+      for (const [key, property] of Object.entries(properties)) {
+        const value = toValue(property, fields[key] ?? "");
+        if (value !== undefined) {
+          content[key] = value;
+        }
+      }
+      await respond(request, { action: "accept", content });
+    } else {
+      await respond(request, {
+        role: "assistant",
+        content: { type: "text", text: reply },
+        model: "mcp-manager",
+        stopReason: "endTurn",
+      });
+    }
+    done();
+  };
+
+  const decline = async () => {
+    if (isElicitation) {
+      await respond(request, { action: "decline" });
+    } else {
+      await respond(request, null, "Request rejected by the user");
+    }
+    done();
+  };
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50">
+      <Card className="w-full max-w-lg">
+        <CardHeader>
+          <CardTitle>{isElicitation ? "Input requested" : "Completion requested"}</CardTitle>
+          <CardDescription>
+            {request.server} · {request.method}
+            {queue.length > 1 && ` · ${queue.length - 1} more waiting`}
+          </CardDescription>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          {isElicitation ? (
+            <>
+              <p className="text-sm whitespace-pre-wrap">
+                {String(request.params.message ?? "")}
+              </p>
+              {Object.entries(properties).map(([key, property]) => (
+                <div key={key} className="grid grid-cols-3 gap-2 items-center">
+                  <label htmlFor={`elicitation_${key}`} className="text-sm truncate">
+                    {property.title ?? key}
+                    {required.includes(key) && " *"}
+                  </label>
+                  {property.enum || property.type === "boolean" ? (
+                    <select
+                      id={`elicitation_${key}`}
+                      className="col-span-2 h-9 rounded-md border border-input bg-transparent px-3 text-sm"
+                      value={fields[key] ?? ""}
+                      onChange={(e) => setFields((prev) => ({ ...prev, [key]: e.target.value }))}
+                    >
+                      <option value="" />
+                      {(property.enum ?? ["true", "false"]).map((choice) => (
+                        <option key={choice} value={choice}>
+                          {choice}
+                        </option>
+                      ))}
+                    </select>
+                  ) : (
+                    <Input
+                      id={`elicitation_${key}`}
+                      className="col-span-2"
+                      type={property.type === "number" || property.type === "integer" ? "number" : "text"}
+                      placeholder={property.description}
+                      value={fields[key] ?? ""}
+                      onChange={(e) => setFields((prev) => ({ ...prev, [key]: e.target.value }))}
+                    />
+                  )}
+                </div>
+              ))}
+            </>
+          ) : (
+            <>
+              <pre className="max-h-64 overflow-y-auto rounded-md bg-muted p-3 text-xs whitespace-pre-wrap">
+                {samplingText(request.params)}
+              </pre>
+              <textarea
+                className="w-full min-h-24 rounded-md border border-input bg-transparent px-3 py-2 text-sm"
+                placeholder="Reply sent back to the server"
+                value={reply}
+                onChange={(e) => setReply(e.target.value)}
+              />
+            </>
+          )}
+          <div className="flex justify-end gap-2">
+            <Button variant="outline" onClick={decline}>
+              {isElicitation ? "Decline" : "Reject"}
+            </Button>
+            <Button onClick={accept} disabled={isElicitation ? missing : !reply}>
+              {isElicitation ? "Submit" : "Send"}
+            </Button>
+          </div>
+        </CardContent>
+      </Card>
+    </div>
+  );
+}
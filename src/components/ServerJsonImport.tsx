@@ -0,0 +1,198 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { open } from "@tauri-apps/plugin-dialog";
+import { FolderOpen } from "lucide-react";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "./ui/card";
+import { Button } from "./ui/button";
+import { Input } from "./ui/input";
+import type { InputSpec, ServerJsonCheck } from "../types";
+
+function initialValues(inputs: InputSpec[]): Record<string, string> {
+  return Object.fromEntries(inputs.map((input) => [input.key, input.default ?? ""]));
+}
+
+function InputField({
+  input,
+  value,
+  onChange,
+}: {
+  input: InputSpec;
+  value: string;
+  onChange: (value: string) => void;
+}) {
+  const label = `${input.key}${input.required ? " *" : ""}`;
+  if (input.choices.length > 0) {
+    return (
+      <label className="block space-y-1 text-sm">
+        <span>{label}</span>
+        <select
+          className="w-full rounded-md border px-2 py-1"
+          value={value}
+          onChange={(e) => onChange(e.target.value)}
+        >
+          <option value="" />
+          {input.choices.map((choice) => (
+            <option key={choice} value={choice}>
+              {choice}
+            </option>
+          ))}
+        </select>
+      </label>
+    );
+  }
+  return (
+    <label className="block space-y-1 text-sm">
+      <span>{label}</span>
+      <Input
+        type={input.secret ? "password" : input.format === "number" ? "number" : "text"}
+        placeholder={input.description}
+        value={value}
+        onChange={(e) => onChange(e.target.value)}
+      />
+    </label>
+  );
+}
+
+// Installs a server described by a local server.json file, in the variant the user picks
+export function ServerJsonImport() {
+  const [path, setPath] = useState("");
+  const [servers, setServers] = useState<ServerJsonCheck[]>([]);
+  const [serverIndex, setServerIndex] = useState(0);
+  const [variantId, setVariantId] = useState<string | null>(null);
+  const [values, setValues] = useState<Record<string, string>>({});
+  const [installing, setInstalling] = useState(false);
+  const [message, setMessage] = useState("");
+
+  const selectServer = (checks: ServerJsonCheck[], index: number) => {
+    const server = checks[index];
+    const variant = server?.variants.find((v) => v.id === server.selected);
+    setServerIndex(index);
+    setVariantId(server?.selected ?? null);
+    setValues(initialValues(variant?.inputs ?? []));
+  };
+
+  const load = async (file: string) => {
+    setPath(file);
+    setMessage("");
+    try {
+      const checks = await invoke<ServerJsonCheck[]>("read_server_json", { path: file });
+      setServers(checks);
+      selectServer(checks, 0);
+    } catch (error) {
+      console.error("Failed to read server.json:", error);
+      setServers([]);
+      setMessage(String(error));
+    }
+  };
+
+  const browse = async () => {
+    const selected = await open({ filters: [{ name: "server.json", extensions: ["json"] }] });
+    if (typeof selected === "string") {
+      await load(selected);
+    }
+  };
+
+  const server = servers[serverIndex];
+  const variant = server?.variants.find((v) => v.id === variantId);
+  const missing = variant?.inputs.some((input) => input.required && !values[input.key]);
+
+  const install = async () => {
+    if (!server || !variant) return;
+    try {
+      setInstalling(true);
+      await invoke("install_server_json", {
+        path,
+        server: server.name,
+        variant: variant.id,
+        values,
+      });
+      setMessage(`Installed ${server.install_name}`);
+    } catch (error) {
+      console.error("Failed to install from server.json:", error);
+      setMessage(String(error));
+    } finally {
+      setInstalling(false);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader className="pb-3">
+        <CardTitle>Install from server.json</CardTitle>
+        <CardDescription>
+          Install a server described in the MCP registry's server.json format
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <div className="flex items-center gap-2">
+          <Input
+            placeholder="/path/to/server.json"
+            value={path}
+            onChange={(e) => setPath(e.target.value)}
+            onKeyDown={(e) => e.key === "Enter" && load(path)}
+          />
+          <Button variant="outline" size="icon" onClick={browse}>
+            <FolderOpen className="h-4 w-4" />
+          </Button>
+          <Button variant="outline" onClick={() => load(path)} disabled={!path}>
+            Read
+          </Button>
+        </div>
+
+        {servers.length > 1 && (
+          <select
+            className="w-full rounded-md border px-2 py-1 text-sm"
+            value={serverIndex}
+            onChange={(e) => selectServer(servers, Number(e.target.value))}
+          >
+            {servers.map((s, index) => (
+              <option key={s.name} value={index}>
+                {s.name}
+              </option>
+            ))}
+          </select>
+        )}
+
+        {server && (
+          <div className="space-y-3">
+            <p className="text-sm text-muted-foreground">{server.description}</p>
+            <div className="space-y-1">
+              {server.variants.map((v) => (
+                <label key={v.id} className="flex items-center gap-2 text-sm">
+                  <input
+                    type="radio"
+                    checked={v.id === variantId}
+                    disabled={!v.available}
+                    onChange={() => {
+                      setVariantId(v.id);
+                      setValues(initialValues(v.inputs));
+                    }}
+                  />
+                  <span>
+                    {v.registry_type}: {v.identifier}
+                  </span>
+                  {v.reason && <span className="text-muted-foreground">({v.reason})</span>}
+                </label>
+              ))}
+            </div>
+            {variant?.inputs.map((input) => (
+              <InputField
+                key={input.key}
+                input={input}
+                value={values[input.key] ?? ""}
+                onChange={(value) => setValues((prev) => ({ ...prev, [input.key]: value }))}
+              />
+            ))}
+          </div>
+        )}
+
+        <div className="flex items-center gap-2">
+          <Button onClick={install} disabled={!variant || missing || installing}>
+            {installing ? "Installing..." : "Install"}
+          </Button>
+          {message && <span className="text-sm text-muted-foreground">{message}</span>}
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
@@ -1,17 +1,23 @@
 import { AvailableServerCard } from "./AvailableServerCard";
-import { McpServerTemplate, EnvInputs } from "../types";
+import { McpServerTemplate, EnvInputs, ParameterValue, RegistryError } from "../types";
 
 interface ServerListProps {
   availableServers: McpServerTemplate[];
+  // Registries that could not be queried; the others' templates are still listed
+  errors?: RegistryError[];
+  // Set when the listing came from the offline cache
+  staleSince?: number | null;
   selectedPath: Record<string, string>;
   envInputs: EnvInputs;
   onSelectDirectory: (name: string) => void;
   onEnvInput: (serverName: string, key: string, value: string) => void;
-  onInstallServer: (template: McpServerTemplate) => void;
+  onInstallServer: (template: McpServerTemplate, values: Record<string, ParameterValue>) => void;
 }
 
 export function ServerList({
   availableServers,
+  errors = [],
+  staleSince,
   selectedPath,
   envInputs,
   onSelectDirectory,
@@ -19,23 +25,36 @@ export function ServerList({
   onInstallServer,
 }: ServerListProps) {
   return (
-    <div className="grid gap-6 grid-cols-1 md:grid-cols-2 auto-rows-fr">
-      {availableServers.map((template) => (
-        <AvailableServerCard
-          key={template.name}
-          name={template.name}
-          description={template.description}
-          command={template.command}
-          args={template.args}
-          installed={template.installed || false}
-          env={template.env}
-          selectedPath={selectedPath[template.name]}
-          envInputs={envInputs}
-          onSelectDirectory={() => onSelectDirectory(template.name)}
-          onEnvInput={(key, value) => onEnvInput(template.name, key, value)}
-          onInstall={() => onInstallServer(template)}
-        />
+    <div className="space-y-4">
+      {staleSince && (
+        <p className="text-sm text-muted-foreground">
+          Offline: showing templates cached on {new Date(staleSince).toLocaleString()}
+        </p>
+      )}
+      {errors.map(({ registry, error }) => (
+        <div key={registry} className="rounded-md border border-red-500 p-3 text-sm text-red-500">
+          {registry}: {error}
+        </div>
       ))}
+      <div className="grid gap-6 grid-cols-1 md:grid-cols-2 auto-rows-fr">
+        {availableServers.map((template) => (
+          <AvailableServerCard
+            key={template.name}
+            name={template.name}
+            description={template.description}
+            command={template.command}
+            args={template.args}
+            installed={template.installed || false}
+            env={template.env}
+            selectedPath={selectedPath[template.name]}
+            envInputs={envInputs}
+            parameters={template.parameters}
+            onSelectDirectory={() => onSelectDirectory(template.name)}
+            onEnvInput={(key, value) => onEnvInput(template.name, key, value)}
+            onInstall={(values) => onInstallServer(template, values)}
+          />
+        ))}
+      </div>
     </div>
   );
 }
@@ -0,0 +1,162 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Plus, Trash2 } from "lucide-react";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "./ui/card";
+import { Button } from "./ui/button";
+import { Input } from "./ui/input";
+import type { TemplateRegistry } from "../types";
+
+function newRegistry(index: number): TemplateRegistry {
+  return {
+    id: `registry-${index}`,
+    name: "",
+    url: "https://",
+    auth: null,
+    priority: 0,
+    enabled: true,
+    timeout_ms: 5000,
+  };
+}
+
+// Edits the registries templates are listed from. Stored auth values come back masked
+// and are kept as they are unless replaced.
+export function TemplateRegistries() {
+  const [registries, setRegistries] = useState<TemplateRegistry[]>([]);
+  const [saving, setSaving] = useState(false);
+  const [message, setMessage] = useState("");
+
+  const load = async () => {
+    try {
+      setRegistries(await invoke<TemplateRegistry[]>("list_template_registries"));
+    } catch (error) {
+      console.error("Failed to load template registries:", error);
+      setMessage(String(error));
+    }
+  };
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  const update = (index: number, changes: Partial<TemplateRegistry>) => {
+    setRegistries((prev) => prev.map((r, i) => (i === index ? { ...r, ...changes } : r)));
+    setMessage("");
+  };
+
+  const updateAuth = (index: number, field: "name" | "value", value: string) => {
+    const auth = registries[index].auth ?? { name: "Authorization", value: "" };
+    const next = { ...auth, [field]: value };
+    update(index, { auth: next.name || next.value ? next : null });
+  };
+
+  const save = async () => {
+    try {
+      setSaving(true);
+      await invoke("save_template_registries", { registries });
+      await load();
+      setMessage("Saved");
+    } catch (error) {
+      console.error("Failed to save template registries:", error);
+      setMessage(String(error));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader className="pb-3">
+        <CardTitle>Template Registries</CardTitle>
+        <CardDescription>
+          Templates are listed from every enabled registry; higher priority wins for duplicates
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {registries.map((registry, index) => (
+          <div key={index} className="space-y-2 rounded-md border p-3">
+            <div className="grid grid-cols-2 gap-2">
+              <Input
+                placeholder="Id"
+                value={registry.id}
+                onChange={(e) => update(index, { id: e.target.value })}
+              />
+              <Input
+                placeholder="Name"
+                value={registry.name}
+                onChange={(e) => update(index, { name: e.target.value })}
+              />
+            </div>
+            <Input
+              placeholder="https://registry.example.com/api/v1/templates"
+              value={registry.url}
+              onChange={(e) => update(index, { url: e.target.value })}
+            />
+            <div className="grid grid-cols-2 gap-2">
+              <Input
+                placeholder="Auth header, e.g. Authorization"
+                value={registry.auth?.name ?? ""}
+                onChange={(e) => updateAuth(index, "name", e.target.value)}
+              />
+              <Input
+                type="password"
+                placeholder="Auth value"
+                value={registry.auth?.value ?? ""}
+                onChange={(e) => updateAuth(index, "value", e.target.value)}
+              />
+            </div>
+            <div className="flex items-center gap-4 text-sm">
+              <label className="flex items-center gap-2">
+                Priority
+                <Input
+                  type="number"
+                  className="w-20"
+                  value={registry.priority}
+                  onChange={(e) => update(index, { priority: Number(e.target.value) || 0 })}
+                />
+              </label>
+              <label className="flex items-center gap-2">
+                Timeout (ms)
+                <Input
+                  type="number"
+                  className="w-24"
+                  min={1}
+                  value={registry.timeout_ms}
+                  onChange={(e) => update(index, { timeout_ms: Number(e.target.value) || 0 })}
+                />
+              </label>
+              <label className="flex items-center gap-2">
+                <input
+                  type="checkbox"
+                  checked={registry.enabled}
+                  onChange={(e) => update(index, { enabled: e.target.checked })}
+                />
+                Enabled
+              </label>
+              <Button
+                variant="outline"
+                size="icon"
+                className="ml-auto"
+                onClick={() => setRegistries((prev) => prev.filter((_, i) => i !== index))}
+              >
+                <Trash2 className="h-4 w-4" />
+              </Button>
+            </div>
+          </div>
+        ))}
+        <div className="flex items-center gap-2">
+          <Button
+            variant="outline"
+            onClick={() => setRegistries((prev) => [...prev, newRegistry(prev.length + 1)])}
+          >
+            <Plus className="h-4 w-4" />
+            Add registry
+          </Button>
+          <Button onClick={save} disabled={saving}>
+            {saving ? "Saving..." : "Save"}
+          </Button>
+          {message && <span className="text-sm text-muted-foreground">{message}</span>}
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
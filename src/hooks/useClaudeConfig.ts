@@ -1,6 +1,21 @@
-import { useCallback } from 'react';
+import { useCallback, useEffect } from 'react';
 import { invoke } from "@tauri-apps/api/core";
-import { ClaudeConfig } from '../types';
+import { listen } from "@tauri-apps/api/event";
+import { ClaudeConfig, ConfigChangeEvent } from '../types';
+
+const CHANGE_EVENT = "config_changed";
+
+// Calls `onChange` whenever a client config is edited outside the app
+export function useConfigChanges(onChange: (event: ConfigChangeEvent) => void) {
+  useEffect(() => {
+    const unlisten = listen<ConfigChangeEvent>(CHANGE_EVENT, (event) => {
+      onChange(event.payload);
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, [onChange]);
+}
 
 export function useClaudeConfig(
   onSelectDirectory: (templateName: string, selectedPath: string) => void
@@ -26,7 +41,9 @@ export function useClaudeConfig(
 
   const selectDirectory = useCallback(async (templateName: string) => {
     try {
-      const selected = await invoke<string | null>("select_folder");
+      const selected = await invoke<string | null>("select_folder", {
+        serverName: templateName,
+      });
       if (selected) {
         onSelectDirectory(templateName, selected);
 
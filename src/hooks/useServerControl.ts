@@ -1,6 +1,45 @@
 import { useCallback } from 'react';
 import { invoke } from "@tauri-apps/api/core";
-import type { McpServerTemplate } from '../types';
+import { confirm, message } from "@tauri-apps/plugin-dialog";
+import type {
+  McpServerTemplate,
+  OperationPlan,
+  ParameterValue,
+  PreflightResult,
+} from '../types';
+
+function describePreflight(preflight: PreflightResult): string {
+  return preflight.issues
+    .map((issue) =>
+      issue.remediation_url ? `${issue.message} (${issue.remediation_url})` : issue.message
+    )
+    .join("\n");
+}
+
+// One line per affected config, store record and side effect
+function describePlan(plan: OperationPlan): string {
+  const lines = plan.configs.map((config) =>
+    config.error
+      ? `${config.config_path}: ${config.error}`
+      : `${config.config_path}: ${
+          config.changes.map((c) => `${c.kind} ${c.name}`).join(", ") || "no changes"
+        }`
+  );
+  lines.push(...plan.store.map((s) => `store: ${s.kind} ${s.name}`));
+  lines.push(
+    ...plan.side_effects.map((effect) =>
+      effect.kind === "stop_server"
+        ? `stop running server ${effect.name}`
+        : `${effect.runner} will download ${effect.package}`
+    )
+  );
+  return lines.join("\n");
+}
+
+async function confirmPlan(plan: OperationPlan, warnings = ""): Promise<boolean> {
+  const text = warnings ? `${warnings}\n\n${describePlan(plan)}` : describePlan(plan);
+  return confirm(text, { title: plan.operation, kind: "warning" });
+}
 
 export function useServerControl(
   onConfigUpdate: () => Promise<void>,
@@ -8,11 +47,28 @@ export function useServerControl(
   onTemplatesUpdate: () => Promise<void>,
   envInputs: Record<string, string>
 ) {
+  // `values` are the template's parameters as entered on its card. `cached` installs the
+  // copy from the offline cache, for listings shown while the registry is unreachable.
   const installServer = useCallback(
-    async (template: McpServerTemplate) => {
+    async (
+      template: McpServerTemplate,
+      values: Record<string, ParameterValue> = {},
+      cached = false
+    ) => {
       try {
-        if (template.name === "filesystem") {
-          const selectedPath = await invoke<string | null>("select_folder");
+        const preflight = await invoke<PreflightResult>("preflight_mcp_server", { template });
+        if (preflight.blocked) {
+          await message(describePreflight(preflight), {
+            title: `Cannot install ${template.name}`,
+            kind: "error",
+          });
+          return;
+        }
+
+        if (!template.parameters?.length && template.name === "filesystem") {
+          const selectedPath = await invoke<string | null>("select_folder", {
+            serverName: template.name,
+          });
           if (!selectedPath) {
             return;
           }
@@ -31,12 +87,27 @@ export function useServerControl(
           });
         }
 
-        await invoke("install_mcp_server", {
+        const request = {
           template: {
             ...template,
             env: template.env ? serverEnv : undefined,
           },
-        });
+          values,
+        };
+        const plan = await invoke<OperationPlan>("plan_install_mcp_server", request);
+        if (!(await confirmPlan(plan, describePreflight(preflight)))) {
+          return;
+        }
+        if (cached && template.source) {
+          await invoke("install_cached_template", {
+            registry: template.source,
+            id: template.id,
+            env: request.template.env,
+            values,
+          });
+        } else {
+          await invoke("install_mcp_server", request);
+        }
 
         await Promise.all([
           onConfigUpdate(),
@@ -56,6 +127,10 @@ export function useServerControl(
   const uninstallServer = useCallback(
     async (name: string) => {
       try {
+        const plan = await invoke<OperationPlan>("plan_uninstall_mcp_server", { name });
+        if (!(await confirmPlan(plan))) {
+          return;
+        }
         await invoke("uninstall_mcp_server", { name });
         await Promise.all([
           onConfigUpdate(),
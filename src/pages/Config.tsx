@@ -1,7 +1,16 @@
 import { HotkeyInput } from "@/components/HotkeyInput";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { ClaudeConfig as ClaudeConfigComponent } from "@/components/ClaudeConfig";
-import type { ClaudeConfig, ServerStatus } from "@/types";
+import { TemplateRegistries } from "@/components/TemplateRegistries";
+import { ServerJsonImport } from "@/components/ServerJsonImport";
+import { ServerList } from "@/components/ServerList";
+import type {
+  ApiData,
+  ClaudeConfig,
+  McpServerTemplate,
+  ParameterValue,
+  ServerStatus,
+} from "@/types";
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { useClaudeConfig } from "@/hooks/useClaudeConfig";
@@ -18,6 +27,10 @@ interface ConfigPageProps {
   onControlServer: (name: string, action: "start" | "stop") => void;
   onUninstallServer: (name: string) => void;
   onConfigChange: (config: ClaudeConfig | null) => void;
+  templates: ApiData | null;
+  envInputs: Record<string, string>;
+  onEnvInput: (serverName: string, key: string, value: string) => void;
+  onInstallServer: (template: McpServerTemplate, values: Record<string, ParameterValue>) => void;
 }
 
 export function ConfigPage({
@@ -29,6 +42,10 @@ export function ConfigPage({
   onControlServer,
   onUninstallServer,
   onConfigChange,
+  templates,
+  envInputs,
+  onEnvInput,
+  onInstallServer,
 }: ConfigPageProps) {
   const [isUpdating, setIsUpdating] = useState(false);
   const { checkClaudeConfig } = useClaudeConfig((templateName) => {
@@ -148,6 +165,30 @@ export function ConfigPage({
             </Card>
           </div>
 
+          <TemplateRegistries />
+
+          {templates && (
+            <Card>
+              <CardHeader className="pb-3">
+                <CardTitle>Server Templates</CardTitle>
+              </CardHeader>
+              <CardContent>
+                <ServerList
+                  availableServers={templates.list}
+                  errors={templates.errors}
+                  staleSince={templates.stale_since}
+                  selectedPath={selectedPath}
+                  envInputs={envInputs}
+                  onSelectDirectory={onSelectDirectory}
+                  onEnvInput={onEnvInput}
+                  onInstallServer={onInstallServer}
+                />
+              </CardContent>
+            </Card>
+          )}
+
+          <ServerJsonImport />
+
           {/* GitHub Copilot MCP Information */}
           <Card>
             <CardHeader className="pb-3">
@@ -184,6 +225,7 @@ export function ConfigPage({
                 onSelectDirectory={onSelectDirectory}
                 onControlServer={handleControlServer}
                 onUninstallServer={handleUninstall}
+                onServersChanged={refreshConfig}
               />
             </div>
           )}
@@ -6,7 +6,7 @@ export interface McpServerConfig {
 
 export interface ClaudeConfig {
   mcpServers: Record<string, McpServerConfig>;
-  globalShortcut: string;
+  globalShortcut?: string;
 }
 
 export interface McpServerArgs {
@@ -22,13 +22,88 @@ export interface McpServerTemplate {
   command: string;
   args: string[];
   env?: Record<string, string>;
+  // Legacy flag; newer templates declare their inputs in `parameters`
   require_file_path: boolean;
+  parameters?: TemplateParameter[];
   repo_url: string;
   created_at: string;
   updated_at: string;
   downloads: number;
   total_usage_time: number;
   installed?: boolean;
+  // Id of the registry the template came from
+  source?: string | null;
+}
+
+export type ParameterType =
+  | "string"
+  | "number"
+  | "boolean"
+  | "path"
+  | "directory"
+  | "url"
+  | "port"
+  | "enum";
+
+// Where a parameter's value goes; defaults to a `{{name}}` placeholder in args and env
+export type ParameterTarget =
+  | { kind: "placeholder" }
+  | { kind: "env"; name: string }
+  | { kind: "args" }
+  | { kind: "flag"; flag: string };
+
+export interface TemplateParameter {
+  name: string;
+  type?: ParameterType;
+  description?: string;
+  required?: boolean;
+  default?: string | number | boolean | string[] | null;
+  secret?: boolean;
+  multiple?: boolean;
+  choices?: string[];
+  target?: ParameterTarget;
+}
+
+export type ParameterValue = string | number | boolean | string[];
+
+export interface RegistryError {
+  registry: string;
+  error: string;
+}
+
+export interface ApiData {
+  total: number;
+  list: McpServerTemplate[];
+  errors: RegistryError[];
+  // Set when results came from the offline cache: when it was last confirmed, in ms
+  stale_since?: number | null;
+}
+
+export interface TemplateRegistry {
+  id: string;
+  name: string;
+  url: string;
+  auth: { name: string; value: string } | null;
+  // Higher priority wins when registries return the same template
+  priority: number;
+  enabled: boolean;
+  timeout_ms: number;
+}
+
+// A sampling or elicitation request a server sent, waiting for the user's answer
+export interface ClientRequest {
+  request_id: number;
+  server: string;
+  method: string;
+  params: Record<string, unknown>;
+}
+
+// One field of an elicitation's `requestedSchema`
+export interface ElicitationProperty {
+  type?: "string" | "number" | "integer" | "boolean";
+  title?: string;
+  description?: string;
+  enum?: string[];
 }
 
 export interface EnvInputs {
@@ -68,6 +143,77 @@ export interface InstalledMcpServer {
   repo_url: string;
 }
 
+export interface DisabledServer {
+  name: string;
+  client: string;
+  project: string | null;
+  entry: Record<string, unknown>;
+  position: number;
+  disabled_at: number;
+}
+
+// Emitted as `config_changed` when a watched client config changes on disk
+export interface ConfigChangeEvent {
+  client: string;
+  path: string;
+  added: string[];
+  removed: string[];
+  modified: string[];
+  // Set when the file no longer parses; the last good state is kept
+  error: string | null;
+}
+
+export interface FieldChange {
+  path: string;
+  before: unknown;
+  after: unknown;
+}
+
+export interface ServerChange {
+  name: string;
+  kind: "added" | "removed" | "modified";
+  changes: FieldChange[];
+}
+
+export interface ConfigPlan {
+  client: string;
+  config_path: string;
+  changes: ServerChange[];
+  error: string | null;
+}
+
+export interface StorePlan {
+  name: string;
+  kind: "added" | "removed" | "modified";
+  changes: FieldChange[];
+}
+
+export type SideEffect =
+  | { kind: "stop_server"; name: string }
+  | { kind: "fetch_package"; runner: string; package: string };
+
+// What an install, uninstall or update would change, without applying it
+export interface OperationPlan {
+  operation: string;
+  configs: ConfigPlan[];
+  store: StorePlan[];
+  side_effects: SideEffect[];
+}
+
+export interface TransactionSummary {
+  id: number;
+  label: string;
+  time: number;
+  files: string[];
+  store_keys: string[];
+}
+
+// Most recent first in both lists
+export interface JournalState {
+  undo: TransactionSummary[];
+  redo: TransactionSummary[];
+}
+
 export interface RunningMcpServer {
   name: string;
   pid: number;
@@ -81,4 +227,70 @@ export interface SystemMcpServers {
   running_servers: RunningMcpServer[];
   copilot_servers: RunningMcpServer[];
   claude_servers: RunningMcpServer[];
-}
\ No newline at end of file
+}
+
+// A value a server.json variant asks for before installing
+export interface InputSpec {
+  key: string;
+  description: string;
+  required: boolean;
+  secret: boolean;
+  format: string | null;
+  default: string | null;
+  choices: string[];
+}
+
+export interface ServerJsonVariant {
+  id: string; // "package-0", "remote-0", ...
+  registry_type: string;
+  identifier: string;
+  runtime: string | null;
+  available: boolean;
+  reason: string | null;
+  inputs: InputSpec[];
+}
+
+export interface ServerJsonCheck {
+  name: string;
+  install_name: string;
+  description: string;
+  variants: ServerJsonVariant[];
+  selected: string | null;
+}
+
+export interface PreflightIssue {
+  client: string | null;
+  severity: "error" | "warning";
+  message: string;
+  remediation_url: string | null;
+}
+
+// Whether the runtime a template's command needs is installed and new enough
+export interface PreflightResult {
+  command: string;
+  runtime: string;
+  path: string | null;
+  version: string | null;
+  min_version: string | null;
+  blocked: boolean;
+  issues: PreflightIssue[];
+}
+
+// The package a server's launcher fetches, as resolved when installed or pinned
+export interface LockEntry {
+  runner: string;
+  requested: string;
+  package: string;
+  version: string;
+  integrity: string | null;
+  resolved_at: number;
+}
+
+export interface LockCheck {
+  server: string;
+  package: string;
+  locked: LockEntry | null;
+  resolved: LockEntry | null;
+  status: "matches" | "changed" | "unlocked" | "failed";
+  error: string | null;
+}
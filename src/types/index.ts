@@ -5,7 +5,7 @@ export interface McpServerConfig {
 
 export interface ClaudeConfig {
   mcpServers: Record<string, McpServerConfig>;
-  globalShortcut: string;
+  globalShortcut?: string;
 }
 
 export interface McpServerArgs {